@@ -1,6 +1,14 @@
-use alloc::{string::String, vec};
+use crate::{
+    Calendar, CurrencyFormat, DateOrder, DateTimeSymbols, LocaleCategory, LocaleError,
+    LocaleSettings, LocaleSource, MeasurementSystem, NumberSeparators, PaperSize, PercentSymbol,
+    ProviderInfo, SignSymbols, Weekday,
+};
+use alloc::{borrow::Cow, string::String, vec, vec::Vec};
 use core::convert::TryFrom;
 
+#[cfg(feature = "android-jni")]
+mod java;
+
 fn get_property(name: &'static [u8]) -> Option<String> {
     let mut value = vec![0u8; libc::PROP_VALUE_MAX as usize];
     // SAFETY: `name` is valid to read from and `value` is valid to write to.
@@ -23,6 +31,9 @@ const PRODUCT_LOCALE_KEY: &[u8] = b"ro.product.locale\0";
 const PRODUCT_LANGUAGE_KEY: &[u8] = b"ro.product.locale.language\0";
 const PRODUCT_REGION_KEY: &[u8] = b"ro.product.locale.region\0";
 
+#[cfg(feature = "android-jni")]
+const SDK_KEY: &[u8] = b"ro.build.version.sdk\0";
+
 // Android 4.0 and below
 const LANG_KEY: &[u8] = b"persist.sys.language\0";
 const COUNTRY_KEY: &[u8] = b"persist.sys.country\0";
@@ -70,6 +81,383 @@ fn read_locale() -> Option<String> {
     }
 }
 
+pub(crate) const SUPPORTED: bool = true;
+
 pub(crate) fn get() -> impl Iterator<Item = String> {
-    read_locale().into_iter()
+    get_with_source().0
+}
+
+/// The locales are copied out of the system property or Java strings, so none can be borrowed.
+pub(crate) fn get_cow() -> impl Iterator<Item = Cow<'static, str>> {
+    get().map(Cow::Owned)
+}
+
+pub(crate) fn get_with_source() -> (impl Iterator<Item = String>, LocaleSource) {
+    // With the `android-jni` feature, the locales come from the first of these sources which has any:
+    // 1. The languages which the user picked for this app, on Android 13 and above.
+    // 2. Every locale of the app's configuration, or only the primary one before Android 7.0.
+    // 3. The Java VM's default locale, from `java.util.Locale.getDefault()`.
+    // Otherwise, or if none has any, this falls back to the single system locale property.
+    locales_with_source(framework_locales, read_locale)
+}
+
+/// Reads the locales from the framework, or from the system locale property if it has none.
+///
+/// Both are queried again on every call, and no JNI references are kept in between, so a
+/// configuration which changed while the app is running, such as after the user picked
+/// another language, is always picked up by the next call.
+fn locales_with_source(
+    framework: impl FnOnce() -> (Vec<String>, LocaleSource),
+    property: impl FnOnce() -> Option<String>,
+) -> (vec::IntoIter<String>, LocaleSource) {
+    let (mut locales, source) = framework();
+    if locales.is_empty() {
+        locales.extend(property());
+    }
+    (locales.into_iter(), source)
+}
+
+#[cfg(feature = "android-jni")]
+fn framework_locales() -> (Vec<String>, LocaleSource) {
+    let api_level = match api_level() {
+        Some(api_level) => api_level,
+        None => return (Vec::new(), LocaleSource::System),
+    };
+
+    first_framework_locales(
+        || java::app_locales(api_level),
+        || java::configuration_locales(api_level),
+        java::default_locale,
+    )
+}
+
+/// Returns the first of the app's languages, the configuration's locales, and the Java VM's
+/// default locale which has any, along with where they came from.
+///
+/// The later sources are only read if the earlier ones have no locales.
+#[cfg(any(test, feature = "android-jni"))]
+fn first_framework_locales(
+    app: impl FnOnce() -> Vec<String>,
+    configuration: impl FnOnce() -> Vec<String>,
+    jvm_default: impl FnOnce() -> Option<String>,
+) -> (Vec<String>, LocaleSource) {
+    let locales = app();
+    if !locales.is_empty() {
+        return (locales, LocaleSource::Application);
+    }
+
+    let locales = configuration();
+    if !locales.is_empty() {
+        return (locales, LocaleSource::System);
+    }
+    (jvm_default().into_iter().collect(), LocaleSource::System)
+}
+
+#[cfg(not(feature = "android-jni"))]
+fn framework_locales() -> (Vec<String>, LocaleSource) {
+    (Vec::new(), LocaleSource::System)
+}
+
+/// The sources of the framework's locales with the `android-jni` feature, in the same order as
+/// [`first_framework_locales`].
+#[cfg(feature = "android-jni")]
+const FRAMEWORK_SOURCES: [&str; 3] = [
+    "LocaleManager.getApplicationLocales",
+    "Configuration.getLocales",
+    "Locale.getDefault",
+];
+
+/// The system properties which the locale is read from, in the same order as [`read_locale`].
+const PROPERTY_SOURCES: [&str; 4] = [
+    "persist.sys.locale",
+    "persist.sys.language",
+    "ro.product.locale",
+    "ro.product.locale.language",
+];
+
+pub(crate) fn describe() -> ProviderInfo {
+    let mut sources = Vec::new();
+    #[cfg(feature = "android-jni")]
+    sources.extend(FRAMEWORK_SOURCES.iter());
+    sources.extend(PROPERTY_SOURCES.iter());
+
+    ProviderInfo {
+        name: "android",
+        sources,
+        active_source: framework_source().or_else(property_source),
+    }
+}
+
+/// Returns the first of the [`FRAMEWORK_SOURCES`] which has any locales.
+#[cfg(feature = "android-jni")]
+fn framework_source() -> Option<&'static str> {
+    let api_level = api_level()?;
+    if !java::app_locales(api_level).is_empty() {
+        Some(FRAMEWORK_SOURCES[0])
+    } else if !java::configuration_locales(api_level).is_empty() {
+        Some(FRAMEWORK_SOURCES[1])
+    } else if java::default_locale().is_some() {
+        Some(FRAMEWORK_SOURCES[2])
+    } else {
+        None
+    }
+}
+
+#[cfg(not(feature = "android-jni"))]
+fn framework_source() -> Option<&'static str> {
+    None
+}
+
+/// Returns the first of the [`PROPERTY_SOURCES`] which [`read_locale`] would use.
+fn property_source() -> Option<&'static str> {
+    let keys = [LOCALE_KEY, LANG_KEY, PRODUCT_LOCALE_KEY];
+    match keys.iter().position(|key| get_property(key).is_some()) {
+        Some(index) => Some(PROPERTY_SOURCES[index]),
+        // The product's language is only used along with its region.
+        None if get_property(PRODUCT_LANGUAGE_KEY).is_some()
+            && get_property(PRODUCT_REGION_KEY).is_some() =>
+        {
+            Some(PROPERTY_SOURCES[3])
+        }
+        None => None,
+    }
+}
+
+/// Returns the API level of the running version of Android, such as 33 for Android 13.
+#[cfg(feature = "android-jni")]
+fn api_level() -> Option<u32> {
+    get_property(SDK_KEY)?.parse().ok()
+}
+
+/// The system property and the framework already provide BCP-47 language tags, so they're
+/// returned as-is.
+pub(crate) fn get_raw() -> impl Iterator<Item = String> {
+    get()
+}
+
+/// System properties which can't be read are indistinguishable from unset ones, so the
+/// locale is only ever missing.
+pub(crate) fn try_get() -> Result<impl Iterator<Item = String>, LocaleError> {
+    Ok(get())
+}
+
+pub(crate) fn get_region() -> Option<String> {
+    read_locale()
+        .and_then(|locale| crate::region_from_tag(&locale))
+        .or_else(|| {
+            // Android 4.0 and below
+            get_property(COUNTRY_KEY)
+                .or_else(|| get_property(PRODUCT_REGION_KEY))
+                .and_then(|region| crate::region_code(&region))
+        })
+}
+
+/// Android doesn't configure formatting separately from the language.
+pub(crate) fn get_format_locale() -> Option<String> {
+    read_locale()
+}
+
+/// The system locale property is the only default which the framework doesn't override.
+pub(crate) fn get_default_locale() -> Option<String> {
+    read_locale()
+}
+
+/// Only the language is configured separately from the formatting locale.
+pub(crate) fn get_locale_for(category: LocaleCategory) -> Option<String> {
+    match category {
+        LocaleCategory::Messages => get().next(),
+        _ => get_format_locale(),
+    }
+}
+
+pub(crate) fn get_all_categories() -> Vec<(LocaleCategory, Option<String>)> {
+    LocaleCategory::ALL
+        .iter()
+        .map(|&category| (category, get_locale_for(category)))
+        .collect()
+}
+
+/// Sorting isn't configured separately, so this follows the formatting locale.
+pub(crate) fn get_collation_locale() -> Option<String> {
+    get_format_locale()
+}
+
+pub(crate) fn get_measurement_system() -> Option<MeasurementSystem> {
+    get_region().map(|region| MeasurementSystem::for_region(&region))
+}
+
+pub(crate) fn get_paper_size() -> Option<PaperSize> {
+    get_region().map(|region| PaperSize::for_region(&region))
+}
+
+pub(crate) fn get_number_separators() -> Option<NumberSeparators> {
+    None
+}
+
+pub(crate) fn get_grouping_sizes() -> Option<Vec<u8>> {
+    None
+}
+
+pub(crate) fn get_list_separator() -> Option<char> {
+    None
+}
+
+pub(crate) fn get_first_day_of_week() -> Option<Weekday> {
+    None
+}
+
+pub(crate) fn get_weekend_days() -> Option<(Weekday, Weekday)> {
+    get_region().map(|region| Weekday::weekend_for_region(&region))
+}
+
+pub(crate) fn uses_24_hour_time() -> Option<bool> {
+    None
+}
+
+pub(crate) fn get_date_field_order() -> Option<DateOrder> {
+    None
+}
+
+/// Android doesn't have a setting for the calendar, so it's the default of the locale's region.
+pub(crate) fn get_calendar() -> Option<Calendar> {
+    read_locale()
+        .and_then(|locale| crate::calendar_from_tag(&locale))
+        .or_else(|| get_region().map(|region| Calendar::for_region(&region)))
+}
+
+/// Android doesn't have a setting for the numbering system either, so it's picked by the locale.
+pub(crate) fn get_numbering_system() -> Option<String> {
+    read_locale().and_then(|locale| crate::numbering_system_from_tag(&locale))
+}
+
+pub(crate) fn get_locale_settings() -> LocaleSettings {
+    LocaleSettings {
+        region: get_region(),
+        measurement_system: get_measurement_system(),
+        currency_code: get_currency_code(),
+        first_day_of_week: get_first_day_of_week(),
+        uses_24_hour_time: uses_24_hour_time(),
+        number_separators: get_number_separators(),
+        ..LocaleSettings::default()
+    }
+}
+
+pub(crate) fn get_datetime_symbols() -> Option<DateTimeSymbols> {
+    None
+}
+
+pub(crate) fn get_currency_code() -> Option<String> {
+    None
+}
+
+pub(crate) fn get_currency_format() -> Option<CurrencyFormat> {
+    None
+}
+
+pub(crate) fn get_percent_symbol() -> Option<PercentSymbol> {
+    None
+}
+
+pub(crate) fn get_sign_symbols() -> Option<SignSymbols> {
+    None
+}
+
+#[cfg(feature = "time-zone")]
+const TIME_ZONE_KEY: &[u8] = b"persist.sys.timezone\0";
+
+#[cfg(feature = "time-zone")]
+pub(crate) fn get_time_zone() -> Option<String> {
+    get_property(TIME_ZONE_KEY)
+}
+
+pub(crate) fn display_name_of(_tag: &str, _in_locale: Option<&str>) -> Option<String> {
+    None
+}
+
+pub(crate) fn native_name_of(_tag: &str) -> Option<String> {
+    None
+}
+
+pub(crate) fn is_locale_available(_tag: &str) -> Option<bool> {
+    None
+}
+
+pub(crate) fn get_available_locales() -> Vec<String> {
+    Vec::new()
+}
+
+pub(crate) fn get_locale_display_name() -> Option<String> {
+    None
+}
+
+/// Locale changes can't be watched on this platform.
+#[cfg(feature = "notify")]
+pub(crate) enum Watcher {}
+
+#[cfg(feature = "notify")]
+pub(crate) fn watch(_callback: alloc::boxed::Box<dyn Fn() + Send + Sync>) -> Option<Watcher> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{first_framework_locales, locales_with_source};
+    use crate::LocaleSource;
+    use alloc::{string::String, vec, vec::Vec};
+    use core::cell::RefCell;
+
+    #[test]
+    fn changed_configuration_is_read_again() {
+        let configuration = RefCell::new(vec![String::from("en-US"), String::from("de-DE")]);
+        let read = || {
+            let (locales, source) = locales_with_source(
+                || (configuration.borrow().clone(), LocaleSource::System),
+                || Some(String::from("en-GB")),
+            );
+            (locales.collect::<Vec<_>>(), source)
+        };
+
+        let (locales, source) = read();
+        assert_eq!(locales, ["en-US", "de-DE"]);
+        assert_eq!(source, LocaleSource::System);
+
+        // The user picks another language, which changes the app's configuration.
+        *configuration.borrow_mut() = vec![String::from("fr-FR")];
+        let (locales, source) = read();
+        assert_eq!(locales, ["fr-FR"]);
+        assert_eq!(source, LocaleSource::System);
+
+        // The system locale property is only read if the framework has no locales.
+        configuration.borrow_mut().clear();
+        let (locales, source) = read();
+        assert_eq!(locales, ["en-GB"]);
+        assert_eq!(source, LocaleSource::System);
+    }
+
+    #[test]
+    fn jvm_default_is_last_framework_source() {
+        let jvm_default = || Some(String::from("pt-BR"));
+
+        let (locales, source) = first_framework_locales(
+            || vec![String::from("fr-CA")],
+            || vec![String::from("en-US")],
+            || unreachable!(),
+        );
+        assert_eq!(locales, ["fr-CA"]);
+        assert_eq!(source, LocaleSource::Application);
+
+        let (locales, source) =
+            first_framework_locales(Vec::new, || vec![String::from("en-US")], || unreachable!());
+        assert_eq!(locales, ["en-US"]);
+        assert_eq!(source, LocaleSource::System);
+
+        // Without a configuration, such as in a headless process, the VM's default is used.
+        let (locales, source) = first_framework_locales(Vec::new, Vec::new, jvm_default);
+        assert_eq!(locales, ["pt-BR"]);
+        assert!(crate::is_well_formed_bcp47(&locales[0]));
+        assert_eq!(source, LocaleSource::System);
+
+        let (locales, _) = first_framework_locales(Vec::new, Vec::new, || None);
+        assert!(locales.is_empty());
+    }
 }
@@ -0,0 +1,236 @@
+//! Reads the locales of the running app from the Android framework through JNI.
+//!
+//! The Java VM is found with `JNI_GetCreatedJavaVMs`, which `libnativehelper` exports from
+//! Android 12. There is no VM in a plain native binary, such as a test run on a device, so the
+//! framework is unavailable there and the system properties are used instead.
+//!
+//! The app's `Context` comes from `ndk-context`, which is initialized by `android-activity` and
+//! the other crates which provide the entry point of a native app. Once there is a VM, it must
+//! have been initialized, since `ndk-context` panics otherwise.
+use alloc::{string::String, vec::Vec};
+use jni::errors::Result;
+use jni::objects::{JObject, JString, JValue};
+use jni::sys::{jint, jsize};
+use jni::{JNIEnv, JavaVM};
+
+/// The API level of Android 7.0, which added `LocaleList`.
+const NOUGAT: u32 = 24;
+/// The API level of Android 13, which added per-app language preferences.
+const TIRAMISU: u32 = 33;
+
+/// Returns the languages which the user picked for this app, in order of preference.
+///
+/// These are read from `LocaleManager.getApplicationLocales()`, which is only available
+/// from Android 13. On earlier versions, or if the user hasn't picked any languages for
+/// this app, the list is empty.
+pub(super) fn app_locales(api_level: u32) -> Vec<String> {
+    if api_level < TIRAMISU {
+        return Vec::new();
+    }
+
+    with_context(|env, context| {
+        let class = env.find_class("android/app/LocaleManager")?;
+        let manager = env
+            .call_method(
+                context,
+                "getSystemService",
+                "(Ljava/lang/Class;)Ljava/lang/Object;",
+                &[JValue::Object(&class)],
+            )?
+            .l()?;
+        if manager.is_null() {
+            return Ok(Vec::new());
+        }
+
+        let locales = env
+            .call_method(
+                &manager,
+                "getApplicationLocales",
+                "()Landroid/os/LocaleList;",
+                &[],
+            )?
+            .l()?;
+        locale_list_tags(env, &locales)
+    })
+    .unwrap_or_default()
+}
+
+/// Returns the locales of the app's resource configuration, in order of preference.
+///
+/// From Android 7.0, this is every locale in `Configuration.getLocales()`. Earlier versions only
+/// support a single locale, which is read from `Configuration.locale`.
+pub(super) fn configuration_locales(api_level: u32) -> Vec<String> {
+    with_context(|env, context| {
+        let resources = env
+            .call_method(
+                context,
+                "getResources",
+                "()Landroid/content/res/Resources;",
+                &[],
+            )?
+            .l()?;
+        let configuration = env
+            .call_method(
+                &resources,
+                "getConfiguration",
+                "()Landroid/content/res/Configuration;",
+                &[],
+            )?
+            .l()?;
+
+        if api_level >= NOUGAT {
+            let locales = env
+                .call_method(
+                    &configuration,
+                    "getLocales",
+                    "()Landroid/os/LocaleList;",
+                    &[],
+                )?
+                .l()?;
+            locale_list_tags(env, &locales)
+        } else {
+            let locale = env
+                .get_field(&configuration, "locale", "Ljava/util/Locale;")?
+                .l()?;
+            if locale.is_null() {
+                return Ok(Vec::new());
+            }
+            Ok(alloc::vec![locale_tag(env, &locale)?])
+        }
+    })
+    .unwrap_or_default()
+}
+
+/// Returns the BCP-47 language tag of the Java VM's default locale, from
+/// `java.util.Locale.getDefault()`.
+///
+/// This doesn't need the app's `Context`, so it's still available when the configuration can't
+/// be read, such as in a headless process without an `Activity`.
+pub(super) fn default_locale() -> Option<String> {
+    with_vm(|env| {
+        let locale = env
+            .call_static_method(
+                "java/util/Locale",
+                "getDefault",
+                "()Ljava/util/Locale;",
+                &[],
+            )?
+            .l()?;
+        if locale.is_null() {
+            return Ok(None);
+        }
+        locale_tag(env, &locale).map(Some)
+    })
+    .flatten()
+}
+
+/// Calls `f` with the app's `Context` on a thread attached to the Java VM, discarding any
+/// exception which is thrown by the framework.
+fn with_context(
+    f: impl FnOnce(&mut JNIEnv, &JObject) -> Result<Vec<String>>,
+) -> Option<Vec<String>> {
+    let vm = created_vm()?;
+
+    // This panics if `ndk-context` wasn't initialized, which the `android-jni` feature requires
+    // of any process with a Java VM.
+    let ctx = ndk_context::android_context();
+
+    // SAFETY: `ndk-context` is initialized with a global reference to the app's `Context`,
+    // which is never deleted, and `JObject` doesn't delete the reference when dropped.
+    let context = unsafe { JObject::from_raw(ctx.context().cast()) };
+
+    with_env(vm, |env| f(env, &context))
+}
+
+/// Calls `f` on a thread attached to the Java VM, discarding any exception which is thrown.
+fn with_vm<T>(f: impl FnOnce(&mut JNIEnv) -> Result<T>) -> Option<T> {
+    with_env(created_vm()?, f)
+}
+
+/// The signature of `JNI_GetCreatedJavaVMs`.
+type GetCreatedJavaVms =
+    unsafe extern "system" fn(*mut *mut jni::sys::JavaVM, jsize, *mut jsize) -> jint;
+
+/// Returns the Java VM of the process, or `None` if there isn't one.
+///
+/// `JNI_GetCreatedJavaVMs` is looked up when it's called, rather than linked, since it's only
+/// exported to apps from Android 12, so it's also `None` on earlier versions.
+fn created_vm() -> Option<JavaVM> {
+    // SAFETY: The name is a null-terminated string.
+    let symbol = unsafe {
+        libc::dlsym(
+            libc::RTLD_DEFAULT,
+            b"JNI_GetCreatedJavaVMs\0".as_ptr().cast(),
+        )
+    };
+    if symbol.is_null() {
+        return None;
+    }
+    // SAFETY: The symbol is the function exported by `libnativehelper`, with this signature.
+    let get_created_vms: GetCreatedJavaVms = unsafe { core::mem::transmute(symbol) };
+
+    let mut vm = core::ptr::null_mut();
+    let mut count = 0;
+    // SAFETY: There is room for one VM, which is all that Android supports.
+    if unsafe { get_created_vms(&mut vm, 1, &mut count) } != jni::sys::JNI_OK || count < 1 {
+        return None;
+    }
+
+    // SAFETY: The pointer is to the VM of the process, which lives as long as it does.
+    unsafe { JavaVM::from_raw(vm) }.ok()
+}
+
+/// Calls `f` on a thread attached to `vm`, discarding any exception which is thrown.
+fn with_env<T>(vm: JavaVM, f: impl FnOnce(&mut JNIEnv) -> Result<T>) -> Option<T> {
+    let mut env = vm.attach_current_thread().ok()?;
+
+    // Any local references created by `f` are released along with the frame.
+    let value = env.with_local_frame(16, f);
+    if env.exception_check().unwrap_or(false) {
+        let _ = env.exception_clear();
+    }
+
+    value.ok()
+}
+
+/// Returns the BCP-47 language tag of every locale in an `android.os.LocaleList`.
+fn locale_list_tags(env: &mut JNIEnv, locales: &JObject) -> Result<Vec<String>> {
+    let len = env.call_method(locales, "size", "()I", &[])?.i()?;
+
+    let mut tags = Vec::new();
+    for idx in 0..len {
+        // Each locale's references are released before reading the next one, so that
+        // long lists can't overflow the local reference table.
+        let tag = env.with_local_frame(4, |env| -> Result<String> {
+            let locale = env
+                .call_method(locales, "get", "(I)Ljava/util/Locale;", &[JValue::Int(idx)])?
+                .l()?;
+            locale_tag(env, &locale)
+        })?;
+        tags.push(tag);
+    }
+
+    Ok(tags)
+}
+
+/// Returns the BCP-47 language tag of a `java.util.Locale`.
+fn locale_tag(env: &mut JNIEnv, locale: &JObject) -> Result<String> {
+    let tag = env
+        .call_method(locale, "toLanguageTag", "()Ljava/lang/String;", &[])?
+        .l()?;
+    Ok(env.get_string(&JString::from(tag))?.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{app_locales, configuration_locales, default_locale, NOUGAT, TIRAMISU};
+
+    #[test]
+    fn uninitialized_context() {
+        // A test binary doesn't have a Java VM, so the framework is unavailable, and
+        // `ndk-context`, which nothing initializes, isn't used.
+        assert!(app_locales(TIRAMISU).is_empty());
+        assert!(configuration_locales(NOUGAT).is_empty());
+        assert_eq!(default_locale(), None);
+    }
+}
@@ -1,7 +1,14 @@
-use alloc::{string::String, vec::Vec};
+use crate::{
+    Calendar, CurrencyFormat, DateOrder, DateTimeSymbols, LocaleCategory, LocaleError,
+    LocaleSettings, LocaleSource, MeasurementSystem, NumberSeparators, PaperSize, PercentSymbol,
+    ProviderInfo, SignSymbols, Weekday,
+};
+use alloc::{borrow::Cow, string::String, vec::Vec};
+use core::convert::TryFrom;
 use core::ffi::c_void;
 
 type CFIndex = isize;
+type CFTypeID = usize;
 type Boolean = u8;
 type CFStringEncoding = u32;
 
@@ -16,6 +23,8 @@ struct CFRange {
 }
 
 type CFTypeRef = *const c_void;
+type CFAllocatorRef = *const c_void;
+type CFPropertyListRef = CFTypeRef;
 
 #[repr(C)]
 struct __CFArray(c_void);
@@ -25,13 +34,50 @@ type CFArrayRef = *const __CFArray;
 struct __CFString(c_void);
 type CFStringRef = *const __CFString;
 
+#[repr(C)]
+struct __CFLocale(c_void);
+type CFLocaleRef = *const __CFLocale;
+
+#[repr(C)]
+struct __CFCalendar(c_void);
+type CFCalendarRef = *const __CFCalendar;
+
+#[cfg(feature = "time-zone")]
+#[repr(C)]
+struct __CFTimeZone(c_void);
+#[cfg(feature = "time-zone")]
+type CFTimeZoneRef = *const __CFTimeZone;
+
+#[repr(C)]
+struct __CFNumberFormatter(c_void);
+type CFNumberFormatterRef = *const __CFNumberFormatter;
+
+type CFOptionFlags = usize;
+type CFNumberFormatterStyle = CFIndex;
+
+#[allow(non_upper_case_globals)]
+const kCFNumberFormatterDecimalStyle: CFNumberFormatterStyle = 1;
+#[allow(non_upper_case_globals)]
+const kCFNumberFormatterCurrencyStyle: CFNumberFormatterStyle = 2;
+#[allow(non_upper_case_globals)]
+const kCFNumberFormatterPercentStyle: CFNumberFormatterStyle = 3;
+
 // Most of these definitions come from `core-foundation-sys`, but we want this crate
 // to be `no_std` and `core-foundation-sys` isn't currently.
 #[link(name = "CoreFoundation", kind = "framework")]
 extern "C" {
+    fn CFArrayGetTypeID() -> CFTypeID;
     fn CFArrayGetCount(theArray: CFArrayRef) -> CFIndex;
     fn CFArrayGetValueAtIndex(theArray: CFArrayRef, idx: CFIndex) -> *const c_void;
 
+    fn CFStringGetTypeID() -> CFTypeID;
+    fn CFStringCreateWithBytes(
+        alloc: CFAllocatorRef,
+        bytes: *const u8,
+        numBytes: CFIndex,
+        encoding: CFStringEncoding,
+        isExternalRepresentation: Boolean,
+    ) -> CFStringRef;
     fn CFStringGetLength(theString: CFStringRef) -> CFIndex;
     fn CFStringGetBytes(
         theString: CFStringRef,
@@ -44,112 +90,1351 @@ extern "C" {
         usedBufLen: *mut CFIndex,
     ) -> CFIndex;
 
+    fn CFGetTypeID(cf: CFTypeRef) -> CFTypeID;
     fn CFRelease(cf: CFTypeRef);
 
+    fn CFPreferencesCopyAppValue(key: CFStringRef, applicationID: CFStringRef)
+        -> CFPropertyListRef;
+    fn CFPreferencesCopyValue(
+        key: CFStringRef,
+        applicationID: CFStringRef,
+        userName: CFStringRef,
+        hostName: CFStringRef,
+    ) -> CFPropertyListRef;
+
+    static kCFPreferencesCurrentApplication: CFStringRef;
+    static kCFPreferencesAnyApplication: CFStringRef;
+    static kCFPreferencesCurrentUser: CFStringRef;
+    static kCFPreferencesAnyHost: CFStringRef;
+
     fn CFLocaleCopyPreferredLanguages() -> CFArrayRef;
+    fn CFLocaleCopyAvailableLocaleIdentifiers() -> CFArrayRef;
+
+    fn CFLocaleCopyCurrent() -> CFLocaleRef;
+    fn CFLocaleCreate(allocator: CFAllocatorRef, localeIdentifier: CFStringRef) -> CFLocaleRef;
+    fn CFLocaleGetValue(locale: CFLocaleRef, key: CFStringRef) -> CFTypeRef;
+    fn CFLocaleGetIdentifier(locale: CFLocaleRef) -> CFStringRef;
+    fn CFLocaleCopyDisplayNameForPropertyValue(
+        displayLocale: CFLocaleRef,
+        key: CFStringRef,
+        value: CFStringRef,
+    ) -> CFStringRef;
+
+    static kCFLocaleIdentifier: CFStringRef;
+    static kCFLocaleCountryCode: CFStringRef;
+    static kCFLocaleCurrencyCode: CFStringRef;
+    static kCFLocaleMeasurementSystem: CFStringRef;
+    static kCFLocaleDecimalSeparator: CFStringRef;
+    static kCFLocaleGroupingSeparator: CFStringRef;
+
+    fn CFDateFormatterCreateDateFormatFromTemplate(
+        allocator: CFAllocatorRef,
+        tmplate: CFStringRef,
+        options: CFOptionFlags,
+        locale: CFLocaleRef,
+    ) -> CFStringRef;
+
+    fn CFNumberFormatterCreate(
+        allocator: CFAllocatorRef,
+        locale: CFLocaleRef,
+        style: CFNumberFormatterStyle,
+    ) -> CFNumberFormatterRef;
+    fn CFNumberFormatterGetFormat(formatter: CFNumberFormatterRef) -> CFStringRef;
+    fn CFNumberFormatterCopyProperty(
+        formatter: CFNumberFormatterRef,
+        key: CFStringRef,
+    ) -> CFTypeRef;
+
+    static kCFNumberFormatterPercentSymbol: CFStringRef;
+    static kCFNumberFormatterPerMillSymbol: CFStringRef;
+    static kCFNumberFormatterPlusSign: CFStringRef;
+    static kCFNumberFormatterMinusSign: CFStringRef;
+
+    fn CFCalendarCopyCurrent() -> CFCalendarRef;
+    fn CFCalendarGetFirstWeekday(calendar: CFCalendarRef) -> CFIndex;
+    fn CFCalendarGetIdentifier(calendar: CFCalendarRef) -> CFStringRef;
+
+    #[cfg(feature = "time-zone")]
+    fn CFTimeZoneCopyDefault() -> CFTimeZoneRef;
+    #[cfg(feature = "time-zone")]
+    fn CFTimeZoneGetName(tz: CFTimeZoneRef) -> CFStringRef;
 }
 
+#[cfg(feature = "notify")]
+#[repr(C)]
+struct __CFNotificationCenter(c_void);
+#[cfg(feature = "notify")]
+type CFNotificationCenterRef = *const __CFNotificationCenter;
+
+#[cfg(feature = "notify")]
+type CFNotificationCallback = extern "C" fn(
+    center: CFNotificationCenterRef,
+    observer: *mut c_void,
+    name: CFStringRef,
+    object: *const c_void,
+    user_info: CFTypeRef,
+);
+
+#[cfg(feature = "notify")]
+#[allow(non_upper_case_globals)]
+const CFNotificationSuspensionBehaviorDeliverImmediately: CFIndex = 4;
+
+#[cfg(feature = "notify")]
+#[link(name = "CoreFoundation", kind = "framework")]
+extern "C" {
+    fn CFNotificationCenterGetLocalCenter() -> CFNotificationCenterRef;
+    fn CFNotificationCenterAddObserver(
+        center: CFNotificationCenterRef,
+        observer: *const c_void,
+        callBack: CFNotificationCallback,
+        name: CFStringRef,
+        object: *const c_void,
+        suspensionBehavior: CFIndex,
+    );
+    fn CFNotificationCenterRemoveObserver(
+        center: CFNotificationCenterRef,
+        observer: *const c_void,
+        name: CFStringRef,
+        object: *const c_void,
+    );
+
+    static kCFLocaleCurrentLocaleDidChangeNotification: CFStringRef;
+}
+
+pub(crate) const SUPPORTED: bool = true;
+
 pub(crate) fn get() -> impl Iterator<Item = String> {
-    _get().into_iter()
-}
-
-fn _get() -> Option<String> {
-    let preferred_langs = unsafe {
-        // SAFETY: This function is safe to call and has no invariants. Any value inside the
-        // array will be owned by us.
-        let langs = CFLocaleCopyPreferredLanguages();
-        if !langs.is_null() {
-            let langs = CFArray(langs);
-            // SAFETY: The returned array is a valid CFArray object.
-            if CFArrayGetCount(langs.0) != 0 {
-                langs
-            } else {
-                return None;
-            }
+    get_with_source().0
+}
+
+/// The languages are decoded from `CFString`s, so none can be borrowed.
+pub(crate) fn get_cow() -> impl Iterator<Item = Cow<'static, str>> {
+    get().map(Cow::Owned)
+}
+
+pub(crate) fn get_with_source() -> (impl Iterator<Item = String>, LocaleSource) {
+    // The languages come from the first of these sources which has any:
+    // 1. The `AppleLanguages` preference of the running application. This is set when an app
+    //    offers its own language picker (or the user picks a language for it in System Settings),
+    //    and is what drives the app's UI, regardless of the global setting.
+    // 2. The user's global preferred languages, from the `AppleLanguages` preference of the
+    //    global domain.
+    // 3. The preferred languages which CoreFoundation reports for the process.
+    //
+    // The languages are returned as they're reported, so Cantonese stays as `yue-Hant-HK` when
+    // that's what the system lists, rather than being collapsed into `zh-Hant-HK`.
+    let (langs, source) = first_languages(app_languages, global_languages, preferred_languages);
+    let len = langs.as_ref().map_or(0, CFArray::len);
+
+    // Each locale is only copied out of the array once the caller asks for it.
+    let locales = (0..len).flat_map(move |idx| langs.as_ref()?.string_at(idx));
+    (locales, source)
+}
+
+/// Returns the first of the app's languages, the global languages, and the preferred languages
+/// which has any, along with where they came from.
+///
+/// The later sources are only read if the earlier ones have no languages. Only the app's own
+/// languages are [`LocaleSource::Application`].
+fn first_languages<T>(
+    app: impl FnOnce() -> Option<T>,
+    global: impl FnOnce() -> Option<T>,
+    preferred: impl FnOnce() -> Option<T>,
+) -> (Option<T>, LocaleSource) {
+    match app() {
+        Some(langs) => (Some(langs), LocaleSource::Application),
+        None => (global().or_else(preferred), LocaleSource::System),
+    }
+}
+
+/// The preferred languages are already BCP-47 language tags, so they're returned as-is.
+pub(crate) fn get_raw() -> impl Iterator<Item = String> {
+    get()
+}
+
+/// CoreFoundation doesn't report why the preferred languages couldn't be read, so they're
+/// only ever missing.
+pub(crate) fn try_get() -> Result<impl Iterator<Item = String>, LocaleError> {
+    Ok(get())
+}
+
+/// The sources of the languages, in the same order as [`get_with_source`].
+const SOURCES: [&str; 3] = [
+    "AppleLanguages (application)",
+    "AppleLanguages (global)",
+    "CFLocaleCopyPreferredLanguages",
+];
+
+pub(crate) fn describe() -> ProviderInfo {
+    let active_source = if app_languages().is_some() {
+        Some(SOURCES[0])
+    } else if global_languages().is_some() {
+        Some(SOURCES[1])
+    } else {
+        preferred_languages()
+            .filter(|langs| langs.len() != 0)
+            .map(|_| SOURCES[2])
+    };
+
+    ProviderInfo {
+        name: "apple",
+        sources: SOURCES.to_vec(),
+        active_source,
+    }
+}
+
+/// Returns the `AppleLanguages` preference of the running application, if it's set.
+///
+/// Only the application's own domain is read, since the search list of
+/// `CFPreferencesCopyAppValue` also includes the global domain, which would make every user's
+/// global languages look like languages picked for the application.
+fn app_languages() -> Option<CFArray> {
+    // SAFETY: The application ID is a valid CFString constant.
+    languages_preference(unsafe { kCFPreferencesCurrentApplication })
+}
+
+/// Returns the `AppleLanguages` preference of the global domain, which holds the languages
+/// picked in System Settings, if it's set.
+///
+/// This is read whenever the application's own domain doesn't set the languages, which
+/// [`app_languages`] doesn't search past. A command-line tool which isn't in an app bundle has no
+/// preferences of its own, and the languages reported for its process may then be empty, so
+/// this is read directly rather than relying on [`preferred_languages`].
+fn global_languages() -> Option<CFArray> {
+    // SAFETY: The application ID is a valid CFString constant.
+    languages_preference(unsafe { kCFPreferencesAnyApplication })
+}
+
+/// Returns the `AppleLanguages` preference in the current user's domain of `application_id`, for
+/// any host, if it's set and isn't empty.
+fn languages_preference(application_id: CFStringRef) -> Option<CFArray> {
+    let key = CFString::new("AppleLanguages")?;
+
+    // SAFETY: `key` and the IDs are valid CFStrings. Any returned value will be owned by us, and
+    // is released when the `CFArray` is dropped.
+    let langs = unsafe {
+        CFPreferencesCopyValue(
+            key.0,
+            application_id,
+            kCFPreferencesCurrentUser,
+            kCFPreferencesAnyHost,
+        )
+    };
+
+    // SAFETY: `langs` is either null or a valid CF object owned by us.
+    unsafe { CFArray::from_property_list(langs) }.filter(|langs| langs.len() != 0)
+}
+
+/// Returns the user's preferred languages.
+fn preferred_languages() -> Option<CFArray> {
+    // SAFETY: This function is safe to call and has no invariants. Any value inside the
+    // array will be owned by us.
+    let langs = unsafe { CFLocaleCopyPreferredLanguages() };
+    if !langs.is_null() {
+        Some(CFArray(langs))
+    } else {
+        None
+    }
+}
+
+pub(crate) fn get_region() -> Option<String> {
+    region(&CFLocale::current()?)
+}
+
+fn region(locale: &CFLocale) -> Option<String> {
+    // SAFETY: The country code key is a valid CFString constant.
+    let country = locale.string_value(unsafe { kCFLocaleCountryCode })?;
+    crate::region_code(&country)
+}
+
+/// Every setting other than the first day of the week, which comes from the calendar, is read from
+/// the same locale.
+pub(crate) fn get_locale_settings() -> LocaleSettings {
+    let locale = CFLocale::current();
+    let locale = locale.as_ref();
+    LocaleSettings {
+        region: locale.and_then(region),
+        measurement_system: locale.and_then(measurement_system),
+        currency_code: locale.and_then(currency_code),
+        first_day_of_week: get_first_day_of_week(),
+        uses_24_hour_time: locale.and_then(uses_24_hour_time_for),
+        number_separators: locale.and_then(number_separators),
+        ..LocaleSettings::default()
+    }
+}
+
+pub(crate) fn get_datetime_symbols() -> Option<DateTimeSymbols> {
+    None
+}
+
+pub(crate) fn get_currency_code() -> Option<String> {
+    currency_code(&CFLocale::current()?)
+}
+
+fn currency_code(locale: &CFLocale) -> Option<String> {
+    // SAFETY: The currency code key is a valid CFString constant.
+    let currency = locale.string_value(unsafe { kCFLocaleCurrencyCode })?;
+    crate::currency_code(&currency)
+}
+
+/// Only the language is configured separately from the formatting locale.
+pub(crate) fn get_locale_for(category: LocaleCategory) -> Option<String> {
+    match category {
+        LocaleCategory::Messages => get().next(),
+        _ => get_format_locale(),
+    }
+}
+
+pub(crate) fn get_all_categories() -> Vec<(LocaleCategory, Option<String>)> {
+    LocaleCategory::ALL
+        .iter()
+        .map(|&category| (category, get_locale_for(category)))
+        .collect()
+}
+
+/// Sorting isn't configured separately, so this follows the formatting locale.
+pub(crate) fn get_collation_locale() -> Option<String> {
+    get_format_locale()
+}
+
+pub(crate) fn get_measurement_system() -> Option<MeasurementSystem> {
+    measurement_system(&CFLocale::current()?)
+}
+
+fn measurement_system(locale: &CFLocale) -> Option<MeasurementSystem> {
+    // SAFETY: The measurement system key is a valid CFString constant.
+    let system = locale.string_value(unsafe { kCFLocaleMeasurementSystem })?;
+    match system.as_str() {
+        "Metric" => Some(MeasurementSystem::Metric),
+        "U.S." => Some(MeasurementSystem::UsCustomary),
+        "U.K." => Some(MeasurementSystem::Uk),
+        _ => None,
+    }
+}
+
+/// Apple platforms don't provide a paper size preference, so it's inferred from the region.
+pub(crate) fn get_paper_size() -> Option<PaperSize> {
+    get_region().map(|region| PaperSize::for_region(&region))
+}
+
+pub(crate) fn get_first_day_of_week() -> Option<Weekday> {
+    let calendar = CFCalendar::current()?;
+
+    // SAFETY: This wrapper contains a valid CFCalendar.
+    let first_weekday = unsafe { CFCalendarGetFirstWeekday(calendar.0) };
+
+    // CoreFoundation counts the days from Sunday as 1 to Saturday as 7.
+    match first_weekday {
+        1..=7 => u32::try_from(first_weekday + 5)
+            .ok()
+            .map(Weekday::from_monday_offset),
+        _ => None,
+    }
+}
+
+pub(crate) fn get_weekend_days() -> Option<(Weekday, Weekday)> {
+    get_region().map(|region| Weekday::weekend_for_region(&region))
+}
+
+/// The default time zone is the system's, which is `NSTimeZone.local`, unless the app has set
+/// another one.
+#[cfg(feature = "time-zone")]
+pub(crate) fn get_time_zone() -> Option<String> {
+    // SAFETY: This function is safe to call and has no invariants. The returned time zone is
+    // owned by us.
+    let time_zone = unsafe { CFTimeZoneCopyDefault() };
+    if time_zone.is_null() {
+        return None;
+    }
+
+    // SAFETY: `time_zone` is a valid CFTimeZone. The name follows the "Get" rule, so it lives as
+    // long as the time zone, and is copied before the time zone is released.
+    unsafe {
+        let name = CFTimeZoneGetName(time_zone);
+        let name = if name.is_null() {
+            None
         } else {
-            return None;
-        }
+            string_from_cfstring(name)
+        };
+        CFRelease(time_zone.cast());
+        name
+    }
+}
+
+/// The current locale's identifier has a `numbers` keyword if the user picked a numbering system,
+/// such as `ar_EG@numbers=latn`, which becomes the `-u-nu-` keyword of its tag.
+pub(crate) fn get_numbering_system() -> Option<String> {
+    crate::numbering_system_from_tag(&get_format_locale()?)
+}
+
+pub(crate) fn uses_24_hour_time() -> Option<bool> {
+    uses_24_hour_time_for(&CFLocale::current()?)
+}
+
+fn uses_24_hour_time_for(locale: &CFLocale) -> Option<bool> {
+    // The `j` template is the locale's preferred hour format, which respects the user's
+    // 24-hour time setting.
+    let format = date_format_from_template(locale, "j")?;
+    crate::settings::pattern_uses_24_hour_time(&format)
+}
+
+pub(crate) fn get_date_field_order() -> Option<DateOrder> {
+    // The `yMd` template is the locale's short numeric date format.
+    let format = date_format_from_template(&CFLocale::current()?, "yMd")?;
+    crate::settings::pattern_date_order(&format)
+}
+
+pub(crate) fn get_calendar() -> Option<Calendar> {
+    let calendar = CFCalendar::current()?;
+
+    // SAFETY: This wrapper contains a valid CFCalendar. The returned identifier isn't owned by us,
+    // and lives as long as the calendar.
+    let identifier = unsafe { CFCalendarGetIdentifier(calendar.0) };
+    if identifier.is_null() {
+        return None;
+    }
+
+    // SAFETY: `identifier` is a valid CFString.
+    let identifier = unsafe { string_from_cfstring(identifier) }?;
+    Calendar::from_identifier(&identifier)
+}
+
+/// Returns the locale's date format pattern which best matches a skeleton, such as `yMd`.
+fn date_format_from_template(locale: &CFLocale, template: &str) -> Option<String> {
+    let template = CFString::new(template)?;
+
+    // SAFETY: `template` is a valid CFString and `locale` is a valid CFLocale. The returned
+    // format will be owned by us.
+    let format = unsafe {
+        CFDateFormatterCreateDateFormatFromTemplate(core::ptr::null(), template.0, 0, locale.0)
+    };
+    if format.is_null() {
+        return None;
+    }
+    let format = CFString(format);
+
+    // SAFETY: `format` is a valid CFString.
+    unsafe { string_from_cfstring(format.0) }
+}
+
+pub(crate) fn get_currency_format() -> Option<CurrencyFormat> {
+    currency_format(&CFLocale::current()?)
+}
+
+pub(crate) fn get_locale_display_name() -> Option<String> {
+    None
+}
+
+/// The name of the locale in its own language, which is its display name in itself.
+pub(crate) fn native_name_of(tag: &str) -> Option<String> {
+    display_name_of(tag, Some(tag))
+}
+
+/// This is the same as `NSLocale.localizedString(forLocaleIdentifier:)`.
+pub(crate) fn display_name_of(tag: &str, in_locale: Option<&str>) -> Option<String> {
+    let locale = match in_locale {
+        Some(identifier) => CFLocale::new(identifier)?,
+        None => CFLocale::current()?,
+    };
+    let tag = CFString::new(tag)?;
+
+    // SAFETY: `locale` is a valid CFLocale, and the key and `tag` are valid CFStrings. The
+    // returned name will be owned by us.
+    let name =
+        unsafe { CFLocaleCopyDisplayNameForPropertyValue(locale.0, kCFLocaleIdentifier, tag.0) };
+    if name.is_null() {
+        return None;
+    }
+    let name = CFString(name);
+
+    // SAFETY: `name` is a valid CFString.
+    unsafe { string_from_cfstring(name.0) }
+}
+
+pub(crate) fn is_locale_available(tag: &str) -> Option<bool> {
+    let available = available_locales()?;
+    Some(crate::is_tag_available(tag, available.into_iter()))
+}
+
+pub(crate) fn get_available_locales() -> Vec<String> {
+    available_locales().unwrap_or_default()
+}
+
+/// Returns the locales of `NSLocale.availableLocaleIdentifiers` as BCP-47 language tags.
+fn available_locales() -> Option<Vec<String>> {
+    // SAFETY: This function is safe to call and has no invariants. The returned array will be
+    // owned by us.
+    let identifiers = unsafe { CFLocaleCopyAvailableLocaleIdentifiers() };
+    if identifiers.is_null() {
+        return None;
+    }
+    let identifiers = CFArray(identifiers);
+
+    let locales = (0..identifiers.len())
+        .filter_map(|idx| identifiers.string_at(idx))
+        .filter_map(|identifier| identifier_to_bcp47(&identifier))
+        .collect();
+    Some(locales)
+}
+
+/// Reads the currency format of `locale` from the pattern of a currency `CFNumberFormatter`,
+/// which is the same as an `NSNumberFormatter` with the currency style.
+fn currency_format(locale: &CFLocale) -> Option<CurrencyFormat> {
+    let formatter = CFNumberFormatter::new(locale, kCFNumberFormatterCurrencyStyle)?;
+    CurrencyFormat::from_pattern(&formatter.pattern()?)
+}
+
+pub(crate) fn get_percent_symbol() -> Option<PercentSymbol> {
+    percent_symbol(&CFLocale::current()?)
+}
+
+/// Reads the percent symbols of `locale` from a percent `CFNumberFormatter`, which are the same
+/// as `NSNumberFormatter.percentSymbol` and `perMillSymbol`.
+fn percent_symbol(locale: &CFLocale) -> Option<PercentSymbol> {
+    let formatter = CFNumberFormatter::new(locale, kCFNumberFormatterPercentStyle)?;
+
+    // SAFETY: The symbol keys are valid CFString constants.
+    let (symbol, per_mille) = unsafe {
+        (
+            formatter.string_property(kCFNumberFormatterPercentSymbol)?,
+            formatter
+                .string_property(kCFNumberFormatterPerMillSymbol)
+                .unwrap_or_default(),
+        )
+    };
+    let space_separated = formatter
+        .pattern()
+        .and_then(|pattern| crate::settings::pattern_separates_percent(&pattern));
+
+    PercentSymbol::from_strings(&symbol, &per_mille, space_separated)
+}
+
+pub(crate) fn get_sign_symbols() -> Option<SignSymbols> {
+    sign_symbols(&CFLocale::current()?)
+}
+
+/// Reads the signs of `locale` from a decimal `CFNumberFormatter`, which are the same as
+/// `NSNumberFormatter.plusSign` and `minusSign`.
+fn sign_symbols(locale: &CFLocale) -> Option<SignSymbols> {
+    let formatter = CFNumberFormatter::new(locale, kCFNumberFormatterDecimalStyle)?;
+
+    // SAFETY: The symbol keys are valid CFString constants.
+    let (positive, negative) = unsafe {
+        (
+            formatter
+                .string_property(kCFNumberFormatterPlusSign)
+                .unwrap_or_default(),
+            formatter.string_property(kCFNumberFormatterMinusSign)?,
+        )
     };
+    SignSymbols::from_strings(&positive, &negative)
+}
+
+pub(crate) fn get_number_separators() -> Option<NumberSeparators> {
+    number_separators(&CFLocale::current()?)
+}
+
+/// Apple platforms don't have a setting for the list separator, so it's derived from the decimal
+/// separator.
+pub(crate) fn get_list_separator() -> Option<char> {
+    get_number_separators().map(|separators| separators.list_separator())
+}
+
+fn number_separators(locale: &CFLocale) -> Option<NumberSeparators> {
+    // SAFETY: The separator keys are valid CFString constants.
+    let (decimal, grouping) = unsafe {
+        (
+            locale.string_value(kCFLocaleDecimalSeparator)?,
+            locale
+                .string_value(kCFLocaleGroupingSeparator)
+                .unwrap_or_default(),
+        )
+    };
+
+    NumberSeparators::from_strings(&decimal, &grouping)
+}
+
+pub(crate) fn get_grouping_sizes() -> Option<Vec<u8>> {
+    let formatter = CFNumberFormatter::new(&CFLocale::current()?, kCFNumberFormatterDecimalStyle)?;
+    // The grouping and secondary grouping sizes of the formatter are those of its pattern.
+    Some(crate::settings::pattern_grouping_sizes(
+        &formatter.pattern()?,
+    ))
+}
+
+/// Returns the locale used for regional formatting, such as numbers and dates.
+///
+/// This is the current locale, which is selected separately from the preferred languages.
+pub(crate) fn get_format_locale() -> Option<String> {
+    let locale = CFLocale::current()?;
 
+    // SAFETY: `locale` is a valid CFLocale. The identifier follows the "Get" rule, so it
+    // is not owned by us and must not be released.
+    let identifier = unsafe { CFLocaleGetIdentifier(locale.0) };
+    if identifier.is_null() {
+        return None;
+    }
+
+    // SAFETY: `identifier` is a valid CFString.
+    let identifier = unsafe { string_from_cfstring(identifier) }?;
+    identifier_to_tag(&identifier)
+}
+
+/// Returns the `AppleLocale` preference of the global domain, which is the region and formats
+/// picked in System Settings, or the current locale if it isn't set.
+pub(crate) fn get_default_locale() -> Option<String> {
+    global_locale()
+        .and_then(|identifier| identifier_to_tag(&identifier))
+        .or_else(get_format_locale)
+}
+
+fn global_locale() -> Option<String> {
+    let key = CFString::new("AppleLocale")?;
+
+    // SAFETY: `key` and the application ID are valid CFStrings. Any returned value will be
+    // owned by us.
+    let value = unsafe { CFPreferencesCopyAppValue(key.0, kCFPreferencesAnyApplication) };
+    if value.is_null() {
+        return None;
+    }
+
+    // SAFETY: `value` is a valid CF object owned by us, which is released once it's been
+    // copied out of if it's a CFString.
     #[allow(clippy::as_conversions)]
     unsafe {
-        // SAFETY: The array has been checked that it contains at least one value.
-        let locale = CFArrayGetValueAtIndex(preferred_langs.0, 0) as CFStringRef;
+        let identifier = if CFGetTypeID(value) == CFStringGetTypeID() {
+            string_from_cfstring(value as CFStringRef)
+        } else {
+            None
+        };
+        CFRelease(value);
+        identifier
+    }
+}
 
-        // SAFETY: `locale` is a valid CFString pointer because the array will always contain a value.
-        let str_len = CFStringGetLength(locale);
+/// Returns the same locales as [`get`], with the Unicode extension of the current locale added
+/// to each, such as `-u-ca-buddhist-hc-h23`.
+///
+/// The current locale carries the user's preferences which apply to every language, such as
+/// the calendar and hour cycle, as keywords of its identifier. Locales which already have a
+/// Unicode extension are returned unchanged.
+pub(crate) fn get_with_extensions() -> impl Iterator<Item = String> {
+    let extension = get_format_locale().and_then(|tag| unicode_extension(&tag).map(String::from));
+    get().map(move |tag| with_extension(tag, extension.as_deref()))
+}
 
-        let range = CFRange {
-            location: 0,
-            length: str_len,
-        };
+/// Returns the Unicode extension of a language tag, such as `u-ca-buddhist` in
+/// `th-TH-u-ca-buddhist`, without any other extensions or private use subtags which follow it.
+fn unicode_extension(tag: &str) -> Option<&str> {
+    let mut start = None;
+    let mut offset = 0;
+    for subtag in tag.split('-') {
+        // Every extension starts with a singleton, which also ends the previous extension.
+        if subtag.len() == 1 {
+            if let Some(start) = start {
+                return Some(&tag[start..offset - 1]);
+            }
+            if subtag.eq_ignore_ascii_case("x") {
+                return None;
+            }
+            if subtag.eq_ignore_ascii_case("u") {
+                start = Some(offset);
+            }
+        }
+        offset += subtag.len() + 1;
+    }
+    start.map(|start| &tag[start..])
+}
 
-        let mut capacity = 0;
-        // SAFETY:
-        // - `locale` is a valid CFString
-        // - The supplied range is within the length of the string.
-        // - `capacity` is writable.
-        // Passing NULL and `0` is correct for the buffer to get the
-        // encoded output length.
-        CFStringGetBytes(
-            locale,
-            range,
-            kCFStringEncodingUTF8,
-            0,
-            false as Boolean,
-            core::ptr::null_mut(),
-            0,
-            &mut capacity,
-        );
-
-        // Guard against a zero-sized allocation, if that were to somehow occur.
-        if capacity == 0 {
-            return None;
+/// Adds a Unicode extension, such as `u-hc-h23`, to `tag`, unless it already has one.
+fn with_extension(tag: String, extension: Option<&str>) -> String {
+    match extension {
+        Some(extension) if unicode_extension(&tag).is_none() => {
+            // The Unicode extension is placed before private use subtags, like `-x-foo`.
+            match tag.to_ascii_lowercase().find("-x-") {
+                Some(private_use) => {
+                    let (main, private_use) = tag.split_at(private_use);
+                    [main, "-", extension, private_use].concat()
+                }
+                None => [tag.as_str(), "-", extension].concat(),
+            }
         }
+        _ => tag,
+    }
+}
+
+/// Converts a locale identifier into a BCP-47 language tag, if it is well-formed.
+pub(crate) fn identifier_to_bcp47(identifier: &str) -> Option<String> {
+    identifier_to_tag(identifier).filter(|tag| crate::is_well_formed_bcp47(tag))
+}
+
+/// Converts a locale identifier, such as `zh_Hans_CN` or `en_US@calendar=gregorian`, into a
+/// BCP-47 language tag.
+///
+/// Locale identifiers use underscores, and may contain keywords after an `@`, separated by `;`.
+/// Keywords which have an equivalent Unicode extension, such as `calendar` (`-u-ca-`), are
+/// converted into it, and any others are dropped.
+fn identifier_to_tag(identifier: &str) -> Option<String> {
+    let mut parts = identifier.splitn(2, '@');
+    let name = parts.next().filter(|name| !name.is_empty())?;
+    let mut tag = name
+        .split('_')
+        .filter(|subtag| !subtag.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+
+    let mut keywords = parts
+        .next()
+        .unwrap_or_default()
+        .split(';')
+        .filter_map(|keyword| {
+            let mut keyword = keyword.splitn(2, '=');
+            let key = unicode_key(keyword.next()?.trim())?;
+            let value = unicode_value(key, keyword.next()?.trim())?;
+            Some((key, value))
+        })
+        .collect::<Vec<_>>();
+
+    // The keys of an extension are sorted in canonical tags, and may only appear once.
+    keywords.sort_by_key(|(key, _)| *key);
+    keywords.dedup_by_key(|(key, _)| *key);
+
+    if !keywords.is_empty() {
+        tag.push_str("-u");
+        for (key, value) in keywords {
+            tag.push('-');
+            tag.push_str(key);
+            tag.push('-');
+            tag.push_str(&value);
+        }
+    }
+
+    Some(tag)
+}
+
+/// Returns the Unicode extension key for a locale identifier keyword, such as `ca` for `calendar`.
+fn unicode_key(keyword: &str) -> Option<&'static str> {
+    let key = match keyword.to_ascii_lowercase().as_str() {
+        "calendar" => "ca",
+        "collation" => "co",
+        "currency" => "cu",
+        "fw" => "fw",
+        "hours" => "hc",
+        "measure" => "ms",
+        "numbers" => "nu",
+        "rg" => "rg",
+        "sd" => "sd",
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// Returns the Unicode extension value for the value of a locale identifier keyword, such as
+/// `gregory` for `calendar=gregorian`, if it is well-formed.
+fn unicode_value(key: &str, value: &str) -> Option<String> {
+    let value = value.to_ascii_lowercase();
+    let value = match (key, value.as_str()) {
+        ("ca", "gregorian") => String::from("gregory"),
+        ("ca", "ethiopic-amete-alem") => String::from("ethioaa"),
+        ("co", "dictionary") => String::from("dict"),
+        ("co", "phonebook") => String::from("phonebk"),
+        ("co", "traditional") => String::from("trad"),
+        _ => value,
+    };
+
+    let well_formed = value.split('-').all(|subtag| {
+        (3..=8).contains(&subtag.len()) && subtag.bytes().all(|b| b.is_ascii_alphanumeric())
+    });
+    if well_formed {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+#[cfg(feature = "notify")]
+type Callback = alloc::boxed::Box<dyn Fn() + Send + Sync>;
+
+/// An observer of `kCFLocaleCurrentLocaleDidChangeNotification`, which is removed when dropped.
+#[cfg(feature = "notify")]
+pub(crate) struct Watcher {
+    center: CFNotificationCenterRef,
+    callback: *mut Callback,
+}
+
+// SAFETY: The notification center is thread-safe, and the callback is `Send + Sync`.
+#[cfg(feature = "notify")]
+unsafe impl Send for Watcher {}
+#[cfg(feature = "notify")]
+unsafe impl Sync for Watcher {}
+
+#[cfg(feature = "notify")]
+pub(crate) fn watch(callback: Callback) -> Option<Watcher> {
+    extern "C" fn locale_changed(
+        _center: CFNotificationCenterRef,
+        observer: *mut c_void,
+        _name: CFStringRef,
+        _object: *const c_void,
+        _user_info: CFTypeRef,
+    ) {
+        // SAFETY: The observer is the callback registered in `watch`, which isn't freed
+        // until after the observer has been removed.
+        let callback = unsafe { &*observer.cast::<Callback>() };
+        callback();
+    }
 
-        // Note: This is the number of bytes (u8) that will be written to
-        // the buffer, not the number of codepoints they would contain.
-        let mut buffer = Vec::with_capacity(capacity as usize);
+    // SAFETY: This function is safe to call and has no invariants. The local center
+    // follows the "Get" rule, so it is not owned by us and must not be released.
+    let center = unsafe { CFNotificationCenterGetLocalCenter() };
+    if center.is_null() {
+        return None;
+    }
+
+    let callback = alloc::boxed::Box::into_raw(alloc::boxed::Box::new(callback));
 
-        // SAFETY:
-        // - `locale` is a valid CFString
-        // - The supplied range is within the length of the string.
-        // - `buffer` is writable and has sufficent capacity to receive the data.
-        // - `maxBufLen` is correctly based on `buffer`'s available capacity.
-        // - `out_len` is writable.
-        let mut out_len = 0;
-        CFStringGetBytes(
-            locale,
-            range,
-            kCFStringEncodingUTF8,
-            0,
-            false as Boolean,
-            buffer.as_mut_ptr(),
-            capacity as CFIndex,
-            &mut out_len,
+    // SAFETY: `center` is a valid notification center, the observer pointer stays valid until it's
+    // removed in `drop`, and the notification name is a valid CFString constant.
+    unsafe {
+        CFNotificationCenterAddObserver(
+            center,
+            callback.cast(),
+            locale_changed,
+            kCFLocaleCurrentLocaleDidChangeNotification,
+            core::ptr::null(),
+            CFNotificationSuspensionBehaviorDeliverImmediately,
         );
+    }
 
-        // Sanity check that both calls to `CFStringGetBytes`
-        // were equivalent. If they weren't, the system is doing
-        // something very wrong...
-        assert!(out_len <= capacity);
+    Some(Watcher { center, callback })
+}
+
+#[cfg(feature = "notify")]
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        // SAFETY: The observer was registered with this center for this notification in `watch`.
+        // Once it's removed, the callback is no longer reachable so it can be freed.
+        unsafe {
+            CFNotificationCenterRemoveObserver(
+                self.center,
+                self.callback.cast(),
+                kCFLocaleCurrentLocaleDidChangeNotification,
+                core::ptr::null(),
+            );
+            drop(alloc::boxed::Box::from_raw(self.callback));
+        }
+    }
+}
+
+/// Copies the contents of a `CFString` into an owned UTF-8 `String`.
+///
+/// # Safety
+///
+/// `string` must be a valid, non-null CFString pointer.
+#[allow(clippy::as_conversions)]
+unsafe fn string_from_cfstring(string: CFStringRef) -> Option<String> {
+    let str_len = CFStringGetLength(string);
+
+    let range = CFRange {
+        location: 0,
+        length: str_len,
+    };
 
-        // SAFETY: The system has written `out_len` elements, so they are
-        // initialized and inside the buffer's capacity bounds.
-        buffer.set_len(out_len as usize);
+    let mut capacity = 0;
+    // SAFETY:
+    // - `string` is a valid CFString
+    // - The supplied range is within the length of the string.
+    // - `capacity` is writable.
+    // Passing NULL and `0` is correct for the buffer to get the
+    // encoded output length.
+    CFStringGetBytes(
+        string,
+        range,
+        kCFStringEncodingUTF8,
+        0,
+        false as Boolean,
+        core::ptr::null_mut(),
+        0,
+        &mut capacity,
+    );
 
-        // This should always contain UTF-8 since we told the system to
-        // write UTF-8 into the buffer, but the value is small enough that
-        // using `from_utf8_unchecked` isn't worthwhile.
-        String::from_utf8(buffer).ok()
+    // Guard against a zero-sized allocation, if that were to somehow occur.
+    if capacity == 0 {
+        return None;
     }
+
+    // Note: This is the number of bytes (u8) that will be written to
+    // the buffer, not the number of codepoints they would contain.
+    let mut buffer = Vec::with_capacity(capacity as usize);
+
+    // SAFETY:
+    // - `string` is a valid CFString
+    // - The supplied range is within the length of the string.
+    // - `buffer` is writable and has sufficent capacity to receive the data.
+    // - `maxBufLen` is correctly based on `buffer`'s available capacity.
+    // - `out_len` is writable.
+    let mut out_len = 0;
+    CFStringGetBytes(
+        string,
+        range,
+        kCFStringEncodingUTF8,
+        0,
+        false as Boolean,
+        buffer.as_mut_ptr(),
+        capacity as CFIndex,
+        &mut out_len,
+    );
+
+    // Sanity check that both calls to `CFStringGetBytes`
+    // were equivalent. If they weren't, the system is doing
+    // something very wrong...
+    assert!(out_len <= capacity);
+
+    // SAFETY: The system has written `out_len` elements, so they are
+    // initialized and inside the buffer's capacity bounds.
+    buffer.set_len(out_len as usize);
+
+    // This should always contain UTF-8 since we told the system to
+    // write UTF-8 into the buffer, but the value is small enough that
+    // using `from_utf8_unchecked` isn't worthwhile.
+    String::from_utf8(buffer).ok()
 }
 
 struct CFArray(CFArrayRef);
 
+impl CFArray {
+    /// Takes ownership of a property list value, if it is an array.
+    ///
+    /// # Safety
+    ///
+    /// `value` must be either null or a valid CF object owned by the caller.
+    #[allow(clippy::as_conversions)]
+    unsafe fn from_property_list(value: CFPropertyListRef) -> Option<Self> {
+        if value.is_null() {
+            return None;
+        }
+
+        if CFGetTypeID(value) == CFArrayGetTypeID() {
+            Some(CFArray(value as CFArrayRef))
+        } else {
+            CFRelease(value);
+            None
+        }
+    }
+
+    fn len(&self) -> CFIndex {
+        // SAFETY: This wrapper contains a valid CFArray.
+        unsafe { CFArrayGetCount(self.0) }
+    }
+
+    /// Copies the string at `idx` out of the array, if it is a string.
+    fn string_at(&self, idx: CFIndex) -> Option<String> {
+        if idx < 0 || idx >= self.len() {
+            return None;
+        }
+
+        // SAFETY: `idx` has been checked to be within the bounds of the array. The value
+        // follows the "Get" rule, so it is not owned by us and must not be released.
+        let value = unsafe { CFArrayGetValueAtIndex(self.0, idx) };
+
+        // SAFETY: `value` is a valid CF object, which has been checked to be a CFString.
+        #[allow(clippy::as_conversions)]
+        unsafe {
+            if value.is_null() || CFGetTypeID(value) != CFStringGetTypeID() {
+                return None;
+            }
+            string_from_cfstring(value as CFStringRef)
+        }
+    }
+}
+
 impl Drop for CFArray {
     fn drop(&mut self) {
         // SAFETY: This wrapper contains a valid CFArray.
         unsafe { CFRelease(self.0.cast()) }
     }
 }
+
+struct CFLocale(CFLocaleRef);
+
+impl CFLocale {
+    /// Returns the current locale of the user.
+    fn current() -> Option<Self> {
+        // SAFETY: This function is safe to call and has no invariants. The returned
+        // locale will be owned by us.
+        let locale = unsafe { CFLocaleCopyCurrent() };
+        if locale.is_null() {
+            None
+        } else {
+            Some(CFLocale(locale))
+        }
+    }
+
+    /// Creates the locale with an identifier, such as `en_US`.
+    fn new(identifier: &str) -> Option<Self> {
+        let identifier = CFString::new(identifier)?;
+
+        // SAFETY: `identifier` is a valid CFString. The returned locale will be owned by us.
+        let locale = unsafe { CFLocaleCreate(core::ptr::null(), identifier.0) };
+        if locale.is_null() {
+            None
+        } else {
+            Some(CFLocale(locale))
+        }
+    }
+
+    /// Copies the value of a locale property, such as `kCFLocaleCountryCode`, if it is a string.
+    fn string_value(&self, key: CFStringRef) -> Option<String> {
+        // SAFETY: This wrapper contains a valid CFLocale and the key is a valid CFString.
+        // The returned value follows the "Get" rule, so it is not owned by us and must not be released.
+        let value = unsafe { CFLocaleGetValue(self.0, key) };
+
+        // SAFETY: `value` is a valid CF object, which has been checked to be a CFString.
+        #[allow(clippy::as_conversions)]
+        unsafe {
+            if value.is_null() || CFGetTypeID(value) != CFStringGetTypeID() {
+                return None;
+            }
+            string_from_cfstring(value as CFStringRef)
+        }
+    }
+}
+
+impl Drop for CFLocale {
+    fn drop(&mut self) {
+        // SAFETY: This wrapper contains a valid CFLocale.
+        unsafe { CFRelease(self.0.cast()) }
+    }
+}
+
+struct CFNumberFormatter(CFNumberFormatterRef);
+
+impl CFNumberFormatter {
+    /// Creates a formatter for `locale` with a style, such as `kCFNumberFormatterCurrencyStyle`.
+    fn new(locale: &CFLocale, style: CFNumberFormatterStyle) -> Option<Self> {
+        // SAFETY: `locale` is a valid CFLocale. The returned formatter will be owned by us.
+        let formatter = unsafe { CFNumberFormatterCreate(core::ptr::null(), locale.0, style) };
+        if formatter.is_null() {
+            None
+        } else {
+            Some(CFNumberFormatter(formatter))
+        }
+    }
+
+    /// Copies the ICU pattern which the formatter uses, such as `#,##0%`.
+    fn pattern(&self) -> Option<String> {
+        // SAFETY: This wrapper contains a valid CFNumberFormatter. The format follows the "Get"
+        // rule, so it is not owned by us and must not be released.
+        let pattern = unsafe { CFNumberFormatterGetFormat(self.0) };
+        if pattern.is_null() {
+            return None;
+        }
+
+        // SAFETY: `pattern` is a valid CFString, which remains valid while `self` is alive.
+        unsafe { string_from_cfstring(pattern) }
+    }
+
+    /// Copies the value of a formatter property, such as `kCFNumberFormatterPercentSymbol`, if it
+    /// is a string.
+    fn string_property(&self, key: CFStringRef) -> Option<String> {
+        // SAFETY: This wrapper contains a valid CFNumberFormatter and the key is a valid CFString.
+        // The returned value will be owned by us.
+        let value = unsafe { CFNumberFormatterCopyProperty(self.0, key) };
+        if value.is_null() {
+            return None;
+        }
+
+        // SAFETY: `value` is a valid CF object owned by us, which has been checked to be a
+        // CFString before it is read.
+        #[allow(clippy::as_conversions)]
+        unsafe {
+            let string = if CFGetTypeID(value) == CFStringGetTypeID() {
+                string_from_cfstring(value as CFStringRef)
+            } else {
+                None
+            };
+            CFRelease(value);
+            string
+        }
+    }
+}
+
+impl Drop for CFNumberFormatter {
+    fn drop(&mut self) {
+        // SAFETY: This wrapper contains a valid CFNumberFormatter.
+        unsafe { CFRelease(self.0.cast()) }
+    }
+}
+
+struct CFCalendar(CFCalendarRef);
+
+impl CFCalendar {
+    /// Returns the current calendar of the user.
+    fn current() -> Option<Self> {
+        // SAFETY: This function is safe to call and has no invariants. The returned
+        // calendar will be owned by us.
+        let calendar = unsafe { CFCalendarCopyCurrent() };
+        if calendar.is_null() {
+            None
+        } else {
+            Some(CFCalendar(calendar))
+        }
+    }
+}
+
+impl Drop for CFCalendar {
+    fn drop(&mut self) {
+        // SAFETY: This wrapper contains a valid CFCalendar.
+        unsafe { CFRelease(self.0.cast()) }
+    }
+}
+
+struct CFString(CFStringRef);
+
+impl CFString {
+    #[allow(clippy::as_conversions)]
+    fn new(string: &str) -> Option<Self> {
+        // SAFETY: `string` is valid to read from for its length in bytes, and is UTF-8.
+        // The returned string will be owned by us.
+        let string = unsafe {
+            CFStringCreateWithBytes(
+                core::ptr::null(),
+                string.as_ptr(),
+                string.len() as CFIndex,
+                kCFStringEncodingUTF8,
+                false as Boolean,
+            )
+        };
+
+        if string.is_null() {
+            None
+        } else {
+            Some(CFString(string))
+        }
+    }
+}
+
+impl Drop for CFString {
+    fn drop(&mut self) {
+        // SAFETY: This wrapper contains a valid CFString.
+        unsafe { CFRelease(self.0.cast()) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::identifier_to_tag;
+
+    #[test]
+    fn language_sources() {
+        use super::first_languages;
+        use crate::LocaleSource;
+
+        let app = || Some("fr");
+        let global = || Some("de");
+        let none = || None;
+
+        assert_eq!(
+            first_languages(app, global, none),
+            (Some("fr"), LocaleSource::Application)
+        );
+        // Languages which are only set globally aren't the app's own.
+        assert_eq!(
+            first_languages(none, global, || Some("en")),
+            (Some("de"), LocaleSource::System)
+        );
+        assert_eq!(
+            first_languages(none, none, || Some("en")),
+            (Some("en"), LocaleSource::System)
+        );
+        assert_eq!(
+            first_languages::<&str>(none, none, none),
+            (None, LocaleSource::System)
+        );
+    }
+
+    #[test]
+    fn currency_symbol_position() {
+        use super::{currency_format, CFLocale};
+        use crate::CurrencySymbolPosition;
+
+        let position = |identifier: &str| {
+            let locale = CFLocale::new(identifier).unwrap();
+            currency_format(&locale).unwrap().symbol_position
+        };
+
+        assert_eq!(position("en_US"), CurrencySymbolPosition::Before);
+        assert_eq!(position("ja_JP"), CurrencySymbolPosition::Before);
+        assert_eq!(position("de_DE"), CurrencySymbolPosition::After);
+        assert_eq!(position("fr_FR"), CurrencySymbolPosition::After);
+    }
+
+    #[test]
+    fn percent_symbols() {
+        use super::{percent_symbol, CFLocale};
+
+        let symbol =
+            |identifier: &str| percent_symbol(&CFLocale::new(identifier).unwrap()).unwrap();
+
+        let en = symbol("en_US");
+        assert_eq!(en.symbol, "%");
+        assert_eq!(en.per_mille.as_deref(), Some("‰"));
+        assert_eq!(en.space_separated, Some(false));
+        assert_eq!(symbol("fr_FR").space_separated, Some(true));
+    }
+
+    #[test]
+    fn sign_symbols() {
+        use super::{sign_symbols, CFLocale};
+
+        let signs = |identifier: &str| sign_symbols(&CFLocale::new(identifier).unwrap()).unwrap();
+
+        let en = signs("en_US");
+        assert_eq!(en.positive, "+");
+        assert_eq!(en.negative, "-");
+        assert_eq!(signs("sv_SE").negative, "\u{2212}");
+    }
+
+    #[test]
+    fn display_names() {
+        use super::display_name_of;
+
+        assert_eq!(
+            display_name_of("fr-CA", Some("fr")).as_deref(),
+            Some("français (Canada)")
+        );
+        assert_eq!(
+            display_name_of("fr-CA", Some("en_US")).as_deref(),
+            Some("French (Canada)")
+        );
+        assert!(!display_name_of("de-DE", None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn native_names() {
+        use super::native_name_of;
+
+        assert_eq!(native_name_of("de").as_deref(), Some("Deutsch"));
+        assert_eq!(native_name_of("ja").as_deref(), Some("日本語"));
+    }
+
+    #[test]
+    fn identifier_underscores() {
+        assert_eq!(identifier_to_tag("en_US").as_deref(), Some("en-US"));
+        assert_eq!(
+            identifier_to_tag("zh_Hans_CN").as_deref(),
+            Some("zh-Hans-CN")
+        );
+        assert_eq!(identifier_to_tag("fr").as_deref(), Some("fr"));
+        assert_eq!(identifier_to_tag(""), None);
+        assert_eq!(identifier_to_tag("@calendar=gregorian"), None);
+    }
+
+    #[test]
+    fn hour_cycle_and_calendar_keywords() {
+        assert_eq!(
+            identifier_to_tag("en_US@hours=h24;calendar=gregorian").as_deref(),
+            Some("en-US-u-ca-gregory-hc-h24")
+        );
+        assert_eq!(
+            identifier_to_tag("ja_JP@calendar=japanese").as_deref(),
+            Some("ja-JP-u-ca-japanese")
+        );
+        assert_eq!(
+            identifier_to_tag("en_GB@hours=h12").as_deref(),
+            Some("en-GB-u-hc-h12")
+        );
+    }
+
+    #[test]
+    fn unicode_extensions() {
+        use super::{unicode_extension, with_extension, String};
+
+        assert_eq!(
+            unicode_extension("en-US-u-ca-gregory-hc-h24"),
+            Some("u-ca-gregory-hc-h24")
+        );
+        assert_eq!(
+            unicode_extension("th-TH-u-ca-buddhist-x-foo"),
+            Some("u-ca-buddhist")
+        );
+        assert_eq!(
+            unicode_extension("de-DE-t-en-u-co-phonebk"),
+            Some("u-co-phonebk")
+        );
+        assert_eq!(unicode_extension("en-US"), None);
+        assert_eq!(unicode_extension("en-x-u-foo"), None);
+
+        let extension = Some("u-ca-buddhist-hc-h23");
+        assert_eq!(
+            with_extension(String::from("en-US"), extension),
+            "en-US-u-ca-buddhist-hc-h23"
+        );
+        assert_eq!(
+            with_extension(String::from("fr-x-priv"), extension),
+            "fr-u-ca-buddhist-hc-h23-x-priv"
+        );
+        // Locales which already have their own Unicode extension keep it.
+        assert_eq!(
+            with_extension(String::from("ja-JP-u-ca-japanese"), extension),
+            "ja-JP-u-ca-japanese"
+        );
+        assert_eq!(with_extension(String::from("de"), None), "de");
+    }
+
+    #[test]
+    fn available_locales() {
+        use super::is_locale_available;
+
+        assert_eq!(is_locale_available("en-US"), Some(true));
+        assert_eq!(is_locale_available("en"), Some(true));
+        assert_eq!(is_locale_available("tlh-XX"), Some(false));
+    }
+
+    #[test]
+    fn available_locale_list() {
+        let locales = super::get_available_locales();
+        assert!(locales.iter().any(|locale| locale == "en-US"));
+        assert!(locales
+            .iter()
+            .all(|locale| crate::is_well_formed_bcp47(locale)));
+    }
+
+    #[test]
+    fn identifier_validation() {
+        use super::identifier_to_bcp47;
+
+        assert_eq!(identifier_to_bcp47("en_GB").as_deref(), Some("en-GB"));
+        assert_eq!(
+            identifier_to_bcp47("th_TH@calendar=buddhist").as_deref(),
+            Some("th-TH-u-ca-buddhist")
+        );
+        assert_eq!(identifier_to_bcp47("not an identifier"), None);
+        assert_eq!(identifier_to_bcp47("en_US.UTF-8"), None);
+    }
+
+    #[test]
+    fn identifier_keywords() {
+        assert_eq!(
+            identifier_to_tag("en_US@calendar=gregorian").as_deref(),
+            Some("en-US-u-ca-gregory")
+        );
+        assert_eq!(
+            identifier_to_tag("de_DE@currency=EUR;calendar=buddhist").as_deref(),
+            Some("de-DE-u-ca-buddhist-cu-eur")
+        );
+        assert_eq!(
+            identifier_to_tag("en_US@rg=gbzzzz").as_deref(),
+            Some("en-US-u-rg-gbzzzz")
+        );
+
+        // Keywords without an equivalent extension, or with malformed values, are dropped.
+        assert_eq!(
+            identifier_to_tag("ja_JP@unknown=value").as_deref(),
+            Some("ja-JP")
+        );
+        assert_eq!(
+            identifier_to_tag("en_US@calendar=x;numbers").as_deref(),
+            Some("en-US")
+        );
+    }
+}
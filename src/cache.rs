@@ -0,0 +1,60 @@
+//! Caching of the locales for applications which treat them as constant for a session.
+use alloc::{string::String, vec::Vec};
+use std::sync::{PoisonError, RwLock};
+
+static CACHE: RwLock<Option<Vec<String>>> = RwLock::new(None);
+
+/// Returns the active locale for the system or application, reading it from the platform
+/// only the first time it's needed.
+///
+/// This is equivalent to the first entry of [`get_locales_cached`]. The cached value is kept
+/// until [`invalidate_locale_cache`] is called.
+///
+/// This requires the `cache` feature.
+pub fn get_locale_cached() -> Option<String> {
+    with_cache(|locales| locales.first().cloned())
+}
+
+/// Returns the preferred locales for the system or application, in descending order of
+/// preference, reading them from the platform only the first time they're needed.
+///
+/// The locales are obtained with [`get_locales`](crate::get_locales), and kept until
+/// [`invalidate_locale_cache`] is called. Changing the [`LocaleProvider`](crate::LocaleProvider)
+/// or `SYS_LOCALE_OVERRIDE` doesn't affect the cached locales until then.
+///
+/// This requires the `cache` feature.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_locales_cached;
+///
+/// for locale in get_locales_cached() {
+///     println!("{}", locale);
+/// }
+/// ```
+pub fn get_locales_cached() -> Vec<String> {
+    with_cache(<[String]>::to_vec)
+}
+
+/// Discards the cached locales, so that the next call to [`get_locale_cached`] or
+/// [`get_locales_cached`] reads them from the platform again.
+///
+/// This is useful after being notified that the locale has changed, such as by `on_locale_change`.
+///
+/// This requires the `cache` feature.
+pub fn invalidate_locale_cache() {
+    *CACHE.write().unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+/// Calls `f` with the cached locales, first reading them if they aren't cached yet.
+fn with_cache<T>(f: impl FnOnce(&[String]) -> T) -> T {
+    if let Some(locales) = &*CACHE.read().unwrap_or_else(PoisonError::into_inner) {
+        return f(locales);
+    }
+
+    // Another thread may have read the locales while this one waited for the lock,
+    // in which case they're used as-is.
+    let mut cache = CACHE.write().unwrap_or_else(PoisonError::into_inner);
+    f(cache.get_or_insert_with(|| crate::get_locales().collect()))
+}
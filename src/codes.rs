@@ -0,0 +1,198 @@
+//! Curated tables of ISO 639 language codes, for converting between their two and three-letter
+//! forms and replacing deprecated codes without any platform support.
+
+/// The length of the language codes which [`normalize_language_code`](crate::normalize_language_code)
+/// converts a tag's language into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CodeLength {
+    /// The two-letter codes of ISO 639-1, such as `de`, which BCP-47 prefers.
+    Two,
+    /// The three-letter codes of ISO 639-2 and ISO 639-3, such as `deu`.
+    ///
+    /// The terminology codes of ISO 639-2 are used, rather than the bibliographic ones such as
+    /// `ger`, since they're the ones shared with ISO 639-3.
+    Three,
+}
+
+/// The ISO 639-1 code of each language along with its ISO 639-2 terminology code, and its
+/// bibliographic code if it has a different one, sorted by the ISO 639-1 code.
+///
+/// This covers the most widely used languages, rather than the whole of ISO 639-1.
+const LANGUAGES: &[(&str, &str, Option<&str>)] = &[
+    ("af", "afr", None),
+    ("am", "amh", None),
+    ("ar", "ara", None),
+    ("az", "aze", None),
+    ("be", "bel", None),
+    ("bg", "bul", None),
+    ("bn", "ben", None),
+    ("bs", "bos", None),
+    ("ca", "cat", None),
+    ("cs", "ces", Some("cze")),
+    ("cy", "cym", Some("wel")),
+    ("da", "dan", None),
+    ("de", "deu", Some("ger")),
+    ("el", "ell", Some("gre")),
+    ("en", "eng", None),
+    ("es", "spa", None),
+    ("et", "est", None),
+    ("eu", "eus", Some("baq")),
+    ("fa", "fas", Some("per")),
+    ("fi", "fin", None),
+    ("fr", "fra", Some("fre")),
+    ("ga", "gle", None),
+    ("gl", "glg", None),
+    ("gu", "guj", None),
+    ("ha", "hau", None),
+    ("he", "heb", None),
+    ("hi", "hin", None),
+    ("hr", "hrv", None),
+    ("hu", "hun", None),
+    ("hy", "hye", Some("arm")),
+    ("id", "ind", None),
+    ("ig", "ibo", None),
+    ("is", "isl", Some("ice")),
+    ("it", "ita", None),
+    ("ja", "jpn", None),
+    ("jv", "jav", None),
+    ("ka", "kat", Some("geo")),
+    ("kk", "kaz", None),
+    ("km", "khm", None),
+    ("kn", "kan", None),
+    ("ko", "kor", None),
+    ("ky", "kir", None),
+    ("lo", "lao", None),
+    ("lt", "lit", None),
+    ("lv", "lav", None),
+    ("mk", "mkd", Some("mac")),
+    ("ml", "mal", None),
+    ("mn", "mon", None),
+    ("mr", "mar", None),
+    ("ms", "msa", Some("may")),
+    ("my", "mya", Some("bur")),
+    ("nb", "nob", None),
+    ("ne", "nep", None),
+    ("nl", "nld", Some("dut")),
+    ("nn", "nno", None),
+    ("no", "nor", None),
+    ("pa", "pan", None),
+    ("pl", "pol", None),
+    ("ps", "pus", None),
+    ("pt", "por", None),
+    ("ro", "ron", Some("rum")),
+    ("ru", "rus", None),
+    ("si", "sin", None),
+    ("sk", "slk", Some("slo")),
+    ("sl", "slv", None),
+    ("so", "som", None),
+    ("sq", "sqi", Some("alb")),
+    ("sr", "srp", None),
+    ("sv", "swe", None),
+    ("sw", "swa", None),
+    ("ta", "tam", None),
+    ("te", "tel", None),
+    ("th", "tha", None),
+    ("tr", "tur", None),
+    ("uk", "ukr", None),
+    ("ur", "urd", None),
+    ("uz", "uzb", None),
+    ("vi", "vie", None),
+    ("yo", "yor", None),
+    ("zh", "zho", Some("chi")),
+    ("zu", "zul", None),
+];
+
+/// The deprecated language codes which are still used by some platforms, such as Java's `iw`,
+/// along with the language and any script which replace them, following the CLDR aliases.
+const DEPRECATED_LANGUAGES: &[(&str, &str, Option<&str>)] = &[
+    ("in", "id", None),
+    ("iw", "he", None),
+    ("ji", "yi", None),
+    ("jw", "jv", None),
+    ("mo", "ro", None),
+    ("no", "nb", None),
+    ("sh", "sr", Some("Latn")),
+    ("tl", "fil", None),
+];
+
+/// The deprecated region codes of countries which were renamed or merged, along with the ones
+/// which replace them. Those which were split into several countries, such as `YU`, aren't
+/// replaced, since it's unknown which of them is meant.
+const DEPRECATED_REGIONS: &[(&str, &str)] = &[
+    ("BU", "MM"),
+    ("DD", "DE"),
+    ("FX", "FR"),
+    ("TP", "TL"),
+    ("YD", "YE"),
+    ("ZR", "CD"),
+];
+
+/// Returns the language and any script which replace a deprecated language code, which must be
+/// in lowercase, or `None` if it isn't deprecated.
+pub(crate) fn preferred_language(language: &str) -> Option<(&'static str, Option<&'static str>)> {
+    DEPRECATED_LANGUAGES
+        .iter()
+        .find(|&&(deprecated, _, _)| deprecated == language)
+        .map(|&(_, preferred, script)| (preferred, script))
+}
+
+/// Returns the region which replaces a deprecated region code, which must be in uppercase, or
+/// `None` if it isn't deprecated.
+pub(crate) fn preferred_region(region: &str) -> Option<&'static str> {
+    DEPRECATED_REGIONS
+        .iter()
+        .find(|&&(deprecated, _)| deprecated == region)
+        .map(|&(_, preferred)| preferred)
+}
+
+/// Returns the code of a language with the given length, which must be in lowercase.
+///
+/// Three-letter codes may be either the terminology or bibliographic codes of ISO 639-2. Returns
+/// `None` if the language isn't one of those which are covered.
+pub(crate) fn convert(language: &str, length: CodeLength) -> Option<&'static str> {
+    let &(two, three, _) = if language.len() == 2 {
+        let index = LANGUAGES
+            .binary_search_by_key(&language, |&(two, _, _)| two)
+            .ok()?;
+        &LANGUAGES[index]
+    } else {
+        LANGUAGES.iter().find(|&&(_, three, bibliographic)| {
+            three == language || bibliographic == Some(language)
+        })?
+    };
+
+    match length {
+        CodeLength::Two => Some(two),
+        CodeLength::Three => Some(three),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{convert, preferred_language, preferred_region, CodeLength, LANGUAGES};
+
+    #[test]
+    fn languages_sorted() {
+        assert!(LANGUAGES.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(convert("de", CodeLength::Three), Some("deu"));
+        assert_eq!(convert("deu", CodeLength::Two), Some("de"));
+        assert_eq!(convert("ger", CodeLength::Two), Some("de"));
+        assert_eq!(convert("ger", CodeLength::Three), Some("deu"));
+        assert_eq!(convert("de", CodeLength::Two), Some("de"));
+        assert_eq!(convert("haw", CodeLength::Two), None);
+        assert_eq!(convert("xx", CodeLength::Three), None);
+    }
+
+    #[test]
+    fn deprecated_codes() {
+        assert_eq!(preferred_language("iw"), Some(("he", None)));
+        assert_eq!(preferred_language("sh"), Some(("sr", Some("Latn"))));
+        assert_eq!(preferred_language("he"), None);
+        assert_eq!(preferred_region("BU"), Some("MM"));
+        assert_eq!(preferred_region("YU"), None);
+    }
+}
@@ -0,0 +1,47 @@
+//! Errors for when the locale couldn't be obtained.
+use core::fmt;
+
+/// The reason why the locale preferences couldn't be obtained.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LocaleError {
+    /// The platform isn't supported, or doesn't provide any way to read the locale.
+    ///
+    /// For example, this is returned for WebAssembly outside of a web browser, or when
+    /// the `js` feature is disabled.
+    Unsupported,
+    /// The platform API used to read the locale failed.
+    PlatformError {
+        /// The raw OS error code, such as the result of `GetLastError` on Windows.
+        ///
+        /// This can be converted into a [`std::io::Error`] with
+        /// [`std::io::Error::from_raw_os_error`].
+        code: i32,
+    },
+    /// The locale was read successfully, but the user doesn't have any locale set.
+    Empty,
+    /// The platform didn't provide the locale within the time allowed by
+    /// `try_get_locales_timeout`.
+    Timeout,
+}
+
+impl fmt::Display for LocaleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LocaleError::Unsupported => {
+                f.write_str("reading the locale isn't supported on this platform")
+            }
+            LocaleError::PlatformError { code } => {
+                write!(
+                    f,
+                    "the platform failed to provide the locale (os error {})",
+                    code
+                )
+            }
+            LocaleError::Empty => f.write_str("no locale is set"),
+            LocaleError::Timeout => f.write_str("the locale wasn't obtained in time"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LocaleError {}
@@ -0,0 +1,85 @@
+//! Collecting the locales into a `heapless::Vec`, whose capacity is fixed when it's compiled.
+use alloc::string::String;
+use heapless::Vec;
+
+/// Returns the preferred locales for the system or application, in descending order of
+/// preference, in a `heapless::Vec` which holds up to `N` locales without allocating.
+///
+/// This returns the same locales as [`get_locales`](crate::get_locales), up to the capacity of
+/// the list. If there are more than `N` locales, only the `N` most preferred are kept, and the
+/// rest are dropped, so `get_locales_fixed::<1>()` returns the same locale as
+/// [`get_locale`](crate::get_locale). Duplicates are removed before the list is truncated, so they
+/// don't take up its capacity. Once the list is full, the remaining locales aren't canonicalized.
+/// Each locale is still allocated as its own `String`.
+///
+/// This requires the `heapless` feature, and Rust 1.56 or newer.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_locales_fixed;
+///
+/// let locales = get_locales_fixed::<4>();
+/// println!("The preferred locales are {:?}", locales);
+/// ```
+pub fn get_locales_fixed<const N: usize>() -> Vec<String, N> {
+    let mut out = Vec::new();
+    if !out.is_full() {
+        crate::for_each_locale(|locale| push_distinct(&mut out, locale));
+    }
+    out
+}
+
+/// Adds a locale to `out` unless it's already there, ignoring its casing, or `out` is full.
+/// Returns whether `out` has room for more locales.
+fn push_distinct<const N: usize>(out: &mut Vec<String, N>, locale: String) -> bool {
+    if !out.iter().any(|seen| seen.eq_ignore_ascii_case(&locale)) {
+        // Once the list is full, the less preferred locales are dropped.
+        let _ = out.push(locale);
+    }
+    !out.is_full()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{push_distinct, Vec};
+    use alloc::string::String;
+
+    fn fixed<const N: usize>(locales: &[&str]) -> Vec<String, N> {
+        let mut out = Vec::new();
+        for locale in locales {
+            if !push_distinct(&mut out, String::from(*locale)) {
+                break;
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn truncated() {
+        let locales = fixed::<2>(&["en-US", "fr", "de"]);
+        assert_eq!(locales.as_slice(), ["en-US", "fr"]);
+        assert!(locales.is_full());
+
+        // Exactly as many locales as the capacity are all kept.
+        let locales = fixed::<3>(&["en-US", "fr", "de"]);
+        assert_eq!(locales.as_slice(), ["en-US", "fr", "de"]);
+
+        // Duplicates don't take up the capacity.
+        let locales = fixed::<2>(&["en-US", "EN-us", "fr", "de"]);
+        assert_eq!(locales.as_slice(), ["en-US", "fr"]);
+
+        assert!(fixed::<0>(&["en-US"]).is_empty());
+        assert!(fixed::<2>(&[]).is_empty());
+    }
+
+    #[test]
+    fn stops_when_full() {
+        let mut out = Vec::<String, 2>::new();
+        assert!(push_distinct(&mut out, String::from("en-US")));
+        // A duplicate doesn't fill the list.
+        assert!(push_distinct(&mut out, String::from("en-us")));
+        assert!(!push_distinct(&mut out, String::from("fr")));
+        assert_eq!(out.as_slice(), ["en-US", "fr"]);
+    }
+}
@@ -5,56 +5,456 @@
 //! - iOS
 //! - macOS
 //! - Linux, BSD, and other UNIX variations
-//! - WebAssembly on the web (via the `js` feature)
+//! - WebAssembly in web browsers, Node.js, and Deno (via the `js` feature), or only in web
+//!   browsers (via the `web-sys` feature)
 //! - Windows
+//!
+//! # Features
+//!
+//! - `env-override` (enabled by default): Allows the locales returned by [`get_locales`] to be
+//!   overridden with the `SYS_LOCALE_OVERRIDE` environment variable. Reading it requires the
+//!   standard library, so it only has an effect on Linux, BSD, and other UNIX variations, which
+//!   always link it, or with the `std` feature. Elsewhere, the crate stays usable without the
+//!   standard library with the default features.
+//! - `android-jni`: Returns every preferred locale on Android, rather than only the primary one,
+//!   as well as the languages which the user picked for the running app on Android 13 and above.
+//!   These are read through JNI from Android 12, whose Java VM can be found by any native code.
+//!   In a process with a Java VM, the app's `Context` must be provided by `ndk-context`, as is
+//!   done by `android-activity`, or reading the locales panics. Without a VM, such as in a plain
+//!   native binary or before Android 12, the system locale property is used as it is without the
+//!   feature. The app's configuration is read again every time, so a
+//!   language which the user changed while the app is running is returned by the next call. If
+//!   the configuration has no locales, such as in a process without an `Activity`, the Java VM's
+//!   default locale from `java.util.Locale.getDefault()` is used instead.
+//! - `cache`: Enables [`get_locale_cached`] and [`get_locales_cached`], which only read the
+//!   locales from the platform once until [`invalidate_locale_cache`] is called. This requires
+//!   Rust 1.63 or newer.
+//! - `mock`: Enables [`set_mock_locales`] and [`clear_mock_locales`], which replace the locales
+//!   returned by [`get_locales`] on the current thread, for testing code that depends on them.
+//!   This requires Rust 1.59 or newer.
+//! - `js`: Enables support for WebAssembly running inside of a web browser, Node.js, or Deno.
+//!   With the `std` feature, this also enables `set_js_global`, for reading the locales from
+//!   another object than `globalThis`, such as in tests or non-standard embeddings.
+//! - `web-sys`: Enables support for WebAssembly running inside of a web browser window, through
+//!   the `web-sys` bindings instead of `js-sys`. This is lighter if `web-sys` is already used, but
+//!   doesn't support web workers or other runtimes. It's ignored if `js` is also enabled.
+//! - `maximize`: On WebAssembly, adds the likely script and region to each locale with
+//!   `Intl.Locale.prototype.maximize`, so that `zh-TW` is returned as `zh-Hant-TW`. Without it,
+//!   the locales are returned as the browser reports them.
+//! - `prefer-document-lang`: On WebAssembly, returns the `lang` attribute of the document's root
+//!   element, such as `<html lang="de">`, before the browser's preferred languages, so that a
+//!   site which forces the language of its content gets the same locale for its formatting. It's
+//!   skipped if it's empty or missing, such as in web workers and outside of browsers.
+//! - `icu_locale_core`: Enables [`get_langid`] and [`get_langids`], which return the locales as
+//!   ICU4X's `LanguageIdentifier`, for passing them to crates which expect one. This requires Rust
+//!   1.88 or newer.
+//! - `smallvec`: Enables [`get_locales_small`], which returns the locales in a `SmallVec` that
+//!   doesn't allocate for up to two locales.
+//! - `heapless`: Enables [`get_locales_fixed`], which returns up to a fixed number of locales in
+//!   a `heapless::Vec`, so that the list itself is never allocated, such as on embedded targets.
+//!   This requires Rust 1.56 or newer.
+//! - `serde`: Implements `Serialize` and `Deserialize` for [`Locale`], using its BCP-47 language tag.
+//! - `desktop`: On Linux and other UNIX variations, reads the locales picked in the settings of
+//!   KDE Plasma, and the formats picked in the settings of GNOME, before the environment
+//!   variables. This is best-effort, and GNOME's settings are read by running `gsettings` each
+//!   time, so it's only worth enabling for graphical apps which may not be started by the
+//!   desktop's session, such as by a display manager.
+//! - `live-c-locale`: On Linux, reads the locale which the process has already picked for
+//!   messages with `setlocale`, such as by calling `setlocale(LC_ALL, "")` like GTK and Qt apps
+//!   do, before the environment variables. This reflects what the app initialized, even if its
+//!   environment has changed since. The locale is only queried and never changed, and it's
+//!   ignored while it's still the `C` locale, which it is until the app calls `setlocale`.
+//! - `no-env`: Never reads the locale from environment variables, which may be controlled by
+//!   whoever starts the process, such as the user running a setuid program or a client of a
+//!   service which passes its environment through. On Linux and other UNIX variations, the
+//!   locale is only read from the system's configuration in `/etc/locale.conf` or
+//!   `/etc/default/locale`, and the `desktop` feature has no effect since the desktop is found
+//!   through `XDG_CURRENT_DESKTOP`. `SYS_LOCALE_OVERRIDE` is also ignored, even if `env-override`
+//!   is enabled.
+//! - `test-stub`: Replaces the platform's provider on every target with one which returns
+//!   the comma-separated locales in the `SYS_LOCALE_TEST_STUB` environment variable when the
+//!   crate is compiled, or `en-US` if it isn't set, so that tests behave the same on every
+//!   machine. The other settings, such as [`get_region`], are derived from the first locale.
+//!   Functions which are specific to a platform, such as `get_user_locale` on Windows, still
+//!   read the platform's settings. This is only intended for tests, such as by enabling it in
+//!   `dev-dependencies`.
+//! - `time-zone`: Enables `get_time_zone`, which returns the IANA name of the user's time zone
+//!   alongside their locale. On Windows, this embeds a table which converts the names of the
+//!   Windows time zones into IANA names.
+//! - `notify`: Enables [`on_locale_change`] for being notified when the user changes their locale.
+//! - `log`: Logs how the locales were obtained at the debug level with the `log` crate, for
+//!   diagnosing why an unexpected locale was returned. This includes any override which was used,
+//!   the values read from the platform, such as the environment variables on UNIX systems, and
+//!   the locales returned by [`get_locales`]. Nothing other than locale preferences is logged.
+//!   The locales are only collected to be logged while the debug level is enabled, so otherwise
+//!   they're still read lazily.
+//! - `tracing`: Emits the same messages as the `log` feature as debug-level events with the
+//!   `tracing` crate. This requires Rust 1.65 or newer.
 #![cfg_attr(
-    any(
-        not(unix),
-        target_os = "macos",
-        target_os = "ios",
-        target_os = "android"
+    all(
+        any(
+            not(unix),
+            target_os = "macos",
+            target_os = "ios",
+            target_os = "android"
+        ),
+        not(feature = "std")
     ),
     no_std
 )]
 extern crate alloc;
-use alloc::string::String;
+use alloc::{borrow::Cow, string::String, vec::Vec};
+
+/// Logs how the locales were obtained with the `log` feature, and emits it as an event with the
+/// `tracing` feature, or otherwise does nothing without evaluating its arguments.
+macro_rules! debug {
+    ($($arg:tt)+) => {
+        #[cfg(feature = "log")]
+        log::debug!($($arg)+);
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($arg)+);
+    };
+}
+
+/// Returns whether anything is logged by [`debug!`], so that the locales are only collected to
+/// log them when they will be.
+#[cfg(any(feature = "log", feature = "tracing"))]
+fn debug_enabled() -> bool {
+    #[cfg(feature = "log")]
+    if log::log_enabled!(log::Level::Debug) {
+        return true;
+    }
+    #[cfg(feature = "tracing")]
+    if tracing::enabled!(tracing::Level::DEBUG) {
+        return true;
+    }
+    false
+}
+
+/// The locales, which were only collected if they were logged.
+#[cfg(any(feature = "log", feature = "tracing"))]
+enum Logged<I: Iterator> {
+    Lazy(I),
+    Collected(alloc::vec::IntoIter<I::Item>),
+}
+
+#[cfg(any(feature = "log", feature = "tracing"))]
+impl<I: Iterator> Iterator for Logged<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        match self {
+            Logged::Lazy(locales) => locales.next(),
+            Logged::Collected(locales) => locales.next(),
+        }
+    }
+}
+
+/// Collects the locales and passes them to `log` if anything is logged, or otherwise keeps them
+/// lazy.
+#[cfg(any(feature = "log", feature = "tracing"))]
+fn logged<I: Iterator<Item = String>>(locales: I, log: impl FnOnce(&[String])) -> Logged<I> {
+    if !debug_enabled() {
+        return Logged::Lazy(locales);
+    }
+
+    let locales = locales.collect::<Vec<_>>();
+    log(&locales);
+    Logged::Collected(locales.into_iter())
+}
+
+#[cfg(feature = "cache")]
+mod cache;
+#[cfg(feature = "cache")]
+pub use cache::{get_locale_cached, get_locales_cached, invalidate_locale_cache};
+mod error;
+pub use error::LocaleError;
+#[cfg(feature = "heapless")]
+mod fixed;
+#[cfg(feature = "heapless")]
+pub use fixed::get_locales_fixed;
+mod overrides;
+#[cfg(feature = "mock")]
+pub use overrides::{clear_mock_locales, set_mock_locales};
+pub use overrides::{clear_provider, set_provider, LocaleProvider, LocaleSource, ProviderInfo};
+#[cfg(feature = "std")]
+pub use overrides::{scoped_locale, LocaleGuard};
+mod codes;
+pub use codes::CodeLength;
+mod likely;
+mod parse;
+pub use parse::{is_well_formed_bcp47, Locale, LocaleKey, ParseLocaleError};
+mod posix;
+pub use posix::{LocaleCategory, NormalizationOptions};
+mod settings;
+pub use settings::{
+    Calendar, CurrencyFormat, CurrencySymbolPosition, DateOrder, DateTimeSymbols, LocaleSettings,
+    MeasurementSystem, NegativeStyle, NumberSeparators, PaperSize, PercentSymbol, SignSymbols,
+    TextDirection, Weekday,
+};
+mod supported;
+pub use supported::SupportedLocales;
 
+// With the `test-stub` feature, the platform's own provider is still compiled for the functions
+// which are specific to it, such as `get_user_locale` on Windows, but is otherwise unused.
 #[cfg(target_os = "android")]
+#[cfg_attr(feature = "test-stub", allow(dead_code))]
 mod android;
-#[cfg(target_os = "android")]
+#[cfg(all(target_os = "android", not(feature = "test-stub")))]
 use android as provider;
 
 #[cfg(any(target_os = "macos", target_os = "ios"))]
+#[cfg_attr(feature = "test-stub", allow(dead_code))]
 mod apple;
-#[cfg(any(target_os = "macos", target_os = "ios"))]
+#[cfg(all(
+    any(target_os = "macos", target_os = "ios"),
+    not(feature = "test-stub")
+))]
 use apple as provider;
 
 #[cfg(all(
     unix,
     not(any(target_os = "macos", target_os = "ios", target_os = "android"))
 ))]
+#[cfg_attr(feature = "test-stub", allow(dead_code, unused_imports))]
 mod unix;
 #[cfg(all(
     unix,
-    not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+    not(any(target_os = "macos", target_os = "ios", target_os = "android")),
+    not(feature = "test-stub")
 ))]
 use unix as provider;
 
-#[cfg(all(target_family = "wasm", feature = "js", not(unix)))]
+#[cfg(all(
+    target_family = "wasm",
+    any(feature = "js", feature = "web-sys"),
+    not(unix)
+))]
+#[cfg_attr(feature = "test-stub", allow(dead_code))]
 mod wasm;
-#[cfg(all(target_family = "wasm", feature = "js", not(unix)))]
+#[cfg(all(
+    target_family = "wasm",
+    any(feature = "js", feature = "web-sys"),
+    not(unix),
+    not(feature = "test-stub")
+))]
 use wasm as provider;
 
+#[cfg(all(target_family = "wasm", feature = "js", feature = "std", not(unix)))]
+pub use wasm::{clear_js_global, set_js_global};
+
 #[cfg(windows)]
+#[cfg_attr(feature = "test-stub", allow(dead_code, unused_imports))]
 mod windows;
-#[cfg(windows)]
+#[cfg(all(windows, not(feature = "test-stub")))]
 use windows as provider;
+#[cfg(windows)]
+pub use windows::FormatOptions;
 
-#[cfg(not(any(unix, all(target_family = "wasm", feature = "js", not(unix)), windows)))]
+#[cfg(feature = "test-stub")]
+mod stub;
+#[cfg(feature = "test-stub")]
+use stub as provider;
+
+#[cfg(not(any(
+    feature = "test-stub",
+    unix,
+    all(
+        target_family = "wasm",
+        any(feature = "js", feature = "web-sys"),
+        not(unix)
+    ),
+    windows
+)))]
 mod provider {
+    pub const SUPPORTED: bool = false;
+
     pub fn get() -> impl Iterator<Item = alloc::string::String> {
         core::iter::empty()
     }
+
+    pub fn get_cow() -> impl Iterator<Item = alloc::borrow::Cow<'static, str>> {
+        core::iter::empty()
+    }
+
+    pub fn get_with_source() -> (
+        impl Iterator<Item = alloc::string::String>,
+        crate::LocaleSource,
+    ) {
+        (core::iter::empty(), crate::LocaleSource::System)
+    }
+
+    pub fn get_raw() -> impl Iterator<Item = alloc::string::String> {
+        core::iter::empty()
+    }
+
+    pub fn try_get() -> Result<core::iter::Empty<alloc::string::String>, crate::LocaleError> {
+        Err(crate::LocaleError::Unsupported)
+    }
+
+    pub fn describe() -> crate::ProviderInfo {
+        crate::ProviderInfo {
+            name: "unsupported",
+            sources: alloc::vec::Vec::new(),
+            active_source: None,
+        }
+    }
+
+    pub fn get_default_locale() -> Option<alloc::string::String> {
+        None
+    }
+
+    pub fn get_region() -> Option<alloc::string::String> {
+        None
+    }
+
+    pub fn get_format_locale() -> Option<alloc::string::String> {
+        None
+    }
+
+    pub fn get_collation_locale() -> Option<alloc::string::String> {
+        None
+    }
+
+    pub fn get_locale_for(_category: crate::LocaleCategory) -> Option<alloc::string::String> {
+        None
+    }
+
+    pub fn get_all_categories(
+    ) -> alloc::vec::Vec<(crate::LocaleCategory, Option<alloc::string::String>)> {
+        crate::LocaleCategory::ALL
+            .iter()
+            .map(|&category| (category, None))
+            .collect()
+    }
+
+    pub fn get_measurement_system() -> Option<crate::MeasurementSystem> {
+        None
+    }
+
+    pub fn get_number_separators() -> Option<crate::NumberSeparators> {
+        None
+    }
+
+    pub fn get_paper_size() -> Option<crate::PaperSize> {
+        None
+    }
+
+    pub fn get_datetime_symbols() -> Option<crate::DateTimeSymbols> {
+        None
+    }
+
+    pub fn get_grouping_sizes() -> Option<alloc::vec::Vec<u8>> {
+        None
+    }
+
+    pub fn get_list_separator() -> Option<char> {
+        None
+    }
+
+    pub fn get_first_day_of_week() -> Option<crate::Weekday> {
+        None
+    }
+
+    pub fn get_weekend_days() -> Option<(crate::Weekday, crate::Weekday)> {
+        None
+    }
+
+    pub fn uses_24_hour_time() -> Option<bool> {
+        None
+    }
+
+    pub fn get_date_field_order() -> Option<crate::DateOrder> {
+        None
+    }
+
+    pub fn get_calendar() -> Option<crate::Calendar> {
+        None
+    }
+
+    pub fn get_numbering_system() -> Option<alloc::string::String> {
+        None
+    }
+
+    pub fn get_locale_settings() -> crate::LocaleSettings {
+        crate::LocaleSettings::default()
+    }
+
+    pub fn get_currency_code() -> Option<alloc::string::String> {
+        None
+    }
+
+    pub fn get_currency_format() -> Option<crate::CurrencyFormat> {
+        None
+    }
+
+    pub fn get_percent_symbol() -> Option<crate::PercentSymbol> {
+        None
+    }
+
+    pub fn get_sign_symbols() -> Option<crate::SignSymbols> {
+        None
+    }
+
+    #[cfg(feature = "time-zone")]
+    pub fn get_time_zone() -> Option<alloc::string::String> {
+        None
+    }
+
+    pub fn get_locale_display_name() -> Option<alloc::string::String> {
+        None
+    }
+
+    pub fn display_name_of(_tag: &str, _in_locale: Option<&str>) -> Option<alloc::string::String> {
+        None
+    }
+
+    pub fn native_name_of(_tag: &str) -> Option<alloc::string::String> {
+        None
+    }
+
+    pub fn is_locale_available(_tag: &str) -> Option<bool> {
+        None
+    }
+
+    pub fn get_available_locales() -> alloc::vec::Vec<alloc::string::String> {
+        alloc::vec::Vec::new()
+    }
+
+    #[cfg(feature = "notify")]
+    pub enum Watcher {}
+
+    #[cfg(feature = "notify")]
+    pub fn watch(_callback: alloc::boxed::Box<dyn Fn() + Send + Sync>) -> Option<Watcher> {
+        None
+    }
+}
+
+/// Whether the locale can be read on the target platform.
+///
+/// If this is `false`, the platform isn't supported, so [`get_locales`] never returns any
+/// locales unless they're overridden, and [`try_get_locales`] returns
+/// [`LocaleError::Unsupported`]. This can be used to tell that apart from a supported platform
+/// where the user simply has no locale set.
+///
+/// WebAssembly is only supported with the `js` or `web-sys` feature.
+pub const SUPPORTED: bool = provider::SUPPORTED;
+
+/// Returns `true` if the locale can be read on the target platform, the same as [`SUPPORTED`].
+///
+/// # Example
+///
+/// ```
+/// use sys_locale::{get_locale, is_supported};
+///
+/// match get_locale() {
+///     Some(locale) => println!("The locale is {}", locale),
+///     None if is_supported() => println!("No locale is set, so ask which language to use"),
+///     None => println!("The locale can't be read on this platform"),
+/// }
+/// ```
+pub const fn is_supported() -> bool {
+    SUPPORTED
 }
 
 /// Returns the active locale for the system or application.
@@ -62,6 +462,9 @@ mod provider {
 /// This may be equivalent to `get_locales().next()` (the first entry),
 /// depending on the platform.
 ///
+/// On Windows, this follows the current user's settings rather than the system's, like
+/// `get_user_locale()`. Use `get_system_locale()` for the locale shared by every user.
+///
 /// # Returns
 ///
 /// Returns `Some(String)` with a BCP-47 language tag inside. If the locale
@@ -77,15 +480,74 @@ mod provider {
 /// println!("The locale is {}", current_locale);
 /// ```
 pub fn get_locale() -> Option<String> {
-    get_locales().next()
+    get_locales_iter().next()
+}
+
+/// Writes the active locale for the system or application into `buf`, returning it as a `&str`
+/// which borrows from `buf`.
+///
+/// This is the same locale as [`get_locale`], for callers who keep the tag in a fixed-size
+/// buffer rather than a `String`. Most tags fit into a few dozen bytes, but those with extensions or
+/// several variants can be longer.
+///
+/// Every platform's API is still called through the allocator to obtain the locales, so this
+/// doesn't remove the need for one. It only avoids keeping an allocation for the result.
+///
+/// # Returns
+///
+/// Returns `Some(&str)` with a BCP-47 language tag inside. If the locale couldn't be obtained,
+/// or it doesn't fit into `buf`, `None` is returned instead and `buf` is left unchanged.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_locale_buf;
+///
+/// let mut buf = [0; 35];
+/// println!("The locale is {}", get_locale_buf(&mut buf).unwrap_or("en-US"));
+/// ```
+pub fn get_locale_buf(buf: &mut [u8]) -> Option<&str> {
+    let locale = get_locale()?;
+    let buf = buf.get_mut(..locale.len())?;
+    buf.copy_from_slice(locale.as_bytes());
+    core::str::from_utf8(buf).ok()
 }
 
 /// Returns the preferred locales for the system or application, in descending order of preference.
 ///
+/// When the `env-override` feature is enabled (and `no-env` isn't) and the `SYS_LOCALE_OVERRIDE`
+/// environment variable contains a comma-separated list of BCP-47 language tags, such as `de-DE,en-US`,
+/// those are returned instead of the platform's preferences. Any empty entries in the list
+/// are skipped, and the platform's preferences are used if no entries remain.
+///
+/// Otherwise, if a [`LocaleProvider`] has been installed with [`set_provider`] and it
+/// returns any locales, those are returned instead of the platform's preferences.
+///
+/// On Windows, the platform's preferences are the user's display languages, which pick the
+/// language of text in the UI, as returned by `GetUserPreferredUILanguages`. These are configured
+/// separately from the region used for formatting numbers and dates, which is returned by
+/// [`get_format_locale`] instead.
+///
+/// On macOS and iOS, the languages are kept as the system reports them. Cantonese in particular
+/// may be reported as either `yue-Hant-HK` or `zh-Hant-HK`, depending on how it was picked, and
+/// `yue` is never replaced with `zh`, so [`is_cantonese`] can be used to recognize both.
+///
+/// Every well-formed locale is returned in its canonical casing, such as `zh-Hant-HK`, so it can
+/// be compared exactly. Tags which don't name a language are skipped, which are `und`
+/// (undetermined), even with a region such as `und-US`, and private use tags such as `x-custom`, although a private use suffix after a
+/// language is kept, as in `en-x-foo`. Each locale is only returned once, even if the platform lists it multiple
+/// times with different casing. Duplicates are removed without changing the order of the
+/// first appearance of every locale.
+///
+/// The order is stable, so calling this repeatedly returns the same locales in the same order,
+/// unless the user's preferences or the overrides are changed in between. No provider collects
+/// the locales into an unordered collection, such as a `HashMap`, which could reorder them.
+///
 /// # Returns
 ///
 /// Returns a `Vec` with any number of BCP-47 language tags inside.
-/// If no locale preferences could be obtained, the vec will be empty.
+/// If no locale preferences could be obtained, the vec will be empty. On UNIX systems,
+/// this includes when the `C` or `POSIX` locale is set, as these don't name a language.
 ///
 /// # Example
 ///
@@ -98,25 +560,4051 @@ pub fn get_locale() -> Option<String> {
 /// println!("The least preferred locale is {}", locales.last().unwrap_or("en-US".to_string()));
 /// ```
 pub fn get_locales() -> impl Iterator<Item = String> {
-    provider::get()
+    get_locales_iter()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::{get_locale, get_locales};
-    extern crate std;
+/// Returns the same locales as [`get_locales`], reading each one only once the iteration reaches
+/// it.
+///
+/// This is the lazy entry point which the other functions are built on, such as [`get_locale`],
+/// which stops after the first locale without building the rest. On Windows, the locales are
+/// decoded from the buffer returned by `GetUserPreferredUILanguages` as they're returned, rather
+/// than collected up front. With the `log` or `tracing` feature, they're collected while debug
+/// logging is enabled so that they can be logged.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_locales_iter;
+///
+/// let english = get_locales_iter().find(|locale| locale.starts_with("en"));
+/// println!("The preferred English locale is {:?}", english);
+/// ```
+pub fn get_locales_iter() -> impl Iterator<Item = String> {
+    let overridden = overrides::get();
+    let system = match overridden {
+        Some(_) => None,
+        None => Some(provider::get()),
+    };
 
-    #[test]
-    fn can_obtain_locale() {
-        assert!(get_locale().is_some(), "no locales were returned");
-        let locales = get_locales();
-        for (i, locale) in locales.enumerate() {
-            assert!(!locale.is_empty(), "locale string {} was empty", i);
-            assert!(
-                !locale.ends_with('\0'),
-                "locale {} contained trailing NUL",
-                i
-            );
+    #[cfg(any(feature = "log", feature = "tracing"))]
+    let system = system.map(|locales| {
+        logged(locales, |locales| {
+            debug!("Read the locales {:?} from the platform", locales);
+        })
+    });
+
+    let locales = dedup(
+        overridden
+            .into_iter()
+            .flatten()
+            .chain(system.into_iter().flatten())
+            .map(canonicalize)
+            .filter(|tag| names_language(tag)),
+    );
+
+    // The locales are only collected when they're logged.
+    #[cfg(any(feature = "log", feature = "tracing"))]
+    let locales = logged(locales, |locales| {
+        debug!("Resolved the locales {:?}", locales);
+    });
+    locales
+}
+
+/// Returns up to `n` of the most preferred locales for the system or application, in descending
+/// order of preference.
+///
+/// This returns the first `n` locales of [`get_locales`], or all of them if there are fewer. The
+/// remaining locales aren't decoded on platforms which provide them lazily, such as Windows and
+/// Apple platforms.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_top_locales;
+///
+/// println!("The three most preferred locales are {:?}", get_top_locales(3));
+/// ```
+pub fn get_top_locales(n: usize) -> Vec<String> {
+    get_locales_iter().take(n).collect()
+}
+
+/// Returns the preferred locales for the system or application, in descending order of preference,
+/// along with where each of them came from.
+///
+/// This returns the same locales as [`get_locales`], which is useful for diagnosing why a
+/// particular locale was chosen, such as by logging it.
+///
+/// - Locales from the `SYS_LOCALE_OVERRIDE` environment variable are [`LocaleSource::Environment`].
+/// - Locales from a [`LocaleProvider`] are [`LocaleSource::Application`].
+/// - On macOS, iOS, and Android 13 and above, the languages picked for the running application
+///   are [`LocaleSource::Application`].
+/// - On Linux, BSD, and other UNIX variations, locales from the environment are
+///   [`LocaleSource::Environment`], and those from `/etc/locale.conf` are [`LocaleSource::System`].
+/// - On WebAssembly, the default locale of `Intl` is [`LocaleSource::Fallback`], and the `LC_ALL`
+///   or `LANG` environment variables of Node.js are [`LocaleSource::Environment`].
+/// - Any other locales are the user's preferences for the whole system, [`LocaleSource::System`].
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_locales_with_source;
+///
+/// for (locale, source) in get_locales_with_source() {
+///     println!("{} came from {:?}", locale, source);
+/// }
+/// ```
+pub fn get_locales_with_source() -> Vec<(String, LocaleSource)> {
+    let (locales, source) = match overrides::get_with_source() {
+        Some((overridden, source)) => (overridden, source),
+        None => {
+            let (system, source) = provider::get_with_source();
+            (system.collect(), source)
+        }
+    };
+
+    dedup(
+        locales
+            .into_iter()
+            .map(canonicalize)
+            .filter(|tag| names_language(tag)),
+    )
+    .map(|locale| (locale, source))
+    .collect()
+}
+
+/// Describes the platform's own provider of the locales, including the sources which it
+/// consulted and which of them the locales came from, for including in bug reports.
+///
+/// This describes the platform, so it ignores the `SYS_LOCALE_OVERRIDE` environment variable and
+/// any [`LocaleProvider`], which [`get_locales_with_source`] reports instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::describe_provider;
+///
+/// // Such as `unix (sources: LANGUAGE, LC_ALL, LC_MESSAGES, LANG, ...; active: LANG)`.
+/// println!("{}", describe_provider());
+/// ```
+pub fn describe_provider() -> ProviderInfo {
+    provider::describe()
+}
+
+/// Replaces the contents of `out` with the preferred locales for the system or application,
+/// in descending order of preference.
+///
+/// This returns the same locales as [`get_locales`], but reuses the allocation of `out`,
+/// which is useful when the locales are read repeatedly, such as in a polling loop.
+/// If no locale preferences could be obtained, `out` will be empty.
+///
+/// Each locale is still allocated as its own `String`. [`get_locales_cow`] borrows those which
+/// don't need to be allocated instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_locales_into;
+///
+/// let mut locales = Vec::new();
+/// loop {
+///     get_locales_into(&mut locales);
+///     println!("The preferred locales are {:?}", locales);
+///     # break;
+/// }
+/// ```
+pub fn get_locales_into(out: &mut Vec<String>) {
+    out.clear();
+
+    // The locales are checked for duplicates against `out` itself, rather than
+    // being collected through `dedup`, so that nothing else needs to be allocated.
+    for_each_locale(|locale| {
+        if !out.iter().any(|seen| seen.eq_ignore_ascii_case(&locale)) {
+            out.push(locale);
+        }
+        true
+    });
+}
+
+/// Returns the preferred locales for the system or application, in descending order of
+/// preference, borrowing those which don't need to be allocated.
+///
+/// This returns the same locales as [`get_locales`], but those which live as long as the program
+/// and are already in their canonical casing are borrowed, rather than copied into a new
+/// `String`. These are the locales of the `test-stub` feature, and those which a
+/// [`LocaleProvider`] borrows in [`LocaleProvider::get_cow`]. The locales are checked for
+/// duplicates within the returned `Vec` itself, so when every locale is borrowed, only the `Vec`
+/// is allocated.
+///
+/// No platform provides its locales as UTF-8 which outlives the call that read them: Windows and
+/// Apple platforms provide UTF-16 which must be decoded, WebAssembly copies them out of JavaScript
+/// strings, and UNIX platforms convert them from the POSIX format. These are returned as
+/// [`Cow::Owned`] without being copied again, as are the locales from `SYS_LOCALE_OVERRIDE` and
+/// those overridden on the current thread.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_locales_cow;
+///
+/// for locale in get_locales_cow() {
+///     println!("The user prefers {}", locale);
+/// }
+/// ```
+pub fn get_locales_cow() -> Vec<Cow<'static, str>> {
+    let mut locales = match overrides::get_cow() {
+        Some(overridden) => overridden,
+        None => provider::get_cow().collect(),
+    };
+
+    // The locales are moved towards the front of `locales` as they're kept, rather than being
+    // collected through `dedup`, so that nothing else needs to be allocated.
+    let mut kept = 0;
+    for i in 0..locales.len() {
+        let locale = canonicalize_cow(core::mem::take(&mut locales[i]));
+        if names_language(&locale)
+            && !locales[..kept]
+                .iter()
+                .any(|seen| seen.eq_ignore_ascii_case(&locale))
+        {
+            locales[kept] = locale;
+            kept += 1;
+        }
+    }
+    locales.truncate(kept);
+
+    debug!("Resolved the locales {:?}", locales);
+    locales
+}
+
+/// Returns the preferred locales for the system or application, in descending order of
+/// preference, in a `SmallVec` which stores up to two locales without allocating.
+///
+/// This returns the same locales as [`get_locales`], but most systems only have one or two
+/// preferred locales, so the list which holds them doesn't need to be allocated, which helps
+/// when the locales are read repeatedly. Like [`get_locales_into`], the locales are checked for
+/// duplicates against the list itself, so nothing else is allocated for it. Each locale is still
+/// allocated as its own `String`, and some platforms allocate while reading them.
+///
+/// This is only available with the `smallvec` feature.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_locales_small;
+///
+/// let locales = get_locales_small();
+/// println!("The preferred locales are {:?}", locales);
+/// ```
+#[cfg(feature = "smallvec")]
+pub fn get_locales_small() -> smallvec::SmallVec<[String; 2]> {
+    let mut out = smallvec::SmallVec::new();
+    for_each_locale(|locale| {
+        if !out
+            .iter()
+            .any(|seen: &String| seen.eq_ignore_ascii_case(&locale))
+        {
+            out.push(locale);
         }
+        true
+    });
+    out
+}
+
+/// Calls `f` with each of the locales from the overrides or the platform in their canonical
+/// casing, skipping those which don't name a language, but not duplicates, until `f` returns
+/// `false`. The locales after that aren't canonicalized or read from the platform's iterator.
+fn for_each_locale(mut f: impl FnMut(String) -> bool) {
+    let mut push = |locale: String| {
+        let locale = canonicalize(locale);
+        !names_language(&locale) || f(locale)
+    };
+    match overrides::get() {
+        Some(overridden) => overridden.into_iter().all(&mut push),
+        None => provider::get().all(&mut push),
+    };
+}
+
+/// Returns the preferred locales for the system or application, in descending order of preference,
+/// or the reason why they couldn't be obtained.
+///
+/// This follows the same rules as [`get_locales`], including the overrides and removal of
+/// duplicates, but distinguishes between the ways that obtaining the locales can fail.
+///
+/// # Errors
+///
+/// - [`LocaleError::Unsupported`] if the platform doesn't provide any way to read the locale.
+/// - [`LocaleError::PlatformError`] if the platform API used to read the locale failed.
+/// - [`LocaleError::Empty`] if the user doesn't have any locale set.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::{try_get_locales, LocaleError};
+///
+/// match try_get_locales() {
+///     Ok(locales) => println!("The most preferred locale is {}", locales[0]),
+///     Err(LocaleError::Unsupported) => println!("This platform isn't supported"),
+///     Err(err) => println!("The locale couldn't be obtained: {}", err),
+/// }
+/// ```
+pub fn try_get_locales() -> Result<Vec<String>, LocaleError> {
+    match overrides::get() {
+        Some(overridden) => collect_locales(Ok(overridden.into_iter())),
+        None => collect_locales(provider::try_get()),
+    }
+}
+
+/// Returns the preferred locales for the system or application, in descending order of preference,
+/// giving up if they couldn't be obtained within `timeout`.
+///
+/// This is the same as [`try_get_locales`], except that the locales are read on a helper thread,
+/// so that a platform call which blocks, such as on a system with a broken locale setup, doesn't
+/// stall the caller. The timeout is best-effort: it bounds how long the caller waits, but the
+/// platform call can't be interrupted, so the helper thread keeps running in the background
+/// until the call returns. The thread is detached and exits on its own once it does. If the
+/// thread can't be spawned, the locales are read on the calling thread instead.
+///
+/// Locales mocked on the calling thread with the `mock` feature are still returned.
+///
+/// This requires the `std` feature.
+///
+/// # Errors
+///
+/// - [`LocaleError::Timeout`] if the locales weren't obtained within `timeout`.
+/// - Any of the errors returned by [`try_get_locales`].
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use sys_locale::try_get_locales_timeout;
+///
+/// let locales = try_get_locales_timeout(Duration::from_millis(500))
+///     .unwrap_or_else(|_| vec![String::from("en-US")]);
+///
+/// println!("The preferred locales are {:?}", locales);
+/// ```
+#[cfg(feature = "std")]
+pub fn try_get_locales_timeout(timeout: std::time::Duration) -> Result<Vec<String>, LocaleError> {
+    if let Some(locales) = overrides::thread_locales() {
+        return collect_locales(Ok(locales.into_iter()));
+    }
+
+    let (sender, receiver) = std::sync::mpsc::sync_channel(1);
+    let spawned = std::thread::Builder::new()
+        .name(String::from("sys-locale"))
+        .spawn(move || {
+            // The receiver is gone if the caller already gave up, which is fine.
+            let _ = sender.send(try_get_locales());
+        });
+    if spawned.is_err() {
+        return try_get_locales();
+    }
+
+    receiver
+        .recv_timeout(timeout)
+        .unwrap_or(Err(LocaleError::Timeout))
+}
+
+/// Returns the preferred UI languages of a different user on Windows, in descending order of
+/// preference, such as the user of another session.
+///
+/// `token` is the raw `HANDLE` of the user's access token, such as one obtained with
+/// `WTSQueryUserToken` or `OpenProcessToken`. The user is impersonated on the calling thread
+/// with `ImpersonateLoggedOnUser` while their languages are read with
+/// `GetUserPreferredUILanguages`, and the thread reverts to its own user before this returns.
+/// [`get_locales`] is unaffected, and always reads the languages of the current user.
+///
+/// Impersonating another user requires the `SeImpersonatePrivilege` privilege, which services
+/// and administrators have. The token must have been opened with `TOKEN_QUERY` and
+/// `TOKEN_DUPLICATE` access, or `TOKEN_QUERY` and `TOKEN_IMPERSONATE` if it is an impersonation
+/// token. The user's profile must also be loaded, since their languages are stored in it.
+///
+/// Like [`try_get_locales`], duplicates are removed and the tags are canonicalized, but the
+/// `SYS_LOCALE_OVERRIDE` environment variable and [`LocaleProvider`] aren't used.
+///
+/// # Errors
+///
+/// - [`LocaleError::PlatformError`] if the user couldn't be impersonated, such as when the
+///   token is invalid or the caller lacks the privilege, or if the languages couldn't be read.
+/// - [`LocaleError::Empty`] if the user doesn't have any languages set.
+///
+/// # Aborts
+///
+/// Aborts the process if the calling thread couldn't stop impersonating the user, rather than
+/// continuing to run as them.
+///
+/// # Safety
+///
+/// `token` must be a valid access token handle, which stays open until this returns. The
+/// calling thread must not already be impersonating a user, since it reverts to its own user
+/// afterwards.
+#[cfg(windows)]
+pub unsafe fn get_locales_for_token(token: isize) -> Result<Vec<String>, LocaleError> {
+    collect_locales(windows::try_get_for_token(token))
+}
+
+/// Collects the locales read by a provider, treating an empty list as [`LocaleError::Empty`].
+fn collect_locales(
+    locales: Result<impl Iterator<Item = String>, LocaleError>,
+) -> Result<Vec<String>, LocaleError> {
+    let locales =
+        dedup(locales?.map(canonicalize).filter(|tag| names_language(tag))).collect::<Vec<_>>();
+    if locales.is_empty() {
+        Err(LocaleError::Empty)
+    } else {
+        Ok(locales)
+    }
+}
+
+/// Returns the locales exactly as the platform provides them, in descending order of preference.
+///
+/// Unlike [`get_locales`], these aren't converted into BCP-47 language tags, nor are duplicates
+/// removed. This is useful for passing the locale to other libraries which expect the platform's
+/// own format. The `SYS_LOCALE_OVERRIDE` environment variable and [`LocaleProvider`] aren't used.
+///
+/// - On Linux, BSD, and other UNIX variations, these are the entries of `LANGUAGE` followed by
+///   the value of `LC_ALL`, `LC_MESSAGES`, or `LANG`, such as `de_DE.UTF-8@euro`.
+/// - On WebAssembly under Node.js, this may be the value of `LC_ALL` or `LANG` in the same format.
+/// - On other platforms, the platform already provides BCP-47 language tags, so these are the
+///   same as the locales returned by [`get_locales`].
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_raw_locales;
+///
+/// for locale in get_raw_locales() {
+///     println!("The platform provided {}", locale);
+/// }
+/// ```
+pub fn get_raw_locales() -> Vec<String> {
+    provider::get_raw().collect()
+}
+
+/// Returns the preferred locales for the system or application, in descending order of
+/// preference, with only the transformations picked in `options` applied to them.
+///
+/// These are the locales from [`get_raw_locales`], such as `sr_RS.UTF-8@latin` on UNIX systems,
+/// unless the `SYS_LOCALE_OVERRIDE` environment variable or a [`LocaleProvider`] overrides them.
+/// The default [`NormalizationOptions`] return the same BCP-47 language tags as [`get_locales`]
+/// for them, such as `sr-Latn-RS`, while turning off its options keeps more of the platform's
+/// format, such as `sr_RS.UTF-8` when only the modifier is dropped. The `C` and `POSIX` locales
+/// are always skipped, and any locales which are the same as an earlier one, without regard to
+/// their casing, are removed.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::{get_locales_with_options, NormalizationOptions};
+///
+/// // Keep the underscores and codesets of POSIX locale names.
+/// let options = NormalizationOptions {
+///     strip_encoding: false,
+///     rewrite_separator: false,
+///     ..NormalizationOptions::default()
+/// };
+///
+/// for locale in get_locales_with_options(&options) {
+///     println!("The user prefers {}", locale);
+/// }
+/// ```
+pub fn get_locales_with_options(options: &NormalizationOptions) -> Vec<String> {
+    let locales = match overrides::get() {
+        Some(overridden) => overridden,
+        None => provider::get_raw().collect(),
+    };
+    dedup(
+        locales
+            .iter()
+            .filter_map(|code| posix::normalize_locale_code(code, options)),
+    )
+    .collect()
+}
+
+/// Converts a language tag into its canonical casing, such as `zh-Hant-HK` for `ZH-hant-hk`.
+///
+/// Tags which aren't well-formed are returned unchanged, since they can't be split into subtags,
+/// as are those which are already canonical, without parsing them into a [`Locale`].
+fn canonicalize(tag: String) -> String {
+    if parse::is_canonical(&tag) {
+        return tag;
+    }
+    match Locale::from_tag(&tag) {
+        Some(locale) => locale.to_tag(),
+        None => tag,
+    }
+}
+
+/// Converts a language tag into its canonical casing like [`canonicalize`], but keeps it as it is
+/// if it's already canonical, including when it's borrowed.
+fn canonicalize_cow(tag: Cow<'static, str>) -> Cow<'static, str> {
+    if parse::is_canonical(&tag) {
+        tag
+    } else {
+        Cow::Owned(canonicalize(tag.into_owned()))
+    }
+}
+
+/// Checks whether a tag names a language, unlike `und` (undetermined), including with a script or
+/// region as in `und-US`, or a private use tag such as `x-custom`. A private use suffix after a
+/// language, as in `en-x-foo`, is fine. The tag may be in any casing.
+fn names_language(tag: &str) -> bool {
+    let first = tag.split('-').next().unwrap_or_default();
+    !first.eq_ignore_ascii_case("und") && !first.eq_ignore_ascii_case("x")
+}
+
+/// Removes any locales that have already been returned, ignoring their casing.
+///
+/// The locales which were returned are remembered by their [`hash_ignoring_case`], rather than
+/// copied. The hash of the latest one is only added to the list once another locale is read, so
+/// that nothing is allocated when only the first locale is taken.
+fn dedup(locales: impl Iterator<Item = String>) -> impl Iterator<Item = String> {
+    let mut seen = Vec::<u64>::new();
+    let mut latest = None;
+    locales.filter(move |locale| {
+        let hash = hash_ignoring_case(locale);
+        if latest == Some(hash) || seen.contains(&hash) {
+            false
+        } else {
+            seen.extend(latest.replace(hash));
+            true
+        }
+    })
+}
+
+/// Returns the 64-bit FNV-1a hash of a tag in lowercase. A user only has a few locales, so two
+/// different ones having the same hash is vanishingly unlikely.
+fn hash_ignoring_case(tag: &str) -> u64 {
+    tag.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte.to_ascii_lowercase())).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Returns the active locale for the system or application, or `default` if it
+/// couldn't be obtained.
+///
+/// The default is only converted into a `String` when it's needed.
+///
+/// # Example
+///
+/// ```
+/// use sys_locale::get_locale_or;
+///
+/// let current_locale = get_locale_or("en-US");
+///
+/// println!("The locale is {}", current_locale);
+/// ```
+pub fn get_locale_or(default: impl Into<String>) -> String {
+    get_locale().unwrap_or_else(|| default.into())
+}
+
+/// The active locale from [`get_locale_or_fallback`], which always contains a tag, along with
+/// whether it was detected.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LocaleResult {
+    /// The locale of the system or application, including from an override.
+    Detected(String),
+    /// The fallback which was used because the active locale couldn't be obtained.
+    Fallback(String),
+}
+
+impl LocaleResult {
+    /// Returns the tag, regardless of whether it was detected.
+    pub fn as_str(&self) -> &str {
+        match self {
+            LocaleResult::Detected(tag) | LocaleResult::Fallback(tag) => tag,
+        }
+    }
+
+    /// Converts this into the tag, regardless of whether it was detected.
+    pub fn into_string(self) -> String {
+        match self {
+            LocaleResult::Detected(tag) | LocaleResult::Fallback(tag) => tag,
+        }
+    }
+
+    /// Returns `true` if the locale was detected, rather than being the fallback.
+    pub fn is_detected(&self) -> bool {
+        matches!(self, LocaleResult::Detected(_))
+    }
+}
+
+/// Returns the active locale for the system or application, or `fallback` if it couldn't be
+/// obtained, along with which of them it is.
+///
+/// This is the same as [`get_locale_or`], but tells the two cases apart, such as for logging
+/// when the fallback was used. The fallback is only converted into a `String` when it's needed.
+///
+/// # Example
+///
+/// ```
+/// use sys_locale::{get_locale_or_fallback, LocaleResult};
+///
+/// match get_locale_or_fallback("en-US") {
+///     LocaleResult::Detected(tag) => println!("The locale is {}", tag),
+///     LocaleResult::Fallback(tag) => println!("No locale was found, so using {}", tag),
+/// }
+/// ```
+pub fn get_locale_or_fallback(fallback: impl Into<String>) -> LocaleResult {
+    match get_locale() {
+        Some(tag) => LocaleResult::Detected(tag),
+        None => LocaleResult::Fallback(fallback.into()),
+    }
+}
+
+/// The locale returned by [`get_locale_or_default`] when the active locale couldn't be obtained.
+///
+/// Being a constant, this can also be used as the fallback in `const` and `static` items.
+///
+/// # Example
+///
+/// ```
+/// use sys_locale::DEFAULT_LOCALE;
+///
+/// static SUPPORTED: [&str; 2] = [DEFAULT_LOCALE, "de-DE"];
+///
+/// assert_eq!(SUPPORTED[0], "en-US");
+/// ```
+pub const DEFAULT_LOCALE: &str = "en-US";
+
+/// Returns the active locale for the system or application, or [`DEFAULT_LOCALE`] if it
+/// couldn't be obtained.
+///
+/// # Example
+///
+/// ```
+/// use sys_locale::{get_locale_or_default, DEFAULT_LOCALE};
+///
+/// let current_locale = get_locale_or_default();
+///
+/// if current_locale == DEFAULT_LOCALE {
+///     println!("Using the default locale");
+/// }
+/// ```
+pub fn get_locale_or_default() -> String {
+    get_locale_or(DEFAULT_LOCALE)
+}
+
+/// Returns the preferred locales for the system or application, in descending order of
+/// preference, or `defaults` if no locale preferences could be obtained.
+///
+/// # Example
+///
+/// ```
+/// use sys_locale::get_locales_or;
+///
+/// let locales = get_locales_or(vec![String::from("en-US")]);
+///
+/// println!("The most preferred locale is {}", locales[0]);
+/// ```
+pub fn get_locales_or(defaults: Vec<String>) -> Vec<String> {
+    let locales = get_locales().collect::<Vec<_>>();
+    if locales.is_empty() {
+        defaults
+    } else {
+        locales
+    }
+}
+
+/// Returns the preferred locales for the system or application, in descending order of
+/// preference, followed by the platform's default locale if it isn't already one of them.
+///
+/// This is useful for negotiating content, so that there's still something to match against
+/// when no preferences could be obtained. The default locale is:
+///
+/// - On Windows, the user's default locale, as returned by `GetUserDefaultLocaleName`.
+/// - On macOS and iOS, the `AppleLocale` preference of the global domain, which follows the
+///   region picked in System Settings, or the current locale if it isn't set.
+/// - On Linux, BSD, and other UNIX variations, the locale of `LANG`.
+/// - On Android, the system locale property.
+/// - On WebAssembly with the `js` feature, the default locale of `Intl`.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_locales_with_system_default;
+///
+/// // For example, `["fr-CA", "en-US"]` if the user prefers French, but the system is in English.
+/// println!("Negotiating with {:?}", get_locales_with_system_default());
+/// ```
+pub fn get_locales_with_system_default() -> Vec<String> {
+    with_default(get_locales(), provider::get_default_locale())
+}
+
+/// Checks whether the system has the data for a locale, such as its formats, so that it can be
+/// offered to the user.
+///
+/// - On Windows, this checks whether the locale is supported with `IsValidLocaleName`.
+/// - On macOS and iOS, this checks the identifiers of `NSLocale.availableLocaleIdentifiers`.
+/// - On Linux and other UNIX systems, this checks the locales listed by `locale -a`, which have
+///   to be generated or installed before they can be used.
+/// - On other platforms, the available locales can't be enumerated.
+///
+/// On macOS, iOS, and UNIX systems, a tag without a region or script, such as `en`, is available
+/// if any locale of that language is, such as `en_US`, and the tags are compared without regard
+/// to their casing.
+///
+/// # Returns
+///
+/// Returns `Some(bool)` with whether the locale is available inside. If the available locales
+/// can't be enumerated, such as when `locale` isn't installed, `None` is returned instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::is_locale_available;
+///
+/// if is_locale_available("de-DE") == Some(false) {
+///     println!("German formats aren't available");
+/// }
+/// ```
+pub fn is_locale_available(tag: &str) -> Option<bool> {
+    provider::is_locale_available(tag)
+}
+
+/// Returns every locale which the system has the data for, rather than those which the user
+/// prefers, such as for picking a locale from a list.
+///
+/// - On Windows, these are the locales enumerated by `EnumSystemLocalesEx`, including the
+///   supplemental locales but not the alternate sort orders.
+/// - On macOS and iOS, these are the identifiers of `NSLocale.availableLocaleIdentifiers`.
+/// - On Linux and other UNIX systems, these are the locales listed by `locale -a`, which have to
+///   be generated or installed. Their codesets are removed, so `en_US.utf8` and `en_US.iso88591`
+///   are both returned as `en-US`, and the `C` and `POSIX` locales aren't returned.
+/// - On other platforms, the available locales can't be enumerated.
+///
+/// # Returns
+///
+/// Returns a `Vec` of BCP-47 language tags in their canonical casing, each of which is only
+/// returned once, in the order of the platform. If the available locales can't be enumerated,
+/// the `Vec` is empty.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_available_locales;
+///
+/// for locale in get_available_locales() {
+///     println!("The system supports {}", locale);
+/// }
+/// ```
+pub fn get_available_locales() -> Vec<String> {
+    let locales = provider::get_available_locales()
+        .into_iter()
+        .map(canonicalize)
+        .filter(|tag| names_language(tag));
+    dedup(locales).collect()
+}
+
+/// Returns the preferred locales for the system or application, in descending order of
+/// preference, with any deprecated codes replaced by their modern equivalents.
+///
+/// This returns the same locales as [`get_locales`], with each one passed through
+/// [`canonicalize_tag`]. Any locales which become the same as an earlier one are removed, such
+/// as `iw-IL` when the user also prefers `he-IL`.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_locales_canonical;
+///
+/// for locale in get_locales_canonical() {
+///     println!("The user prefers {}", locale);
+/// }
+/// ```
+pub fn get_locales_canonical() -> Vec<String> {
+    dedup(get_locales().map(|tag| canonicalize_tag(&tag))).collect()
+}
+
+/// Converts a BCP-47 language tag into its canonical casing, replacing any deprecated language
+/// or region codes with their modern equivalents, following the aliases of the CLDR.
+///
+/// This doesn't depend on the platform, so it's available everywhere, including without the
+/// standard library. It only contains these common deprecated codes, which some platforms still
+/// use, such as Java's `iw`:
+///
+/// | Deprecated | Preferred | Language or region |
+/// |------------|-----------|--------------------|
+/// | `in`       | `id`      | Indonesian         |
+/// | `iw`       | `he`      | Hebrew             |
+/// | `ji`       | `yi`      | Yiddish            |
+/// | `jw`       | `jv`      | Javanese           |
+/// | `mo`       | `ro`      | Moldavian, which is Romanian |
+/// | `no`       | `nb`      | Norwegian, which is usually Bokmål |
+/// | `sh`       | `sr-Latn` | Serbo-Croatian, as Serbian in Latin script, unless it has a script |
+/// | `tl`       | `fil`     | Tagalog, which is Filipino |
+/// | `BU`       | `MM`      | Burma, which is Myanmar |
+/// | `DD`       | `DE`      | East Germany, which is Germany |
+/// | `FX`       | `FR`      | Metropolitan France, which is France |
+/// | `TP`       | `TL`      | East Timor, which is Timor-Leste |
+/// | `YD`       | `YE`      | South Yemen, which is Yemen |
+/// | `ZR`       | `CD`      | Zaire, which is the Democratic Republic of the Congo |
+///
+/// Regions which were split into several countries, such as `YU` (Yugoslavia) and `CS` (Serbia
+/// and Montenegro), are kept, since it's unknown which of them is meant. The variants and
+/// extensions of the tag are kept. Tags which aren't well-formed are returned unchanged.
+///
+/// # Example
+///
+/// ```
+/// use sys_locale::canonicalize_tag;
+///
+/// assert_eq!(canonicalize_tag("iw-IL"), "he-IL");
+/// assert_eq!(canonicalize_tag("in"), "id");
+/// assert_eq!(canonicalize_tag("en-us"), "en-US");
+/// ```
+pub fn canonicalize_tag(tag: &str) -> String {
+    let locale = match Locale::from_tag(tag) {
+        Some(locale) => locale,
+        None => return String::from(tag),
+    };
+
+    let (language, likely_script) =
+        codes::preferred_language(locale.language()).unwrap_or((locale.language(), None));
+    let script = locale.script().or(likely_script);
+    let region = locale
+        .region()
+        .map(|region| codes::preferred_region(region).unwrap_or(region));
+    locale
+        .with_language(language)
+        .with_script_and_region(script, region)
+        .to_tag()
+}
+
+/// Appends `default` to `locales` in its canonical casing, unless it's already one of them.
+fn with_default(locales: impl Iterator<Item = String>, default: Option<String>) -> Vec<String> {
+    let default = default.map(canonicalize).filter(|tag| names_language(tag));
+    dedup(locales.chain(default)).collect()
+}
+
+/// Returns the preferred locales for the system or application, in descending order of
+/// preference, omitting any which aren't well-formed BCP-47 language tags.
+///
+/// This returns the same locales as [`get_locales`], except that each one is checked with
+/// [`is_well_formed_bcp47`] and dropped if it isn't well-formed. The `C` and `POSIX` locales are
+/// always dropped, as are private use tags which don't name a language, such as `x-whatever`,
+/// and values which still contain a codeset or modifier, such as `en_US.UTF-8` or `de_DE@euro`.
+/// The order of the remaining locales is unchanged.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_locales_validated;
+///
+/// for locale in get_locales_validated() {
+///     println!("The user prefers {}", locale);
+/// }
+/// ```
+pub fn get_locales_validated() -> Vec<String> {
+    get_locales().filter(|tag| is_valid_tag(tag)).collect()
+}
+
+/// Returns the preferred locales on macOS and iOS, in descending order of preference, with the
+/// user's formatting preferences kept as Unicode extension keywords, such as
+/// `en-US-u-ca-buddhist-hc-h23`.
+///
+/// The calendar, hour cycle, and other preferences which the user picks for every language are
+/// read from the keywords of the current locale's identifier, such as
+/// `en_US@calendar=buddhist;hours=h23`, and added to each locale which doesn't already have a
+/// Unicode extension of its own. [`get_locales`] returns the locales without them.
+///
+/// Like [`get_locales`], the `SYS_LOCALE_OVERRIDE` environment variable and [`LocaleProvider`]
+/// take precedence, and are returned unchanged.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_locales_with_extensions;
+///
+/// for locale in get_locales_with_extensions() {
+///     println!("The user prefers {}", locale);
+/// }
+/// ```
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+pub fn get_locales_with_extensions() -> impl Iterator<Item = String> {
+    let overridden = overrides::get();
+    let system = match overridden {
+        Some(_) => None,
+        None => Some(apple::get_with_extensions()),
+    };
+
+    dedup(
+        overridden
+            .into_iter()
+            .flatten()
+            .chain(system.into_iter().flatten())
+            .map(canonicalize)
+            .filter(|tag| names_language(tag)),
+    )
+}
+
+/// Checks whether a locale is a well-formed BCP-47 language tag which names a language.
+fn is_valid_tag(tag: &str) -> bool {
+    // `POSIX` is shaped like a language subtag, so it isn't rejected by the syntax check.
+    let language = tag.split('-').next().unwrap_or_default();
+    !language.eq_ignore_ascii_case("x")
+        && !tag.eq_ignore_ascii_case("POSIX")
+        && is_well_formed_bcp47(tag)
+}
+
+/// Returns the fallback chain of a BCP-47 language tag, starting with the tag itself and
+/// followed by each shorter tag that is obtained by removing the last subtag.
+///
+/// This is the truncation fallback commonly used to look up resources, so a missing
+/// translation for `zh-Hant-HK` is looked up in `zh-Hant`, followed by `zh`. When an extension
+/// or private use subtag is removed, its singleton (such as `-u`) is removed along with it.
+///
+/// # Example
+///
+/// ```
+/// use sys_locale::expand_fallback_chain;
+///
+/// assert_eq!(expand_fallback_chain("zh-Hant-HK"), ["zh-Hant-HK", "zh-Hant", "zh"]);
+/// assert_eq!(expand_fallback_chain("fr"), ["fr"]);
+/// ```
+pub fn expand_fallback_chain(tag: &str) -> Vec<String> {
+    fallback_chain(tag).collect()
+}
+
+/// Returns the [fallback chain](expand_fallback_chain) of a tag, where each shorter tag is only
+/// made once it's needed.
+fn fallback_chain(tag: &str) -> impl Iterator<Item = String> {
+    let first = Some(String::from(tag)).filter(|tag| !tag.is_empty());
+    core::iter::successors(first, |tag| truncate_subtag(tag).map(String::from))
+}
+
+/// Removes the last subtag of a tag, along with its singleton if it was the only subtag of an
+/// extension, or returns `None` if there is nothing left.
+fn truncate_subtag(tag: &str) -> Option<&str> {
+    let mut tag = &tag[..tag.rfind('-')?];
+    if let Some(end) = tag.rfind('-').filter(|end| tag.len() - end == 2) {
+        tag = &tag[..end];
+    }
+    Some(tag).filter(|tag| !tag.is_empty())
+}
+
+/// Returns the preferred locales for the system or application, each followed by its
+/// [fallback chain](expand_fallback_chain).
+///
+/// The locales are in the same order as [`get_locales`], with each one immediately followed by
+/// its own fallbacks before the next preferred locale. A locale which has already appeared in
+/// the list, such as the `en` of both `en-US` and `en-GB`, is only returned the first time.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_locales_with_fallbacks;
+///
+/// // For example, `["en-US", "en", "fr-CA", "fr"]`.
+/// println!("Resources are searched in {:?}", get_locales_with_fallbacks());
+/// ```
+pub fn get_locales_with_fallbacks() -> Vec<String> {
+    fallback_iter().collect()
+}
+
+/// Returns the same locales as [`get_locales_with_fallbacks`], but expands the fallback chain of
+/// each preferred locale only once the iteration reaches it.
+///
+/// This is useful for looking up a resource, where the search stops at the first locale which
+/// has it, so the rest of the list is never built.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::fallback_iter;
+///
+/// let translations = ["en", "fr"];
+/// let found = fallback_iter().find(|locale| translations.contains(&locale.as_str()));
+/// println!("Using the {:?} translation", found);
+/// ```
+pub fn fallback_iter() -> impl Iterator<Item = String> {
+    with_fallbacks(get_locales_iter())
+}
+
+/// Follows each locale with its fallback chain, skipping any which have already been returned.
+fn with_fallbacks(locales: impl Iterator<Item = String>) -> impl Iterator<Item = String> {
+    dedup(locales.flat_map(|tag| fallback_chain(&tag)))
+}
+
+/// Returns the distinct languages of the preferred locales for the system or application, in
+/// descending order of preference.
+///
+/// This is the [language](Locale::language) of each locale from [`get_locales`], such as `en`
+/// for both `en-US` and `en-GB`, where each language is only returned the first time it appears.
+/// Locales which aren't well-formed BCP-47 language tags are skipped.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_primary_languages;
+///
+/// // For example, `["en", "fr"]` for the locales `["en-US", "en-GB", "fr-FR"]`.
+/// println!("The user speaks {:?}", get_primary_languages());
+/// ```
+pub fn get_primary_languages() -> Vec<String> {
+    let languages = get_locales()
+        .filter_map(|tag| Locale::from_tag(&tag).map(|locale| String::from(locale.language())));
+    dedup(languages).collect()
+}
+
+/// Returns the distinct language and region pairs of the preferred locales for the system or
+/// application, in descending order of preference.
+///
+/// Each locale from [`get_locales`] is reduced to its [language](Locale::language) and
+/// [region](Locale::region), such as `zh-HK` for `zh-Hant-HK` and `de-CH` for `de-CH-1901`, or
+/// to only its language if it doesn't have a region, such as `zh` for `zh-Hant`. Each pair is
+/// only returned the first time it appears, and locales which aren't well-formed BCP-47
+/// language tags are skipped.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_language_region_tags;
+///
+/// // For example, `["zh-HK", "en"]` for the locales `["zh-Hant-HK", "zh-HK", "en"]`.
+/// println!("The user's locales are {:?}", get_language_region_tags());
+/// ```
+pub fn get_language_region_tags() -> Vec<String> {
+    let tags = get_locales().filter_map(|tag| {
+        let locale = Locale::from_tag(&tag)?;
+        let mut tag = String::from(locale.language());
+        if let Some(region) = locale.region() {
+            tag.push('-');
+            tag.push_str(region);
+        }
+        Some(tag)
+    });
+    dedup(tags).collect()
+}
+
+/// Returns the entry of `supported` which best matches the user's preferences, such as the
+/// languages which an application has translations for.
+///
+/// This follows the lookup matching of RFC 4647: each preferred locale from [`get_locales`] is
+/// tried in order, along with its [fallback chain](expand_fallback_chain), and the first entry of
+/// `supported` which is equal to one of them is returned. A more specific match for a preferred
+/// locale is found before a less specific one, so `en-GB` matches `en-GB` over `en`, and `en`
+/// matches a preference of `en-GB` if nothing more specific is supported. The tags are compared
+/// without regard to their casing.
+///
+/// # Returns
+///
+/// Returns `Some(String)` with the matching entry of `supported` inside. If none of the preferred
+/// locales match, `None` is returned instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::best_match;
+///
+/// let language = best_match(&["en", "de", "pt-BR"]).unwrap_or_else(|| String::from("en"));
+///
+/// println!("Showing the {} translation", language);
+/// ```
+pub fn best_match(supported: &[&str]) -> Option<String> {
+    lookup(get_locales(), supported)
+}
+
+/// Returns every entry of `supported` which matches one of the user's preferred locales, in the
+/// user's order of preference, such as the translations which an application was built with.
+///
+/// Each preferred locale from [`get_locales`] is matched in the same way as by [`best_match`],
+/// so it falls back to its language if nothing more specific is supported. An entry is only
+/// returned once, for the first preferred locale which matches it.
+///
+/// # Returns
+///
+/// Returns a `Vec` with the matching entries of `supported` inside. If none of the preferred
+/// locales match, the vec will be empty, so that the application can use its own default.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::{get_supported_locales, SupportedLocales};
+///
+/// const SUPPORTED: SupportedLocales = SupportedLocales::new(&["en", "de", "pt-BR"]);
+///
+/// let languages = get_supported_locales(&SUPPORTED);
+/// println!("Showing the {:?} translations", languages);
+/// ```
+pub fn get_supported_locales(supported: &SupportedLocales) -> Vec<String> {
+    lookup_all(get_locales(), supported.tags())
+}
+
+/// Returns the entry of `supported` which best matches `preferences`, rather than the user's
+/// preferred locales, such as the tags of an HTTP `Accept-Language` header from
+/// [`parse_accept_language`].
+///
+/// The tags are matched in the same way as by [`best_match`]. As in the lookup matching of
+/// RFC 4647, the wildcard `*` is ignored.
+///
+/// # Example
+///
+/// ```
+/// use sys_locale::{best_match_for, parse_accept_language};
+///
+/// let preferences = parse_accept_language("fr-CH, fr;q=0.9, en;q=0.8");
+///
+/// assert_eq!(best_match_for(&preferences, &["en", "fr"]).as_deref(), Some("fr"));
+/// ```
+pub fn best_match_for(preferences: &[String], supported: &[&str]) -> Option<String> {
+    lookup(
+        preferences.iter().filter(|tag| *tag != "*").cloned(),
+        supported,
+    )
+}
+
+/// Parses the language ranges of an HTTP `Accept-Language` header, such as
+/// `en-US,en;q=0.9,fr;q=0.8`, for server-side code where the system's locale isn't the user's.
+///
+/// # Returns
+///
+/// Returns the tags of the header in their canonical casing, from the highest quality value to the
+/// lowest, with those of the same quality in the order of the header. The wildcard `*` is kept.
+///
+/// Ranges with a quality of `0`, which marks them as not acceptable, are left out, as are those
+/// which aren't well-formed tags. A quality value which is malformed or out of range is treated as
+/// `1`, which is also the default when it's missing. See [`parse_accept_language_weights`] for
+/// the quality values too.
+///
+/// # Example
+///
+/// ```
+/// use sys_locale::parse_accept_language;
+///
+/// assert_eq!(
+///     parse_accept_language("en-us, fr;q=0.8, en;q=0.9"),
+///     ["en-US", "en", "fr"]
+/// );
+/// ```
+pub fn parse_accept_language(header: &str) -> Vec<String> {
+    parse_accept_language_weights(header)
+        .into_iter()
+        .map(|(tag, _)| tag)
+        .collect()
+}
+
+/// Parses the language ranges of an HTTP `Accept-Language` header along with their quality
+/// values, in the same way as [`parse_accept_language`].
+///
+/// The result can be used as the weights of [`rank_against`].
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::{parse_accept_language_weights, rank_against};
+///
+/// let weights = parse_accept_language_weights("fr-CH, fr;q=0.9, *;q=0.5");
+/// let weights = weights
+///     .iter()
+///     .map(|(tag, weight)| (tag.as_str(), *weight))
+///     .collect::<Vec<_>>();
+///
+/// let ranked = rank_against(&weights);
+/// ```
+pub fn parse_accept_language_weights(header: &str) -> Vec<(String, f32)> {
+    let mut ranges = header
+        .split(',')
+        .filter_map(|range| {
+            let mut parts = range.split(';');
+            let tag = parts.next().unwrap_or_default().trim();
+            let tag = if tag == "*" {
+                String::from(tag)
+            } else {
+                Locale::from_tag(tag)?.to_tag()
+            };
+
+            let quality = parts
+                .filter_map(|parameter| {
+                    let (name, value) = parameter.split_at(parameter.find('=')?);
+                    if name.trim().eq_ignore_ascii_case("q") {
+                        Some(value[1..].trim())
+                    } else {
+                        None
+                    }
+                })
+                .next()
+                .and_then(|value| value.parse::<f32>().ok())
+                .filter(|quality| (0.0..=1.0).contains(quality))
+                .unwrap_or(1.0);
+            if quality == 0.0 {
+                None
+            } else {
+                Some((tag, quality))
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // The sort is stable, so ranges with the same quality keep their order in the header.
+    ranges.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(core::cmp::Ordering::Equal));
+    ranges
+}
+
+/// Finds the first entry of `supported` in the fallback chains of `preferences`.
+fn lookup(preferences: impl Iterator<Item = String>, supported: &[&str]) -> Option<String> {
+    preferences
+        .flat_map(|tag| expand_fallback_chain(&tag))
+        .find_map(|candidate| {
+            supported
+                .iter()
+                .find(|tag| tag.eq_ignore_ascii_case(&candidate))
+                .map(|tag| String::from(*tag))
+        })
+}
+
+/// Matches each of `preferences` with [`lookup`], returning every match once in the order of the
+/// preferences.
+fn lookup_all(preferences: impl Iterator<Item = String>, supported: &[&str]) -> Vec<String> {
+    let mut matches = Vec::new();
+    for tag in preferences.filter_map(|tag| lookup(core::iter::once(tag), supported)) {
+        if !matches.contains(&tag) {
+            matches.push(tag);
+        }
+    }
+    matches
+}
+
+/// Scores each of the user's preferred locales against a weighted list of language ranges, such
+/// as those of an HTTP `Accept-Language` header, and returns them sorted by their score.
+///
+/// Each locale from [`get_locales`] is scored with the weight of the most specific entry of
+/// `weights` which matches it, comparing their languages and then their regions:
+/// - An entry with a region, such as `en-GB`, only matches locales with the same language and
+///   region, such as `en-GB` and `en-Latn-GB`.
+/// - An entry without a region, such as `en`, matches every locale with the same language, unless
+///   one with the locale's region also matches.
+/// - The wildcard `*` matches every locale, unless another entry does.
+///
+/// If several equally specific entries match, the highest weight is used. Locales which no entry
+/// matches have a score of `0.0`. The tags are compared without regard to their casing.
+///
+/// # Returns
+///
+/// Returns a `Vec` with each preferred locale and its score, from the highest score to the
+/// lowest. Locales with the same score keep the user's order of preference.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::rank_against;
+///
+/// // Parsed from `Accept-Language: fr-CH, fr;q=0.9, en;q=0.8, *;q=0.5`.
+/// let weights = [("fr-CH", 1.0), ("fr", 0.9), ("en", 0.8), ("*", 0.5)];
+///
+/// for (locale, score) in rank_against(&weights) {
+///     println!("{} scored {}", locale, score);
+/// }
+/// ```
+pub fn rank_against(weights: &[(&str, f32)]) -> Vec<(String, f32)> {
+    rank(get_locales(), weights)
+}
+
+/// Scores and sorts `preferences` against `weights`, as in [`rank_against`].
+fn rank(preferences: impl Iterator<Item = String>, weights: &[(&str, f32)]) -> Vec<(String, f32)> {
+    let weights = weights
+        .iter()
+        .filter_map(|&(range, weight)| {
+            if range == "*" {
+                return Some((None, weight));
+            }
+            Some((Some(Locale::from_tag(range)?), weight))
+        })
+        .collect::<Vec<_>>();
+
+    let mut ranked = preferences
+        .map(|tag| {
+            let locale = Locale::from_tag(&tag);
+            // The specificity of each entry which matches, with the region being most specific.
+            let score = weights
+                .iter()
+                .filter_map(|(range, weight)| {
+                    let specificity = match (range, &locale) {
+                        (None, _) => 0,
+                        (Some(range), Some(locale)) if range.language() == locale.language() => {
+                            match range.region() {
+                                None => 1,
+                                Some(region) if locale.region() == Some(region) => 2,
+                                Some(_) => return None,
+                            }
+                        }
+                        (Some(_), _) => return None,
+                    };
+                    Some((specificity, *weight))
+                })
+                .fold(
+                    None,
+                    |best: Option<(u8, f32)>, (specificity, weight)| match best {
+                        Some((best_specificity, best_weight))
+                            if best_specificity > specificity
+                                || (best_specificity == specificity && best_weight >= weight) =>
+                        {
+                            best
+                        }
+                        _ => Some((specificity, weight)),
+                    },
+                )
+                .map_or(0.0, |(_, weight)| weight);
+            (tag, score)
+        })
+        .collect::<Vec<_>>();
+
+    // The sort is stable, so locales with the same score keep their order of preference.
+    ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(core::cmp::Ordering::Equal));
+    ranked
+}
+
+/// Adds the likely script and region to a BCP-47 language tag, such as `zh-Hans-CN` for `zh`,
+/// following the likely subtags of the CLDR.
+///
+/// This doesn't depend on the platform, so it's available everywhere, including without the
+/// standard library. It only contains a curated subset of the CLDR's data, which covers the most
+/// widely used languages and the regions where they're written in other scripts, such as `zh-TW`
+/// becoming `zh-Hant-TW`. Rare languages may not be covered. Any script or region which the tag
+/// already has is kept, along with its variants and extensions.
+///
+/// # Returns
+///
+/// Returns `Some(String)` with the maximized tag in its canonical casing inside. If the tag isn't
+/// well-formed, or its language isn't covered, `None` is returned instead.
+///
+/// # Example
+///
+/// ```
+/// use sys_locale::maximize;
+///
+/// assert_eq!(maximize("zh").as_deref(), Some("zh-Hans-CN"));
+/// assert_eq!(maximize("zh-TW").as_deref(), Some("zh-Hant-TW"));
+/// assert_eq!(maximize("en-GB").as_deref(), Some("en-Latn-GB"));
+/// ```
+pub fn maximize(tag: &str) -> Option<String> {
+    let locale = Locale::from_tag(tag)?;
+    let (script, region) =
+        likely::likely_subtags(locale.language(), locale.script(), locale.region())?;
+    Some(
+        locale
+            .with_script_and_region(Some(script), Some(region))
+            .to_tag(),
+    )
+}
+
+/// Removes the script and region from a BCP-47 language tag where they're the likely ones, such
+/// as `zh` for `zh-Hans-CN`, following the likely subtags of the CLDR.
+///
+/// This is the reverse of [`maximize`], using the same data: a subtag is removed if
+/// maximizing the shorter tag adds it back. Removing the script is preferred over removing the
+/// region, so `zh-Hant-TW` becomes `zh-TW` rather than `zh-Hant`. The variants and extensions of
+/// the tag are kept.
+///
+/// # Returns
+///
+/// Returns `Some(String)` with the minimized tag in its canonical casing inside. If the tag isn't
+/// well-formed, or its language isn't covered and it doesn't have both a script and region,
+/// `None` is returned instead.
+///
+/// # Example
+///
+/// ```
+/// use sys_locale::minimize;
+///
+/// assert_eq!(minimize("zh-Hans-CN").as_deref(), Some("zh"));
+/// assert_eq!(minimize("zh-Hant-TW").as_deref(), Some("zh-TW"));
+/// assert_eq!(minimize("en-Latn-GB").as_deref(), Some("en-GB"));
+/// ```
+pub fn minimize(tag: &str) -> Option<String> {
+    let locale = Locale::from_tag(tag)?;
+    let (script, region) =
+        likely::minimal_subtags(locale.language(), locale.script(), locale.region())?;
+    Some(locale.with_script_and_region(script, region).to_tag())
+}
+
+/// Converts the language of a BCP-47 language tag between its two-letter ISO 639-1 code and
+/// its three-letter ISO 639-2 code, such as `de` and `deu`, so that tags from sources which use
+/// either can be compared.
+///
+/// This doesn't depend on the platform, so it's available everywhere, including without the
+/// standard library. It only contains a curated table of the most widely used languages. The
+/// bibliographic codes of ISO 639-2, such as `ger`, are also understood, but are converted into
+/// the terminology codes, such as `deu`, when three letters are preferred. Languages which don't
+/// have a two-letter code, such as `haw` (Hawaiian), or which aren't covered, keep their code
+/// even if two letters are preferred. The rest of the tag is kept.
+///
+/// # Returns
+///
+/// Returns `Some(String)` with the converted tag in its canonical casing inside. If the tag isn't
+/// well-formed, `None` is returned instead.
+///
+/// # Example
+///
+/// ```
+/// use sys_locale::{normalize_language_code, CodeLength};
+///
+/// assert_eq!(normalize_language_code("deu-CH", CodeLength::Two).as_deref(), Some("de-CH"));
+/// assert_eq!(normalize_language_code("de", CodeLength::Three).as_deref(), Some("deu"));
+/// assert_eq!(normalize_language_code("haw", CodeLength::Two).as_deref(), Some("haw"));
+/// ```
+pub fn normalize_language_code(tag: &str, prefer: CodeLength) -> Option<String> {
+    let locale = Locale::from_tag(tag)?;
+    Some(match codes::convert(locale.language(), prefer) {
+        Some(language) => locale.with_language(language).to_tag(),
+        None => locale.to_tag(),
+    })
+}
+
+/// Checks whether a BCP-47 language tag is for Cantonese, ignoring its casing.
+///
+/// Cantonese has its own language code, `yue`, which is also written as the extended language
+/// subtag of `zh-yue`. It's often tagged as Chinese in Hong Kong or Macau instead, such as
+/// `zh-Hant-HK`, which is what Cantonese is mostly spoken as there, so those are recognized
+/// too. Chinese in those regions which is explicitly written in Simplified Han, as in
+/// `zh-Hans-HK`, is assumed to be Mandarin.
+///
+/// Like the rest of the crate, the subtags may be separated by `_` instead of `-`, as in
+/// `zh_Hant_HK`.
+///
+/// # Example
+///
+/// ```
+/// use sys_locale::is_cantonese;
+///
+/// assert!(is_cantonese("yue-Hant-HK"));
+/// assert!(is_cantonese("zh-Hant-HK"));
+/// assert!(!is_cantonese("zh-Hant-TW"));
+/// ```
+pub fn is_cantonese(tag: &str) -> bool {
+    let tag = tag.replace('_', "-");
+    let mut subtags = tag.split('-');
+    let language = subtags.next().unwrap_or_default();
+    if language.eq_ignore_ascii_case("yue") {
+        return true;
+    }
+    if !language.eq_ignore_ascii_case("zh") {
+        return false;
+    }
+
+    // Extended language subtags aren't parsed by `Locale`.
+    if matches!(subtags.next(), Some(extlang) if extlang.eq_ignore_ascii_case("yue")) {
+        return true;
+    }
+    match Locale::from_tag(&tag) {
+        Some(locale) => {
+            matches!(locale.region(), Some("HK") | Some("MO")) && locale.script() != Some("Hans")
+        }
+        None => false,
+    }
+}
+
+/// Returns the active locale for the system or application, parsed into its components.
+///
+/// This is equivalent to parsing the result of [`get_locale`] with [`Locale::from_tag`].
+///
+/// # Returns
+///
+/// Returns `Some(Locale)` if the locale could be obtained and is a well-formed BCP-47
+/// language tag. Otherwise, `None` is returned instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_locale_parsed;
+///
+/// if let Some(locale) = get_locale_parsed() {
+///     println!("The language is {}", locale.language());
+/// }
+/// ```
+pub fn get_locale_parsed() -> Option<Locale> {
+    get_locale().and_then(|tag| Locale::from_tag(&tag))
+}
+
+/// Returns the user's most preferred locale as an ICU4X `LanguageIdentifier`, with the
+/// `icu_locale_core` feature.
+///
+/// This is the first of [`get_langids`], so a locale which can't be parsed is skipped in favor of
+/// the next one.
+///
+/// # Example
+///
+/// ```no_run
+/// let langid = sys_locale::get_langid();
+///
+/// if let Some(langid) = langid {
+///     println!("The language is {}", langid.language);
+/// }
+/// ```
+#[cfg(feature = "icu_locale_core")]
+pub fn get_langid() -> Option<icu_locale_core::LanguageIdentifier> {
+    get_locales().find_map(|tag| parse_langid(&tag))
+}
+
+/// Returns the user's preferred locales as ICU4X `LanguageIdentifier`s, in descending order of
+/// preference, with the `icu_locale_core` feature.
+///
+/// These are the locales of [`get_locales`], so they're normalized before being parsed. Any which
+/// still can't be parsed are skipped. Extensions such as `-u-ca-buddhist` aren't part of a
+/// `LanguageIdentifier`, so they're dropped.
+///
+/// # Example
+///
+/// ```no_run
+/// for langid in sys_locale::get_langids() {
+///     println!("{} is a preferred language", langid.language);
+/// }
+/// ```
+#[cfg(feature = "icu_locale_core")]
+pub fn get_langids() -> Vec<icu_locale_core::LanguageIdentifier> {
+    get_locales().filter_map(|tag| parse_langid(&tag)).collect()
+}
+
+#[cfg(feature = "icu_locale_core")]
+fn parse_langid(tag: &str) -> Option<icu_locale_core::LanguageIdentifier> {
+    icu_locale_core::LanguageIdentifier::try_from_locale_bytes(tag.as_bytes()).ok()
+}
+
+/// Returns the locale used for formatting values such as numbers, dates and currencies.
+///
+/// Some platforms allow this to be configured separately from the preferred languages returned by
+/// [`get_locales`], such as an English UI with German number formatting. On platforms where this
+/// isn't configured separately, this is the same as [`get_locale`].
+///
+/// - On Windows, this is the user's default locale (`GetUserDefaultLocaleName`).
+/// - On macOS and iOS, this is the user's current locale (`CFLocaleCopyCurrent`).
+/// - On Linux, BSD, and other UNIX variations, this is the `LC_NUMERIC` locale.
+///
+/// # Returns
+///
+/// Returns `Some(String)` with a BCP-47 language tag inside. If the locale
+/// couldn't be obtained, `None` is returned instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_format_locale;
+///
+/// let format_locale = get_format_locale().unwrap_or_else(|| String::from("en-US"));
+///
+/// println!("Numbers are formatted for {}", format_locale);
+/// ```
+pub fn get_format_locale() -> Option<String> {
+    provider::get_format_locale()
+}
+
+/// Returns the locale used for sorting and comparing text.
+///
+/// On Linux, BSD, and other UNIX variations, this is the `LC_COLLATE` locale, which can be
+/// configured separately from the other categories. It is overridden by `LC_ALL`, and falls
+/// back to `LANG`. On other platforms, this is the same as [`get_format_locale`].
+///
+/// # Returns
+///
+/// Returns `Some(String)` with a BCP-47 language tag inside. If the locale
+/// couldn't be obtained, `None` is returned instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_collation_locale;
+///
+/// let collation_locale = get_collation_locale().unwrap_or_else(|| String::from("en-US"));
+///
+/// println!("Text is sorted for {}", collation_locale);
+/// ```
+pub fn get_collation_locale() -> Option<String> {
+    provider::get_collation_locale()
+}
+
+/// Returns the locale used for a single category of settings, such as the formatting of dates.
+///
+/// On Linux, BSD, and other UNIX variations, each category is configured with its own `LC_*`
+/// environment variable, such as `LC_TIME` for [`LocaleCategory::Time`]. These follow the POSIX
+/// precedence, so `LC_ALL` overrides every category, and `LANG` is used for any category which
+/// isn't set. On other platforms, [`LocaleCategory::Messages`] is the platform's most preferred
+/// language and every other category is the same as [`get_format_locale`].
+///
+/// Unlike [`get_locale`], the `SYS_LOCALE_OVERRIDE` environment variable and [`LocaleProvider`]
+/// aren't used.
+///
+/// # Returns
+///
+/// Returns `Some(String)` with a BCP-47 language tag inside. If the locale
+/// couldn't be obtained, `None` is returned instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::{get_locale_for, LocaleCategory};
+///
+/// if let Some(locale) = get_locale_for(LocaleCategory::Time) {
+///     println!("Dates are formatted for {}", locale);
+/// }
+/// ```
+pub fn get_locale_for(category: LocaleCategory) -> Option<String> {
+    provider::get_locale_for(category)
+}
+
+/// Returns the locale used for every category of settings, such as for showing all of them in a
+/// diagnostics report.
+///
+/// Each category is resolved the same way as [`get_locale_for`], and is returned in the order
+/// of [`LocaleCategory::ALL`]. A category is paired with `None` if its locale couldn't be
+/// obtained, such as when its `LC_*` variable, `LC_ALL`, and `LANG` are all unset.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_all_categories;
+///
+/// for (category, locale) in get_all_categories() {
+///     println!("{:?}: {}", category, locale.as_deref().unwrap_or("(unset)"));
+/// }
+/// ```
+pub fn get_all_categories() -> Vec<(LocaleCategory, Option<String>)> {
+    provider::get_all_categories()
+}
+
+/// Returns the current user's default locale on Windows (`GetUserDefaultLocaleName`).
+///
+/// This is the locale selected in the user's regional settings, which is what [`get_format_locale`]
+/// returns on Windows. [`get_locale`] also follows the current user's settings, but returns their
+/// most preferred UI language (`GetUserPreferredUILanguages`) instead, which may be different.
+///
+/// # Returns
+///
+/// Returns `Some(String)` with a BCP-47 language tag inside. If the locale
+/// couldn't be obtained, `None` is returned instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_user_locale;
+///
+/// println!("The user's locale is {:?}", get_user_locale());
+/// ```
+#[cfg(windows)]
+pub fn get_user_locale() -> Option<String> {
+    windows::get_user_locale()
+}
+
+/// Converts a Windows locale identifier (LCID), such as `0x0409`, into a BCP-47 language tag,
+/// such as `en-US`, with `LCIDToLocaleName`.
+///
+/// This is useful for migrating data which was stored with LCIDs, which have been deprecated in
+/// favor of locale names. Neutral locales, such as `0x0009`, are converted into a tag with only a
+/// language, such as `en`.
+///
+/// # Returns
+///
+/// Returns `Some(String)` with a BCP-47 language tag inside. If Windows doesn't recognize the
+/// LCID, `None` is returned instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::lcid_to_bcp47;
+///
+/// assert_eq!(lcid_to_bcp47(0x0409).as_deref(), Some("en-US"));
+/// ```
+#[cfg(windows)]
+pub fn lcid_to_bcp47(lcid: u32) -> Option<String> {
+    windows::lcid_to_bcp47(lcid)
+}
+
+/// Returns the default locale of the operating system on Windows (`GetSystemDefaultLocaleName`).
+///
+/// Unlike [`get_user_locale`], this is shared by every user on the machine, so it is useful for
+/// services running under a system account, which don't have regional settings of their own.
+///
+/// # Returns
+///
+/// Returns `Some(String)` with a BCP-47 language tag inside. If the locale
+/// couldn't be obtained, `None` is returned instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_system_locale;
+///
+/// println!("The system's locale is {:?}", get_system_locale());
+/// ```
+#[cfg(windows)]
+pub fn get_system_locale() -> Option<String> {
+    windows::get_system_locale()
+}
+
+/// Returns the same settings as [`get_number_separators`], read as `options` describes, such as
+/// without the user's customizations from the Region settings.
+///
+/// This is only available on Windows.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::{get_number_separators_with_options, FormatOptions};
+///
+/// let customized = get_number_separators_with_options(&FormatOptions::default());
+/// let base = get_number_separators_with_options(&FormatOptions {
+///     user_overrides: false,
+/// });
+/// println!("The separators are {:?}, which are {:?} by default", customized, base);
+/// ```
+#[cfg(windows)]
+pub fn get_number_separators_with_options(options: &FormatOptions) -> Option<NumberSeparators> {
+    windows::get_number_separators_with_options(options)
+}
+
+/// Returns the system of measurement units in the same way as [`get_measurement_system`], read as `options` describes.
+///
+/// This is only available on Windows.
+#[cfg(windows)]
+pub fn get_measurement_system_with_options(options: &FormatOptions) -> Option<MeasurementSystem> {
+    windows::get_measurement_system_with_options(options)
+}
+
+/// Returns the default paper size in the same way as [`get_paper_size`], read as `options` describes.
+///
+/// This is only available on Windows.
+#[cfg(windows)]
+pub fn get_paper_size_with_options(options: &FormatOptions) -> Option<PaperSize> {
+    windows::get_paper_size_with_options(options)
+}
+
+/// Returns the list separator in the same way as [`get_list_separator`], read as `options` describes.
+///
+/// This is only available on Windows.
+#[cfg(windows)]
+pub fn get_list_separator_with_options(options: &FormatOptions) -> Option<char> {
+    windows::get_list_separator_with_options(options)
+}
+
+/// Returns the sizes of the digit groups in the same way as [`get_grouping_sizes`], read as `options` describes.
+///
+/// This is only available on Windows.
+#[cfg(windows)]
+pub fn get_grouping_sizes_with_options(options: &FormatOptions) -> Option<Vec<u8>> {
+    windows::get_grouping_sizes_with_options(options)
+}
+
+/// Returns the first day of the week in the same way as [`get_first_day_of_week`], read as `options` describes.
+///
+/// This is only available on Windows.
+#[cfg(windows)]
+pub fn get_first_day_of_week_with_options(options: &FormatOptions) -> Option<Weekday> {
+    windows::get_first_day_of_week_with_options(options)
+}
+
+/// Returns the names of the months and days in the same way as [`get_datetime_symbols`], read as `options` describes.
+///
+/// This is only available on Windows.
+#[cfg(windows)]
+pub fn get_datetime_symbols_with_options(options: &FormatOptions) -> Option<DateTimeSymbols> {
+    windows::get_datetime_symbols_with_options(options)
+}
+
+/// Returns the currency code in the same way as [`get_currency_code`], read as `options` describes.
+///
+/// This is only available on Windows.
+#[cfg(windows)]
+pub fn get_currency_code_with_options(options: &FormatOptions) -> Option<String> {
+    windows::get_currency_code_with_options(options)
+}
+
+/// Returns the percent symbol in the same way as [`get_percent_symbol`], read as `options` describes.
+///
+/// This is only available on Windows.
+#[cfg(windows)]
+pub fn get_percent_symbol_with_options(options: &FormatOptions) -> Option<PercentSymbol> {
+    windows::get_percent_symbol_with_options(options)
+}
+
+/// Returns the plus and minus signs in the same way as [`get_sign_symbols`], read as `options` describes.
+///
+/// This is only available on Windows.
+#[cfg(windows)]
+pub fn get_sign_symbols_with_options(options: &FormatOptions) -> Option<SignSymbols> {
+    windows::get_sign_symbols_with_options(options)
+}
+
+/// Returns the order of the date fields in the same way as [`get_date_field_order`], read as `options` describes.
+///
+/// This is only available on Windows.
+#[cfg(windows)]
+pub fn get_date_field_order_with_options(options: &FormatOptions) -> Option<DateOrder> {
+    windows::get_date_field_order_with_options(options)
+}
+
+/// Returns the calendar in the same way as [`get_calendar`], read as `options` describes.
+///
+/// This is only available on Windows.
+#[cfg(windows)]
+pub fn get_calendar_with_options(options: &FormatOptions) -> Option<Calendar> {
+    windows::get_calendar_with_options(options)
+}
+
+/// Returns the numbering system in the same way as [`get_numbering_system`], read as `options` describes.
+///
+/// This is only available on Windows.
+#[cfg(windows)]
+pub fn get_numbering_system_with_options(options: &FormatOptions) -> Option<String> {
+    windows::get_numbering_system_with_options(options)
+}
+
+/// Returns whether the time is shown on a 24-hour clock in the same way as [`uses_24_hour_time`], read as `options` describes.
+///
+/// This is only available on Windows.
+#[cfg(windows)]
+pub fn uses_24_hour_time_with_options(options: &FormatOptions) -> Option<bool> {
+    windows::uses_24_hour_time_with_options(options)
+}
+
+/// Returns the same settings as [`get_locale_settings`], reading the format settings as `options`
+/// describes.
+///
+/// This is only available on Windows.
+#[cfg(windows)]
+pub fn get_locale_settings_with_options(options: &FormatOptions) -> LocaleSettings {
+    LocaleSettings {
+        locale: get_locale(),
+        ..windows::get_locale_settings_with_options(options)
+    }
+}
+
+/// Converts a locale identifier used by macOS and iOS, such as `zh_Hans_CN` or
+/// `en_US@calendar=gregorian`, into a BCP-47 language tag, such as `zh-Hans-CN` or
+/// `en-US-u-ca-gregory`.
+///
+/// This is the same conversion used for the identifiers returned by the platform, such as by
+/// [`get_format_locale`]. Keywords which don't have an equivalent Unicode extension are dropped.
+///
+/// # Returns
+///
+/// Returns `Some(String)` with a BCP-47 language tag inside. If the identifier doesn't convert
+/// into a well-formed tag, `None` is returned instead.
+///
+/// # Example
+///
+/// ```
+/// use sys_locale::identifier_to_bcp47;
+///
+/// assert_eq!(identifier_to_bcp47("pt_BR").as_deref(), Some("pt-BR"));
+/// ```
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+pub fn identifier_to_bcp47(identifier: &str) -> Option<String> {
+    apple::identifier_to_bcp47(identifier)
+}
+
+/// Returns the preferred locales of another user on Linux and the BSDs, such as for a daemon
+/// which acts on behalf of the users of a shared machine, rather than those of the calling
+/// process.
+///
+/// The user's home directory is found in `/etc/passwd`, and the locale is read from the files in
+/// it which configure their session, where later ones take precedence:
+///
+/// - `~/.pam_environment`, which is read by PAM when the user logs in.
+/// - `~/.config/environment.d/*.conf`, which configures the systemd user manager.
+/// - `~/.config/locale.conf`, which is read by the login shell on some distributions.
+///
+/// This is best-effort. Reading another user's files usually requires permission, such as being
+/// run as root, and settings which are only made elsewhere, such as by the desktop environment,
+/// aren't found. The system's default locale in `/etc/locale.conf` isn't used either.
+///
+/// # Returns
+///
+/// Returns the user's locales as BCP-47 language tags, in order of preference. If the user isn't
+/// found, or none of their files can be read or configure the locale, the list is empty.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_locales_for_user;
+///
+/// let locales = get_locales_for_user(1000);
+/// println!("User 1000 prefers {:?}", locales);
+/// ```
+#[cfg(all(
+    unix,
+    not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+))]
+pub fn get_locales_for_user(uid: u32) -> Vec<String> {
+    unix::get_locales_for_user(uid)
+}
+
+/// Returns the region (country) the system or user has configured.
+///
+/// Some platforms allow the region to be set independently of the language,
+/// so this may differ from the region found in [`get_locale`]'s tag, or be
+/// available when that tag doesn't contain a region at all. On WebAssembly with the `maximize`
+/// feature, the likely region is inferred when the tag doesn't contain one.
+///
+/// # Returns
+///
+/// Returns `Some(String)` with an uppercase ISO 3166-1 alpha-2 code inside, such
+/// as `US` or `CH`. If the region couldn't be obtained, `None` is returned instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_region;
+///
+/// let region = get_region().unwrap_or_else(|| String::from("US"));
+///
+/// println!("The region is {}", region);
+/// ```
+pub fn get_region() -> Option<String> {
+    provider::get_region()
+}
+
+/// Returns the system of measurement units the user prefers.
+///
+/// - On macOS and iOS, this is the measurement system of the user's current locale.
+/// - On Windows, this is the measurement system of the user's default locale. Windows only
+///   distinguishes between metric and US units, so [`MeasurementSystem::Uk`] is never returned.
+/// - On Linux, BSD, and other UNIX variations, this is inferred from the region of the
+///   `LC_MEASUREMENT` locale.
+/// - On other platforms, this is inferred from the region of [`get_locale`].
+///
+/// # Returns
+///
+/// Returns `Some(MeasurementSystem)` if the measurement system could be determined.
+/// Otherwise, `None` is returned instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::{get_measurement_system, MeasurementSystem};
+///
+/// match get_measurement_system() {
+///     Some(MeasurementSystem::Metric) | None => println!("It is 10 kilometers away"),
+///     Some(MeasurementSystem::UsCustomary) | Some(MeasurementSystem::Uk) => {
+///         println!("It is 6.2 miles away")
+///     }
+/// }
+/// ```
+pub fn get_measurement_system() -> Option<MeasurementSystem> {
+    provider::get_measurement_system()
+}
+
+/// Returns the size of paper the user prints on by default.
+///
+/// - On Windows, this is the paper size of the user's default locale.
+/// - On Linux with glibc, this is read from the `LC_PAPER` locale if it's installed, or is
+///   otherwise inferred from its region.
+/// - On other platforms, this is inferred from the region of the user's locale, where the US,
+///   Canada, Mexico, and a few other countries in the Americas use Letter, and the rest of the
+///   world uses A4.
+///
+/// # Returns
+///
+/// Returns `Some(PaperSize)` if the paper size could be determined. Otherwise, `None` is
+/// returned instead, including when the platform uses a paper size other than A4 or Letter.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::{get_paper_size, PaperSize};
+///
+/// let (width, height) = match get_paper_size() {
+///     Some(PaperSize::Letter) => (215.9, 279.4),
+///     Some(PaperSize::A4) | None => (210.0, 297.0),
+/// };
+///
+/// println!("Printing on {} × {} mm paper", width, height);
+/// ```
+pub fn get_paper_size() -> Option<PaperSize> {
+    provider::get_paper_size()
+}
+
+/// Returns the characters the user's locale uses to separate the parts of a number.
+///
+/// - On macOS and iOS, these are the separators of the user's current locale.
+/// - On Windows, these are the separators of the user's default locale.
+/// - On Linux, these are the separators of the `LC_NUMERIC` locale, if it's installed.
+/// - On other platforms, these aren't available.
+///
+/// # Returns
+///
+/// Returns `Some(NumberSeparators)` if the separators could be obtained. Otherwise,
+/// `None` is returned instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_number_separators;
+///
+/// if let Some(separators) = get_number_separators() {
+///     println!("A decimal is written as 1{}5", separators.decimal);
+/// }
+/// ```
+pub fn get_number_separators() -> Option<NumberSeparators> {
+    provider::get_number_separators()
+}
+
+/// Returns the character the user's locale uses to separate the items of a list, such as the
+/// fields of a CSV file which is opened by a spreadsheet.
+///
+/// This is usually `,`, but it's `;` in locales which use `,` as their decimal separator, such
+/// as `de-DE`, so that it doesn't conflict with the numbers in the list.
+///
+/// - On Windows, this is the list separator of the user's default locale (`LOCALE_SLIST`), as
+///   used by Excel.
+/// - On macOS, iOS, and Linux, this is derived from the decimal separator of
+///   [`get_number_separators`], since they don't have a setting for it.
+/// - On other platforms, this isn't available.
+///
+/// # Returns
+///
+/// Returns `Some(char)` if the separator could be obtained. Otherwise, `None` is returned
+/// instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_list_separator;
+///
+/// let separator = get_list_separator().unwrap_or(',');
+///
+/// println!("Name{}Price", separator);
+/// ```
+pub fn get_list_separator() -> Option<char> {
+    provider::get_list_separator()
+}
+
+/// Returns the sizes of the groups of digits in the user's number format, from the group closest
+/// to the decimal separator outwards.
+///
+/// The last size repeats for the rest of the number, so `[3]` groups every three digits, such as
+/// `1,234,567`, and `[3, 2]` groups the first three digits and then every two, such as the
+/// `12,34,567` of Indian English. An empty `Vec` means that digits aren't grouped.
+///
+/// - On macOS and iOS, this is the grouping of the user's current locale's number format.
+/// - On Windows, this is the grouping of the user's default locale (`LOCALE_SGROUPING`).
+/// - On Linux with glibc, this is the grouping of the `LC_NUMERIC` locale, if it's installed.
+/// - On other platforms, this isn't available.
+///
+/// # Returns
+///
+/// Returns `Some(Vec<u8>)` if the grouping could be obtained. Otherwise, `None` is returned
+/// instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_grouping_sizes;
+///
+/// let sizes = get_grouping_sizes().unwrap_or_else(|| vec![3]);
+///
+/// println!("Digits are grouped in {:?}", sizes);
+/// ```
+pub fn get_grouping_sizes() -> Option<Vec<u8>> {
+    provider::get_grouping_sizes()
+}
+
+/// Returns the strings the user's locale uses when formatting dates and times, such as the
+/// AM and PM designators.
+///
+/// - On Windows, these are the designators and separators of the user's default locale.
+/// - On other platforms, these aren't available.
+///
+/// # Returns
+///
+/// Returns `Some(DateTimeSymbols)` if any of the symbols could be obtained. Otherwise,
+/// `None` is returned instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_datetime_symbols;
+///
+/// if let Some(symbols) = get_datetime_symbols() {
+///     println!("Afternoons are written as 1:30 {}", symbols.pm.unwrap_or_default());
+/// }
+/// ```
+pub fn get_datetime_symbols() -> Option<DateTimeSymbols> {
+    provider::get_datetime_symbols()
+}
+
+/// Returns the ISO 4217 code of the currency used in the user's region, such as `USD` or `EUR`.
+///
+/// - On macOS and iOS, this is the currency of the user's current locale.
+/// - On Windows, this is the international currency symbol of the user's default locale.
+/// - On Linux with glibc, this is the `int_curr_symbol` of the `LC_MONETARY` locale, if it's installed.
+/// - On other platforms, this isn't available.
+///
+/// # Returns
+///
+/// Returns `Some(String)` with an uppercase, three letter currency code inside. If the currency
+/// couldn't be obtained, `None` is returned instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_currency_code;
+///
+/// let currency = get_currency_code().unwrap_or_else(|| String::from("USD"));
+///
+/// println!("Prices are shown in {}", currency);
+/// ```
+pub fn get_currency_code() -> Option<String> {
+    provider::get_currency_code()
+}
+
+/// Returns how the user's locale formats amounts of money, such as whether the currency symbol
+/// leads or trails the amount.
+///
+/// - On macOS and iOS, this is read from the currency pattern of the user's current locale.
+/// - On other platforms, this isn't available.
+///
+/// # Returns
+///
+/// Returns `Some(CurrencyFormat)` if the format could be determined. Otherwise, `None` is
+/// returned instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::{get_currency_format, CurrencySymbolPosition};
+///
+/// let price = match get_currency_format().map(|format| format.symbol_position) {
+///     Some(CurrencySymbolPosition::After) => "1.50 €",
+///     Some(CurrencySymbolPosition::Before) | None => "€1.50",
+/// };
+///
+/// println!("It costs {}", price);
+/// ```
+pub fn get_currency_format() -> Option<CurrencyFormat> {
+    provider::get_currency_format()
+}
+
+/// Returns the symbols the user's locale uses when formatting percentages, such as the `%` and
+/// whether it's separated from the number by a space, as in the French `50 %`.
+///
+/// - On macOS and iOS, these are the percent and per-mille symbols of the user's current locale.
+/// - On Windows, these are the percent and per-mille symbols of the user's default locale.
+/// - On other platforms, these aren't available, including on Linux, where `LC_NUMERIC` doesn't
+///   define them.
+///
+/// # Returns
+///
+/// Returns `Some(PercentSymbol)` if the percent sign could be obtained. Otherwise, `None` is
+/// returned instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_percent_symbol;
+///
+/// let percentage = match get_percent_symbol() {
+///     Some(percent) if percent.space_separated == Some(true) => format!("50\u{a0}{}", percent.symbol),
+///     Some(percent) => format!("50{}", percent.symbol),
+///     None => String::from("50%"),
+/// };
+///
+/// println!("It's {} done", percentage);
+/// ```
+pub fn get_percent_symbol() -> Option<PercentSymbol> {
+    provider::get_percent_symbol()
+}
+
+/// Returns the signs the user's locale writes to show whether a number is positive or negative,
+/// which aren't always the ASCII `+` and `-`, such as the minus sign (U+2212) in Swedish.
+///
+/// - On macOS and iOS, these are the plus and minus signs of the user's current locale.
+/// - On Windows, these are the positive and negative signs of the user's default locale.
+/// - On Linux with glibc, these are the signs of the `LC_NUMERIC` locale. glibc only defines
+///   them for monetary amounts, which use the same signs as other numbers.
+/// - On other platforms, these aren't available.
+///
+/// # Returns
+///
+/// Returns `Some(SignSymbols)` if the minus sign could be obtained. Otherwise, `None` is returned
+/// instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_sign_symbols;
+///
+/// let minus = get_sign_symbols().map_or_else(|| String::from("-"), |signs| signs.negative);
+/// println!("Five below zero is {}5", minus);
+/// ```
+pub fn get_sign_symbols() -> Option<SignSymbols> {
+    provider::get_sign_symbols()
+}
+
+/// Returns the IANA name of the user's time zone, such as `Europe/Paris`, with the `time-zone`
+/// feature.
+///
+/// The time zone isn't part of the locale, but is often shown alongside it, such as in a summary
+/// of the user's settings.
+///
+/// - On macOS and iOS, this is the name of the default time zone, which is `NSTimeZone.local`
+///   unless the app has changed it.
+/// - On Windows, this is the `TimeZoneKeyName` of the current time zone, such as
+///   `Romance Standard Time`, converted into the IANA name of the zone which CLDR uses for it.
+/// - On Linux and other UNIX variations, this is read from the `TZ` environment variable, or
+///   otherwise from `/etc/timezone` or the target of the `/etc/localtime` symlink.
+/// - On Android, this is the `persist.sys.timezone` system property.
+/// - On WebAssembly with the `js` feature, this is the time zone of `Intl.DateTimeFormat`.
+/// - On other platforms, this isn't available.
+///
+/// # Returns
+///
+/// Returns `Some(String)` with an IANA time zone name inside. If the time zone couldn't be
+/// obtained, or it doesn't have an IANA name, `None` is returned instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_time_zone;
+///
+/// println!("Your time zone is {}", get_time_zone().unwrap_or_else(|| String::from("unknown")));
+/// ```
+#[cfg(feature = "time-zone")]
+pub fn get_time_zone() -> Option<String> {
+    provider::get_time_zone()
+}
+
+/// Returns a human-readable name of the user's locale, such as "English locale for the USA",
+/// which is only intended to be displayed, such as in a diagnostics report.
+///
+/// - On Linux with glibc, this is the title of the `LC_IDENTIFICATION` locale, or otherwise the
+///   name of its language, if it's installed.
+/// - On other platforms, this isn't available.
+///
+/// The name is written in whichever language the platform provides it in, which is usually
+/// English, so it shouldn't be parsed or shown as a translated name.
+///
+/// # Returns
+///
+/// Returns `Some(String)` if the name could be obtained. Otherwise, `None` is returned instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::{get_locale, get_locale_display_name};
+///
+/// println!(
+///     "Locale: {} ({})",
+///     get_locale().unwrap_or_default(),
+///     get_locale_display_name().unwrap_or_default(),
+/// );
+/// ```
+pub fn get_locale_display_name() -> Option<String> {
+    provider::get_locale_display_name()
+}
+
+/// Returns the name of the locale `tag`, such as "français (Canada)" for `fr-CA`, written in the
+/// language of `in_locale`, or of the user's current locale if it's `None`.
+///
+/// This is intended for showing the locales in a language picker, where each is often shown
+/// in its own language, by passing the same tag as both arguments.
+///
+/// - On macOS and iOS, this is the name provided by `NSLocale`.
+/// - On Windows, names are only available in English, in the language of `tag` itself, or in
+///   the user's UI language, which is also used when `in_locale` is `None`.
+/// - On other platforms, this isn't available, since they don't provide a database of names.
+///
+/// # Returns
+///
+/// Returns `Some(String)` if the name could be obtained. Otherwise, `None` is returned
+/// instead, including when the platform doesn't recognize `tag` or `in_locale`.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::display_name_of;
+///
+/// for tag in ["en-US", "fr-CA", "ja-JP"].iter() {
+///     let name = display_name_of(tag, Some(tag)).unwrap_or_else(|| tag.to_string());
+///     println!("{}: {}", tag, name);
+/// }
+/// ```
+pub fn display_name_of(tag: &str, in_locale: Option<&str>) -> Option<String> {
+    provider::display_name_of(tag, in_locale)
+}
+
+/// Returns the name of the locale `tag` written in its own language, such as "Deutsch" for `de`
+/// or "日本語" for `ja`, for labeling each language in a language picker.
+///
+/// Unlike [`display_name_of`], which writes the name in a language picked by the caller, this is
+/// always the locale's own name for itself, whatever the user's language is.
+///
+/// - On macOS and iOS, this is the name which `NSLocale` gives the locale in its own language,
+///   including its region, such as "Deutsch (Schweiz)" for `de-CH`.
+/// - On Windows, this is the native name of the locale's language, from
+///   `LOCALE_SNATIVELANGUAGENAME`, so it's "Deutsch" for `de-CH`.
+/// - On other platforms, this isn't available, since they don't provide a database of names.
+///
+/// # Returns
+///
+/// Returns `Some(String)` if the name could be obtained. Otherwise, `None` is returned
+/// instead, including when the platform doesn't recognize `tag`.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::native_name_of;
+///
+/// for tag in ["de", "ja", "ar"].iter() {
+///     let name = native_name_of(tag).unwrap_or_else(|| tag.to_string());
+///     println!("{}: {}", tag, name);
+/// }
+/// ```
+pub fn native_name_of(tag: &str) -> Option<String> {
+    provider::native_name_of(tag)
+}
+
+/// Returns the day which the user's calendar weeks start on.
+///
+/// - On macOS and iOS, this is the first weekday of the user's current calendar.
+/// - On Windows, this is the first day of the week of the user's default locale.
+/// - On Linux with glibc, this is the first weekday of the `LC_TIME` locale, if it's installed.
+/// - On other platforms, this isn't available.
+///
+/// # Returns
+///
+/// Returns `Some(Weekday)` if the first day of the week could be obtained. Otherwise,
+/// `None` is returned instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::{get_first_day_of_week, Weekday};
+///
+/// let first_day = get_first_day_of_week().unwrap_or(Weekday::Monday);
+///
+/// println!("The calendar starts on {:?}", first_day);
+/// ```
+pub fn get_first_day_of_week() -> Option<Weekday> {
+    provider::get_first_day_of_week()
+}
+
+/// Returns the first and last days of the weekend in the user's region, such as Saturday and
+/// Sunday in most of the world, or Friday and Saturday in much of the Middle East.
+///
+/// No platform provides the weekend through an API which this crate uses, so it's inferred from
+/// the region following the CLDR. On Linux, BSD, and other UNIX variations, this is the region of
+/// the `LC_TIME` locale, and on other platforms it's the region from [`get_region`].
+///
+/// # Returns
+///
+/// Returns `Some((Weekday, Weekday))` with the first and last days of the weekend, which are the
+/// same day if the weekend is only one day long. If the region couldn't be obtained, `None` is
+/// returned instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::{get_weekend_days, Weekday};
+///
+/// let (first, last) = get_weekend_days().unwrap_or((Weekday::Saturday, Weekday::Sunday));
+///
+/// println!("The weekend is from {:?} to {:?}", first, last);
+/// ```
+pub fn get_weekend_days() -> Option<(Weekday, Weekday)> {
+    provider::get_weekend_days()
+}
+
+/// Returns whether the user prefers times to be shown on a 24-hour clock, rather than
+/// a 12-hour clock with AM and PM.
+///
+/// - On macOS and iOS, this is the hour format of the user's current locale, including any
+///   override of the 24-hour time setting.
+/// - On Windows, this is the hour format of the user's default time format.
+/// - On Linux, this is the hour format of the `LC_TIME` locale's time format, if it's installed.
+/// - On other platforms, this isn't available.
+///
+/// # Returns
+///
+/// Returns `Some(bool)` if the hour format could be obtained. Otherwise, `None` is returned instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::uses_24_hour_time;
+///
+/// if uses_24_hour_time().unwrap_or(true) {
+///     println!("It is 14:30");
+/// } else {
+///     println!("It is 2:30 PM");
+/// }
+/// ```
+pub fn uses_24_hour_time() -> Option<bool> {
+    provider::uses_24_hour_time()
+}
+
+/// Returns the order in which the user's dates are written, such as day, month, then year.
+///
+/// - On macOS and iOS, this is the order of the user's current locale's short date format.
+/// - On Windows, this is the order of the user's default short date format.
+/// - On Linux, this is the order of the `LC_TIME` locale's date format, if it's installed.
+/// - On other platforms, this isn't available.
+///
+/// # Returns
+///
+/// Returns `Some(DateOrder)` if the date format could be obtained and writes the day, month, and
+/// year in one of the supported orders. Otherwise, `None` is returned instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::{get_date_field_order, DateOrder};
+///
+/// let placeholder = match get_date_field_order() {
+///     Some(DateOrder::Dmy) => "DD/MM/YYYY",
+///     Some(DateOrder::Mdy) => "MM/DD/YYYY",
+///     Some(DateOrder::Ymd) | None => "YYYY-MM-DD",
+/// };
+///
+/// println!("Enter a date as {}", placeholder);
+/// ```
+pub fn get_date_field_order() -> Option<DateOrder> {
+    provider::get_date_field_order()
+}
+
+/// Returns the calendar which the user's dates are written in by default, such as the Buddhist
+/// calendar in Thailand.
+///
+/// - On macOS and iOS, this is the calendar of the user's current locale.
+/// - On Windows, this is the calendar of the user's default locale.
+/// - On Linux, this is picked by a `@calendar=` modifier or `-u-ca-` keyword in the `LC_TIME`
+///   locale, or is otherwise the default of its region.
+/// - On Android and the web, this is the default of the active locale's region, unless the
+///   locale has a `-u-ca-` keyword. With the `js` feature, `Intl` is asked for the calendar.
+/// - On other platforms, this isn't available.
+///
+/// # Returns
+///
+/// Returns `Some(Calendar)` if the calendar could be determined and is one of those which are
+/// supported. Otherwise, `None` is returned instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::{get_calendar, Calendar};
+///
+/// if let Some(calendar) = get_calendar() {
+///     if calendar != Calendar::Gregorian {
+///         println!("Dates are written in the {:?} calendar", calendar);
+///     }
+/// }
+/// ```
+pub fn get_calendar() -> Option<Calendar> {
+    provider::get_calendar()
+}
+
+/// Returns the CLDR identifier of the numbering system which the user's numbers are written in by
+/// default, such as `latn` for `0123` or `arab` for `٠١٢٣`.
+///
+/// - On macOS and iOS, this is the numbering system of the user's current locale.
+/// - On Windows, this is read from the native digits of the user's default locale, if they're
+///   substituted for Latin digits.
+/// - On Linux, this is picked by a `@numbers=` modifier or `-u-nu-` keyword in the `LC_NUMERIC`
+///   locale.
+/// - On Android and the web, this is picked by a `-u-nu-` keyword in the active locale. With the
+///   `js` feature, `Intl` is asked for the numbering system.
+/// - On other platforms, this isn't available.
+///
+/// Otherwise, it's the default of the locale's language and region, such as `arab` for `ar-EG`
+/// but `latn` for `ar-MA`.
+///
+/// # Returns
+///
+/// Returns `Some(String)` with an identifier such as `latn`, `arab` or `arabext`, or `None` if
+/// the numbering system couldn't be determined.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::get_numbering_system;
+///
+/// if get_numbering_system().as_deref() == Some("latn") {
+///     println!("Numbers are written with the digits 0 to 9");
+/// }
+/// ```
+pub fn get_numbering_system() -> Option<String> {
+    provider::get_numbering_system()
+}
+
+/// Returns a snapshot of the settings derived from the user's locale, such as their region and
+/// number separators, for showing them together.
+///
+/// Each setting is the same as the one returned by its own function, such as [`get_region`] or
+/// [`get_number_separators`], but they're read together where the platform allows it:
+///
+/// - On macOS and iOS, every setting other than the first day of the week is read from the same
+///   `CFLocale`.
+/// - On Linux and other UNIX variations, the environment is only read once, including the
+///   desktop's settings with the `desktop` feature.
+/// - On other platforms, each setting is read separately.
+///
+/// Any setting which the platform doesn't provide is `None`.
+///
+/// # Example
+///
+/// ```no_run
+/// let settings = sys_locale::get_locale_settings();
+///
+/// if let Some(separators) = settings.number_separators {
+///     println!("The decimal separator is {}", separators.decimal);
+/// }
+/// ```
+pub fn get_locale_settings() -> LocaleSettings {
+    LocaleSettings {
+        // The locale may be overridden, so the provider leaves it to `get_locale`.
+        locale: get_locale(),
+        ..provider::get_locale_settings()
+    }
+}
+
+/// Returns the direction in which text is written in the active locale.
+///
+/// This is derived from the script of [`get_locale_parsed`], or the script which its language is
+/// most commonly written in if it doesn't have one, so it's consistent across every platform.
+///
+/// # Returns
+///
+/// Returns `Some(TextDirection)` if the locale could be obtained and parsed. Otherwise,
+/// `None` is returned instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::{get_text_direction, TextDirection};
+///
+/// if get_text_direction() == Some(TextDirection::RightToLeft) {
+///     println!("The layout should be mirrored");
+/// }
+/// ```
+pub fn get_text_direction() -> Option<TextDirection> {
+    get_locale_parsed().map(|locale| TextDirection::for_locale(&locale))
+}
+
+/// Discards everything which this crate has cached about the locale, so that the next call to
+/// any function reads it from the platform again.
+///
+/// This is meant to be called after being notified that the locale has changed, such as from
+/// the callback of [`on_locale_change`]. With the `cache` feature, this discards the locales
+/// cached by `get_locale_cached` and `get_locales_cached`, the same as
+/// `invalidate_locale_cache`. The other settings, such as [`get_region`] and
+/// [`get_number_separators`], are never cached, so they're always read again. Without the
+/// `cache` feature, nothing is cached at all, so this does nothing and costs nothing.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::{get_locale, refresh_all};
+///
+/// // The user changed their locale settings.
+/// refresh_all();
+/// println!("The locale is now {:?}", get_locale());
+/// ```
+pub fn refresh_all() {
+    #[cfg(feature = "cache")]
+    invalidate_locale_cache();
+}
+
+/// A registration for locale change notifications, created by [`on_locale_change`].
+///
+/// Dropping the watcher unregisters it, after which its callback is never called again.
+#[cfg(feature = "notify")]
+pub struct LocaleWatcher {
+    watcher: Option<provider::Watcher>,
+}
+
+#[cfg(feature = "notify")]
+impl LocaleWatcher {
+    /// Returns `true` if the platform supports locale change notifications, and the
+    /// callback will be called when the locale changes.
+    pub fn is_active(&self) -> bool {
+        self.watcher.is_some()
+    }
+}
+
+/// Calls `callback` whenever the user changes their locale settings, until the returned
+/// [`LocaleWatcher`] is dropped.
+///
+/// - On macOS and iOS, this observes `kCFLocaleCurrentLocaleDidChangeNotification`
+///   (`NSCurrentLocaleDidChangeNotification`). The callback is called on the thread that
+///   posted the notification.
+/// - On Windows, this listens for `WM_SETTINGCHANGE` with the `"intl"` section, which is
+///   broadcast when the regional settings change. A hidden window is created to receive it,
+///   whose messages are handled by a dedicated thread, so the caller doesn't need its own
+///   message loop. The callback is called on that thread.
+/// - On other platforms, locale changes can't be observed, so the callback is never called
+///   and the returned watcher does nothing. This can be checked with [`LocaleWatcher::is_active`].
+///
+/// The callback must not panic. Once it's called, any of the other functions in this crate
+/// may be used to read the new locale settings. Call [`refresh_all`] from the callback to keep
+/// anything which is cached up to date.
+///
+/// This requires the `notify` feature.
+///
+/// # Example
+///
+/// ```no_run
+/// use sys_locale::{get_locale, on_locale_change};
+///
+/// let watcher = on_locale_change(|| {
+///     println!("The locale is now {:?}", get_locale());
+/// });
+///
+/// // ... until the application no longer needs to be notified.
+/// drop(watcher);
+/// ```
+#[cfg(feature = "notify")]
+pub fn on_locale_change(callback: impl Fn() + Send + Sync + 'static) -> LocaleWatcher {
+    LocaleWatcher {
+        watcher: provider::watch(alloc::boxed::Box::new(callback)),
+    }
+}
+
+/// Checks whether a BCP-47 language tag is one of the `available` locales, or a less specific tag
+/// of one, such as `en` for `en-US`, without regard to their casing.
+#[cfg(any(feature = "test-stub", all(unix, not(target_os = "android"))))]
+pub(crate) fn is_tag_available(tag: &str, mut available: impl Iterator<Item = String>) -> bool {
+    available.any(|locale| match locale.get(..tag.len()) {
+        Some(prefix) if !tag.is_empty() && prefix.eq_ignore_ascii_case(tag) => {
+            locale.len() == tag.len() || locale.as_bytes()[tag.len()] == b'-'
+        }
+        _ => false,
+    })
+}
+
+/// Extracts the ISO 3166-1 alpha-2 region subtag of a BCP-47 language tag, if present.
+#[cfg(any(
+    test,
+    feature = "test-stub",
+    all(unix, not(any(target_os = "macos", target_os = "ios"))),
+    all(target_family = "wasm", any(feature = "js", feature = "web-sys"))
+))]
+pub(crate) fn region_from_tag(tag: &str) -> Option<String> {
+    Locale::from_tag(tag)?.region().and_then(region_code)
+}
+
+/// Returns the calendar of a BCP-47 language tag, from its `-u-ca-` keyword or its region.
+#[cfg(any(
+    feature = "test-stub",
+    all(unix, not(any(target_os = "macos", target_os = "ios"))),
+    all(target_family = "wasm", any(feature = "js", feature = "web-sys"))
+))]
+pub(crate) fn calendar_from_tag(tag: &str) -> Option<Calendar> {
+    Calendar::for_locale(&Locale::from_tag(tag)?)
+}
+
+/// Returns the CLDR numbering system of a BCP-47 language tag, from its `-u-nu-` keyword or its
+/// language and region.
+#[cfg(any(
+    test,
+    feature = "test-stub",
+    unix,
+    windows,
+    all(target_family = "wasm", any(feature = "js", feature = "web-sys"))
+))]
+pub(crate) fn numbering_system_from_tag(tag: &str) -> Option<String> {
+    Some(settings::numbering_system_for_locale(&Locale::from_tag(
+        tag,
+    )?))
+}
+
+/// Validates and canonicalizes a standalone ISO 3166-1 alpha-2 region code.
+#[cfg(any(
+    test,
+    feature = "test-stub",
+    unix,
+    windows,
+    all(target_family = "wasm", any(feature = "js", feature = "web-sys"))
+))]
+pub(crate) fn region_code(code: &str) -> Option<String> {
+    if code.len() == 2 && code.bytes().all(|b| b.is_ascii_alphabetic()) {
+        Some(code.to_ascii_uppercase())
+    } else {
+        None
+    }
+}
+
+/// Validates and canonicalizes an ISO 4217 currency code, such as `usd` or the padded `USD `.
+#[cfg(any(
+    test,
+    target_os = "macos",
+    target_os = "ios",
+    all(target_os = "linux", target_env = "gnu"),
+    windows
+))]
+pub(crate) fn currency_code(code: &str) -> Option<String> {
+    let code = code.trim();
+    if code.len() == 3 && code.bytes().all(|b| b.is_ascii_alphabetic()) {
+        Some(code.to_ascii_uppercase())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        canonicalize, clear_provider, collect_locales, currency_code, dedup, expand_fallback_chain,
+        get_locale, get_locales, get_locales_cow, get_locales_into, get_locales_validated,
+        get_locales_with_fallbacks, region_from_tag, set_provider, try_get_locales, LocaleError,
+        LocaleProvider,
+    };
+    use alloc::{borrow::Cow, string::String, vec, vec::Vec};
+    extern crate std;
+    #[cfg(feature = "cache")]
+    use super::{get_locale_cached, get_locales_cached, invalidate_locale_cache};
+
+    /// Serializes the tests which change the sources consulted by every thread.
+    static GLOBAL_OVERRIDES: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    /// Holds `GLOBAL_OVERRIDES` and clears the provider once it's dropped, even if the test
+    /// panicked, so that the tests which follow see the platform's locales.
+    struct OverridesGuard {
+        _lock: std::sync::MutexGuard<'static, ()>,
+    }
+
+    impl Drop for OverridesGuard {
+        fn drop(&mut self) {
+            clear_provider();
+        }
+    }
+
+    /// Waits for the other tests which change the overrides, recovering the lock if one of them
+    /// panicked.
+    fn lock_overrides() -> OverridesGuard {
+        OverridesGuard {
+            _lock: GLOBAL_OVERRIDES
+                .lock()
+                .unwrap_or_else(|err| err.into_inner()),
+        }
+    }
+
+    /// Installs `provider` until the returned guard is dropped.
+    fn install_provider(provider: &'static &'static dyn LocaleProvider) -> OverridesGuard {
+        let guard = lock_overrides();
+        set_provider(provider);
+        guard
+    }
+
+    #[test]
+    fn can_obtain_locale() {
+        assert!(get_locale().is_some(), "no locales were returned");
+        let locales = get_locales();
+        for (i, locale) in locales.enumerate() {
+            assert!(!locale.is_empty(), "locale string {} was empty", i);
+            assert!(
+                !locale.ends_with('\0'),
+                "locale {} contained trailing NUL",
+                i
+            );
+        }
+    }
+
+    #[test]
+    fn stable_order() {
+        let _lock = lock_overrides();
+
+        let first = get_locales().collect::<Vec<_>>();
+        for _ in 0..10 {
+            assert_eq!(get_locales().collect::<Vec<_>>(), first);
+        }
+    }
+
+    #[cfg(all(
+        feature = "env-override",
+        not(feature = "no-env"),
+        any(
+            feature = "std",
+            all(
+                unix,
+                not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+            )
+        )
+    ))]
+    #[test]
+    fn env_override() {
+        let _lock = lock_overrides();
+
+        std::env::set_var("SYS_LOCALE_OVERRIDE", "de-DE,,en-US");
+        let locales = get_locales().collect::<Vec<_>>();
+        std::env::remove_var("SYS_LOCALE_OVERRIDE");
+
+        assert_eq!(locales, ["de-DE", "en-US"]);
+    }
+
+    #[cfg(feature = "no-env")]
+    #[test]
+    fn env_override_ignored_without_env() {
+        let _lock = lock_overrides();
+
+        std::env::set_var("SYS_LOCALE_OVERRIDE", "tlh");
+        let locales = get_locales().collect::<Vec<_>>();
+        std::env::remove_var("SYS_LOCALE_OVERRIDE");
+
+        assert!(!locales.iter().any(|locale| locale == "tlh"));
+    }
+
+    #[cfg(all(
+        feature = "env-override",
+        not(feature = "no-env"),
+        any(
+            feature = "std",
+            all(
+                unix,
+                not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+            )
+        )
+    ))]
+    #[test]
+    fn locale_sources() {
+        use super::{get_locales_with_source, LocaleSource};
+
+        struct RemoteConfig;
+        impl LocaleProvider for RemoteConfig {
+            fn get(&self) -> Vec<String> {
+                vec![String::from("pl-PL")]
+            }
+        }
+
+        let _provider = install_provider(&(&RemoteConfig as &dyn LocaleProvider));
+        let provided = get_locales_with_source();
+
+        std::env::set_var("SYS_LOCALE_OVERRIDE", "de-DE,en-us,de-DE");
+        let overridden = get_locales_with_source();
+        std::env::remove_var("SYS_LOCALE_OVERRIDE");
+
+        assert_eq!(
+            provided,
+            [(String::from("pl-PL"), LocaleSource::Application)]
+        );
+        assert_eq!(
+            overridden,
+            [
+                (String::from("de-DE"), LocaleSource::Environment),
+                (String::from("en-US"), LocaleSource::Environment),
+            ]
+        );
+    }
+
+    #[test]
+    fn provider_description() {
+        use super::describe_provider;
+
+        let expected = if cfg!(feature = "test-stub") {
+            "stub"
+        } else if cfg!(target_os = "android") {
+            "android"
+        } else if cfg!(any(target_os = "macos", target_os = "ios")) {
+            "apple"
+        } else if cfg!(unix) {
+            "unix"
+        } else if cfg!(windows) {
+            "windows"
+        } else if cfg!(all(
+            target_family = "wasm",
+            any(feature = "js", feature = "web-sys")
+        )) {
+            "wasm"
+        } else {
+            "unsupported"
+        };
+
+        let info = describe_provider();
+        assert_eq!(info.name, expected);
+        if expected != "unsupported" {
+            assert!(!info.sources.is_empty());
+        }
+        if let Some(active_source) = info.active_source {
+            assert!(info.sources.contains(&active_source));
+        }
+    }
+
+    #[test]
+    fn custom_provider() {
+        struct RemoteConfig;
+        impl LocaleProvider for RemoteConfig {
+            fn get(&self) -> Vec<String> {
+                vec![String::from("tlh"), String::from("eo")]
+            }
+        }
+
+        struct Empty;
+        impl LocaleProvider for Empty {
+            fn get(&self) -> Vec<String> {
+                Vec::new()
+            }
+        }
+
+        let _lock = lock_overrides();
+        let system = get_locales().collect::<Vec<_>>();
+
+        set_provider(&(&RemoteConfig as &dyn LocaleProvider));
+        assert_eq!(get_locale().as_deref(), Some("tlh"));
+        assert_eq!(get_locales().collect::<Vec<_>>(), ["tlh", "eo"]);
+
+        // An empty provider falls back to the platform.
+        set_provider(&(&Empty as &dyn LocaleProvider));
+        assert_eq!(get_locales().collect::<Vec<_>>(), system);
+
+        clear_provider();
+        assert_eq!(get_locales().collect::<Vec<_>>(), system);
+    }
+
+    #[test]
+    fn locales_with_options() {
+        use super::{get_locales_with_options, NormalizationOptions};
+
+        struct Raw;
+        impl LocaleProvider for Raw {
+            fn get(&self) -> Vec<String> {
+                ["de_de.UTF-8@euro", "C.UTF-8", "de-DE", "sr_RS@latin"]
+                    .iter()
+                    .map(|tag| String::from(*tag))
+                    .collect()
+            }
+        }
+
+        let _provider = install_provider(&(&Raw as &dyn LocaleProvider));
+        let normalized = get_locales_with_options(&NormalizationOptions::default());
+        let kept = get_locales_with_options(&NormalizationOptions {
+            rewrite_separator: false,
+            canonical_case: false,
+            ..NormalizationOptions::default()
+        });
+
+        assert_eq!(normalized, ["de-DE", "sr-Latn-RS"]);
+        assert_eq!(kept, ["de_de", "de-DE", "sr_Latn_RS"]);
+    }
+
+    #[cfg(feature = "smallvec")]
+    #[test]
+    fn small_locales() {
+        use super::get_locales_small;
+
+        struct One;
+        impl LocaleProvider for One {
+            fn get(&self) -> Vec<String> {
+                vec![String::from("en-gb"), String::from("en-GB")]
+            }
+        }
+
+        struct Three;
+        impl LocaleProvider for Three {
+            fn get(&self) -> Vec<String> {
+                ["de-DE", "und", "fr", "DE-de", "it"]
+                    .iter()
+                    .map(|tag| String::from(*tag))
+                    .collect()
+            }
+        }
+
+        let _provider = install_provider(&(&One as &dyn LocaleProvider));
+        let inline = get_locales_small();
+        set_provider(&(&Three as &dyn LocaleProvider));
+        let spilled = get_locales_small();
+        let expected = get_locales().collect::<Vec<_>>();
+
+        assert_eq!(inline.as_slice(), ["en-GB"]);
+        assert!(!inline.spilled());
+        assert_eq!(spilled.as_slice(), ["de-DE", "fr", "it"]);
+        assert!(spilled.spilled());
+        assert_eq!(spilled.as_slice(), expected.as_slice());
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn mock_locales_per_thread() {
+        use super::{clear_mock_locales, set_mock_locales};
+
+        let _lock = lock_overrides();
+        let system = get_locales().collect::<Vec<_>>();
+        let spawn = |tag: &'static str| {
+            std::thread::spawn(move || {
+                set_mock_locales(vec![String::from(tag)]);
+                let mut observed = Vec::new();
+                for _ in 0..100 {
+                    observed.push(get_locale());
+                    std::thread::yield_now();
+                }
+                clear_mock_locales();
+                (observed, get_locales().collect::<Vec<_>>())
+            })
+        };
+
+        let german = spawn("de-DE");
+        let japanese = spawn("ja-JP");
+        let (german, german_after) = german.join().unwrap();
+        let (japanese, japanese_after) = japanese.join().unwrap();
+
+        assert!(german.iter().all(|tag| tag.as_deref() == Some("de-DE")));
+        assert!(japanese.iter().all(|tag| tag.as_deref() == Some("ja-JP")));
+
+        // The real locales are used again once the mock is cleared, and were never
+        // visible to the thread which didn't set a mock.
+        assert_eq!(german_after, system);
+        assert_eq!(japanese_after, system);
+        assert_eq!(get_locales().collect::<Vec<_>>(), system);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn locales_timeout() {
+        use super::try_get_locales_timeout;
+        use std::time::Duration;
+
+        struct Slow;
+        impl LocaleProvider for Slow {
+            fn get(&self) -> Vec<String> {
+                std::thread::sleep(Duration::from_millis(500));
+                vec![String::from("fi-FI")]
+            }
+        }
+
+        struct Fast;
+        impl LocaleProvider for Fast {
+            fn get(&self) -> Vec<String> {
+                vec![String::from("sv-FI")]
+            }
+        }
+
+        let _provider = install_provider(&(&Slow as &dyn LocaleProvider));
+        assert_eq!(
+            try_get_locales_timeout(Duration::from_millis(10)),
+            Err(LocaleError::Timeout)
+        );
+
+        set_provider(&(&Fast as &dyn LocaleProvider));
+        let locales = try_get_locales_timeout(Duration::from_secs(5));
+
+        assert_eq!(locales, Ok(vec![String::from("sv-FI")]));
+    }
+
+    #[cfg(feature = "cache")]
+    #[test]
+    fn cache_invalidation() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        struct Counting(AtomicUsize);
+        impl LocaleProvider for Counting {
+            fn get(&self) -> Vec<String> {
+                let reads = self.0.fetch_add(1, Ordering::SeqCst);
+                vec![String::from(if reads == 0 { "en-US" } else { "fr-FR" })]
+            }
+        }
+        static COUNTING: Counting = Counting(AtomicUsize::new(0));
+        static PROVIDER: &dyn LocaleProvider = &COUNTING;
+
+        let _provider = install_provider(&PROVIDER);
+        invalidate_locale_cache();
+
+        assert_eq!(get_locale_cached().as_deref(), Some("en-US"));
+        assert_eq!(get_locales_cached(), ["en-US"]);
+        assert_eq!(COUNTING.0.load(Ordering::SeqCst), 1);
+
+        invalidate_locale_cache();
+        assert_eq!(get_locales_cached(), ["fr-FR"]);
+        assert_eq!(get_locale_cached().as_deref(), Some("fr-FR"));
+        assert_eq!(COUNTING.0.load(Ordering::SeqCst), 2);
+
+        invalidate_locale_cache();
+    }
+
+    #[cfg(all(feature = "cache", feature = "mock"))]
+    #[test]
+    fn refreshed_after_change() {
+        use super::{clear_mock_locales, refresh_all, set_mock_locales};
+
+        let _lock = lock_overrides();
+        set_mock_locales(vec![String::from("en-US")]);
+        refresh_all();
+        assert_eq!(get_locales_cached(), ["en-US"]);
+
+        // The change isn't seen until the caches are refreshed, as from `on_locale_change`.
+        set_mock_locales(vec![String::from("fr-FR"), String::from("en-US")]);
+        assert_eq!(get_locale_cached().as_deref(), Some("en-US"));
+        assert_eq!(get_locale().as_deref(), Some("fr-FR"));
+
+        refresh_all();
+        assert_eq!(get_locales_cached(), ["fr-FR", "en-US"]);
+        assert_eq!(get_locale_cached().as_deref(), Some("fr-FR"));
+
+        clear_mock_locales();
+        refresh_all();
+    }
+
+    #[test]
+    fn locales_into_reused_buffer() {
+        struct Many;
+        impl LocaleProvider for Many {
+            fn get(&self) -> Vec<String> {
+                vec![
+                    String::from("de-DE"),
+                    String::from("en-US"),
+                    String::from("de-de"),
+                    String::from("fr"),
+                ]
+            }
+        }
+
+        struct One;
+        impl LocaleProvider for One {
+            fn get(&self) -> Vec<String> {
+                vec![String::from("ja-JP")]
+            }
+        }
+
+        let _lock = lock_overrides();
+        let mut locales = Vec::new();
+
+        set_provider(&(&Many as &dyn LocaleProvider));
+        get_locales_into(&mut locales);
+        assert_eq!(locales, ["de-DE", "en-US", "fr"]);
+        assert_eq!(locales, get_locales().collect::<Vec<_>>());
+
+        // None of the locales from the first call remain.
+        set_provider(&(&One as &dyn LocaleProvider));
+        get_locales_into(&mut locales);
+        assert_eq!(locales, ["ja-JP"]);
+
+        clear_provider();
+        get_locales_into(&mut locales);
+        assert_eq!(locales, get_locales().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn borrowed_locales() {
+        struct Static;
+        impl LocaleProvider for Static {
+            fn get(&self) -> Vec<String> {
+                self.get_cow().into_iter().map(String::from).collect()
+            }
+
+            fn get_cow(&self) -> Vec<Cow<'static, str>> {
+                ["de-DE", "en-us", "DE-de", "und", "fr"]
+                    .iter()
+                    .map(|tag| Cow::Borrowed(*tag))
+                    .collect()
+            }
+        }
+
+        let _provider = install_provider(&(&Static as &dyn LocaleProvider));
+        let locales = get_locales_cow();
+        assert_eq!(locales, ["de-DE", "en-US", "fr"]);
+        assert_eq!(locales, get_locales().collect::<Vec<_>>());
+
+        // Only the locale which wasn't in its canonical casing is copied.
+        assert!(matches!(locales[0], Cow::Borrowed(_)));
+        assert!(matches!(locales[1], Cow::Owned(_)));
+        assert!(matches!(locales[2], Cow::Borrowed(_)));
+
+        clear_provider();
+        assert_eq!(get_locales_cow(), get_locales().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn validated_locales() {
+        struct Leaky;
+        impl LocaleProvider for Leaky {
+            fn get(&self) -> Vec<String> {
+                vec![
+                    String::from("C"),
+                    String::from("fr-CA"),
+                    String::from(""),
+                    String::from("en-US.UTF-8"),
+                    String::from("POSIX"),
+                    String::from("de-DE@euro"),
+                    String::from("zh-Hant-HK"),
+                    String::from("x-private"),
+                    String::from("zh-yue-HK"),
+                    String::from("en_US"),
+                    String::from("en"),
+                ]
+            }
+        }
+
+        let _provider = install_provider(&(&Leaky as &dyn LocaleProvider));
+        let locales = get_locales_validated();
+
+        assert_eq!(locales, ["fr-CA", "zh-Hant-HK", "zh-yue-HK", "en"]);
+    }
+
+    #[test]
+    fn fallback_chain() {
+        assert_eq!(
+            expand_fallback_chain("zh-Hant-HK"),
+            ["zh-Hant-HK", "zh-Hant", "zh"]
+        );
+        assert_eq!(expand_fallback_chain("en-US"), ["en-US", "en"]);
+        assert_eq!(expand_fallback_chain("fr"), ["fr"]);
+        assert_eq!(
+            expand_fallback_chain("th-TH-u-ca-buddhist"),
+            ["th-TH-u-ca-buddhist", "th-TH-u-ca", "th-TH", "th"]
+        );
+        assert!(expand_fallback_chain("").is_empty());
+    }
+
+    #[test]
+    fn locales_with_fallbacks() {
+        struct Regional;
+        impl LocaleProvider for Regional {
+            fn get(&self) -> Vec<String> {
+                vec![
+                    String::from("en-US"),
+                    String::from("fr"),
+                    String::from("en-GB"),
+                ]
+            }
+        }
+
+        let _provider = install_provider(&(&Regional as &dyn LocaleProvider));
+        let locales = get_locales_with_fallbacks();
+        let lazy = super::fallback_iter().collect::<Vec<_>>();
+
+        assert_eq!(locales, ["en-US", "en", "fr", "en-GB"]);
+        assert_eq!(lazy, locales);
+    }
+
+    #[test]
+    fn fallbacks_stop_early() {
+        use core::cell::Cell;
+
+        let pulled = Cell::new(0);
+        let locales = ["en-US", "fr-CA", "de"].iter().map(|&tag| {
+            pulled.set(pulled.get() + 1);
+            String::from(tag)
+        });
+        let mut fallbacks = super::with_fallbacks(locales);
+
+        assert_eq!(fallbacks.next().as_deref(), Some("en-US"));
+        assert_eq!(fallbacks.next().as_deref(), Some("en"));
+        // The following locales haven't been read yet.
+        assert_eq!(pulled.get(), 1);
+
+        assert_eq!(fallbacks.next().as_deref(), Some("fr-CA"));
+        assert_eq!(pulled.get(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn locale_buf() {
+        use super::{get_locale_buf, scoped_locale};
+
+        let _lock = lock_overrides();
+
+        let mut buf = [0; 35];
+        let mut small = [0; 4];
+        {
+            let _guard = scoped_locale(vec![String::from("en-GB")]);
+            assert_eq!(get_locale_buf(&mut buf), Some("en-GB"));
+            assert_eq!(get_locale_buf(&mut buf[..5]), Some("en-GB"));
+            // The buffer is too small for the tag.
+            assert_eq!(get_locale_buf(&mut small), None);
+            assert_eq!(get_locale_buf(&mut []), None);
+        }
+        assert_eq!(small, [0; 4]);
+
+        let _guard = scoped_locale(Vec::new());
+        assert_eq!(get_locale_buf(&mut buf), None);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn locale_or_fallback() {
+        use super::{get_locale_or_fallback, scoped_locale, LocaleResult};
+
+        let _lock = lock_overrides();
+
+        let detected = {
+            let _guard = scoped_locale(vec![String::from("fr-CA"), String::from("fr")]);
+            get_locale_or_fallback("en-US")
+        };
+        assert_eq!(detected, LocaleResult::Detected(String::from("fr-CA")));
+        assert!(detected.is_detected());
+        assert_eq!(detected.as_str(), "fr-CA");
+
+        let fallback = {
+            let _guard = scoped_locale(Vec::new());
+            get_locale_or_fallback("en-GB")
+        };
+        assert_eq!(fallback, LocaleResult::Fallback(String::from("en-GB")));
+        assert!(!fallback.is_detected());
+        assert_eq!(fallback.into_string(), "en-GB");
+    }
+
+    #[cfg(all(feature = "log", feature = "std"))]
+    #[test]
+    fn logs_resolution() {
+        use super::scoped_locale;
+        use std::sync::Mutex;
+
+        static MESSAGES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+        struct TestLogger;
+        impl log::Log for TestLogger {
+            fn enabled(&self, metadata: &log::Metadata) -> bool {
+                metadata.level() <= log::Level::Debug
+            }
+
+            fn log(&self, record: &log::Record) {
+                let message = alloc::format!("{}", record.args());
+                MESSAGES.lock().unwrap().push(message);
+            }
+
+            fn flush(&self) {}
+        }
+
+        let _lock = lock_overrides();
+        // Only one logger can ever be installed, so no other test may install one.
+        log::set_logger(&TestLogger).unwrap();
+        log::set_max_level(log::LevelFilter::Debug);
+
+        let locales = {
+            let _guard = scoped_locale(vec![String::from("de-de"), String::from("en")]);
+            get_locales().collect::<Vec<_>>()
+        };
+        assert_eq!(locales, ["de-DE", "en"]);
+
+        let messages = MESSAGES.lock().unwrap();
+        assert!(messages
+            .iter()
+            .any(|message| message
+                == r#"Using the locales ["de-de", "en"] overridden on this thread"#));
+        assert!(messages
+            .iter()
+            .any(|message| message == r#"Resolved the locales ["de-DE", "en"]"#));
+    }
+
+    #[cfg(all(feature = "tracing", feature = "std"))]
+    #[test]
+    fn traces_resolution() {
+        use super::scoped_locale;
+        use core::fmt;
+        use std::sync::Mutex;
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata, Subscriber};
+
+        static MESSAGES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+        struct Message<'a>(&'a mut String);
+        impl Visit for Message<'_> {
+            fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+                if field.name() == "message" {
+                    *self.0 = alloc::format!("{:?}", value);
+                }
+            }
+        }
+
+        struct TestSubscriber;
+        impl Subscriber for TestSubscriber {
+            fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+                *metadata.level() <= tracing::Level::DEBUG
+            }
+
+            fn new_span(&self, _span: &Attributes<'_>) -> Id {
+                Id::from_u64(1)
+            }
+
+            fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+            fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+            fn event(&self, event: &Event<'_>) {
+                let mut message = String::new();
+                event.record(&mut Message(&mut message));
+                MESSAGES.lock().unwrap().push(message);
+            }
+
+            fn enter(&self, _span: &Id) {}
+
+            fn exit(&self, _span: &Id) {}
+        }
+
+        let _lock = lock_overrides();
+        // The subscriber only receives the events emitted on this thread.
+        let locales = tracing::subscriber::with_default(TestSubscriber, || {
+            let _guard = scoped_locale(vec![String::from("de-de"), String::from("en")]);
+            get_locales().collect::<Vec<_>>()
+        });
+        assert_eq!(locales, ["de-DE", "en"]);
+
+        let messages = MESSAGES.lock().unwrap();
+        assert!(messages
+            .iter()
+            .any(|message| message
+                == r#"Using the locales ["de-de", "en"] overridden on this thread"#));
+        assert!(messages
+            .iter()
+            .any(|message| message == r#"Resolved the locales ["de-DE", "en"]"#));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn scoped_locales_nest() {
+        use super::scoped_locale;
+
+        let _lock = lock_overrides();
+        let original = get_locales().collect::<Vec<_>>();
+        let locales = || get_locales().collect::<Vec<_>>();
+
+        let outer = scoped_locale(vec![String::from("de-DE"), String::from("en")]);
+        assert_eq!(locales(), ["de-DE", "en"]);
+
+        {
+            let _inner = scoped_locale(vec![String::from("ja-JP")]);
+            assert_eq!(locales(), ["ja-JP"]);
+            assert_eq!(get_locale().as_deref(), Some("ja-JP"));
+
+            let empty = scoped_locale(Vec::new());
+            assert!(locales().is_empty());
+            drop(empty);
+
+            assert_eq!(locales(), ["ja-JP"]);
+        }
+        assert_eq!(locales(), ["de-DE", "en"]);
+
+        // Other threads aren't affected.
+        let other = std::thread::spawn(move || get_locales().collect::<Vec<_>>());
+        assert_eq!(other.join().unwrap(), original);
+
+        drop(outer);
+        assert_eq!(locales(), original);
+
+        // Dropping an outer guard first also ends the inner one.
+        let outer = scoped_locale(vec![String::from("fr")]);
+        let inner = scoped_locale(vec![String::from("it")]);
+        drop(outer);
+        assert_eq!(locales(), original);
+        drop(inner);
+        assert_eq!(locales(), original);
+    }
+
+    #[test]
+    fn primary_languages() {
+        use super::get_primary_languages;
+
+        struct Multilingual;
+        impl LocaleProvider for Multilingual {
+            fn get(&self) -> Vec<String> {
+                vec![
+                    String::from("en-US"),
+                    String::from("en-GB"),
+                    String::from("fr-FR"),
+                    String::from("de_DE.UTF-8"),
+                    String::from("zh-Hant-TW"),
+                    String::from("EN"),
+                    String::from("fr-CA"),
+                ]
+            }
+        }
+
+        let _provider = install_provider(&(&Multilingual as &dyn LocaleProvider));
+        let languages = get_primary_languages();
+
+        assert_eq!(languages, ["en", "fr", "zh"]);
+    }
+
+    #[test]
+    fn language_region_tags() {
+        use super::get_language_region_tags;
+
+        struct Scripted;
+        impl LocaleProvider for Scripted {
+            fn get(&self) -> Vec<String> {
+                vec![
+                    String::from("zh-Hant-HK"),
+                    String::from("de-CH-1901"),
+                    String::from("zh-Hant"),
+                    String::from("zh-HK"),
+                    String::from("de-CH"),
+                ]
+            }
+        }
+
+        let _provider = install_provider(&(&Scripted as &dyn LocaleProvider));
+        let tags = get_language_region_tags();
+
+        assert_eq!(tags, ["zh-HK", "de-CH", "zh"]);
+    }
+
+    #[cfg(feature = "icu_locale_core")]
+    #[test]
+    fn langids() {
+        use super::{get_langid, get_langids};
+
+        struct Mixed;
+        impl LocaleProvider for Mixed {
+            fn get(&self) -> Vec<String> {
+                vec![
+                    String::from("not a locale"),
+                    String::from("th-TH-u-ca-buddhist"),
+                    String::from("zh-hant-tw"),
+                ]
+            }
+        }
+
+        let _provider = install_provider(&(&Mixed as &dyn LocaleProvider));
+        let langid = get_langid();
+        let langids = get_langids();
+
+        let langid = langid.unwrap();
+        assert_eq!(langid.language.as_str(), "th");
+        assert_eq!(langid.script, None);
+        assert_eq!(
+            langid.region.map(|region| region.to_string()).as_deref(),
+            Some("TH")
+        );
+        assert_eq!(langids.len(), 2);
+        assert_eq!(langids[0], langid);
+        assert_eq!(langids[1].to_string(), "zh-Hant-TW");
+    }
+
+    #[test]
+    fn locale_settings_match_getters() {
+        use super::{
+            get_currency_code, get_first_day_of_week, get_locale_settings, get_measurement_system,
+            get_number_separators, get_region, uses_24_hour_time,
+        };
+
+        let _lock = lock_overrides();
+        let settings = get_locale_settings();
+
+        assert_eq!(settings.locale, get_locale());
+        assert_eq!(settings.region, get_region());
+        assert_eq!(settings.measurement_system, get_measurement_system());
+        assert_eq!(settings.currency_code, get_currency_code());
+        assert_eq!(settings.first_day_of_week, get_first_day_of_week());
+        assert_eq!(settings.uses_24_hour_time, uses_24_hour_time());
+        assert_eq!(settings.number_separators, get_number_separators());
+    }
+
+    #[test]
+    fn locales_without_language_skipped() {
+        struct Undetermined;
+        impl LocaleProvider for Undetermined {
+            fn get(&self) -> Vec<String> {
+                vec![
+                    String::from("und"),
+                    String::from("x-custom"),
+                    String::from("en-x-foo"),
+                    String::from("X-Other"),
+                    String::from("UND"),
+                    String::from("und-Latn"),
+                    String::from("und-US"),
+                    String::from("fr"),
+                ]
+            }
+        }
+
+        let _provider = install_provider(&(&Undetermined as &dyn LocaleProvider));
+        let locales = get_locales().collect::<Vec<_>>();
+
+        assert_eq!(locales, ["en-x-foo", "fr"]);
+    }
+
+    #[test]
+    fn undetermined_language() {
+        use super::names_language;
+
+        assert!(!names_language("und"));
+        assert!(!names_language("UND"));
+        assert!(!names_language("und-US"));
+        assert!(!names_language("Und-Latn-US"));
+        assert!(!names_language("x-custom"));
+        assert!(names_language("en-x-und"));
+        assert!(names_language("fr-CA"));
+        // A language code which only starts with `und` isn't undetermined.
+        assert!(names_language("undx"));
+    }
+
+    #[test]
+    fn system_default_appended() {
+        use super::with_default;
+
+        let locales = || vec![String::from("fr-CA"), String::from("en-US")].into_iter();
+
+        assert_eq!(
+            with_default(locales(), Some(String::from("de-DE"))),
+            ["fr-CA", "en-US", "de-DE"]
+        );
+        // The default is only appended if it isn't already one of the locales.
+        assert_eq!(
+            with_default(locales(), Some(String::from("en-us"))),
+            ["fr-CA", "en-US"]
+        );
+        assert_eq!(with_default(locales(), None), ["fr-CA", "en-US"]);
+        assert_eq!(
+            with_default(Vec::new().into_iter(), Some(String::from("en-GB"))),
+            ["en-GB"]
+        );
+    }
+
+    #[test]
+    fn maximized_tags() {
+        use super::maximize;
+
+        assert_eq!(maximize("zh").as_deref(), Some("zh-Hans-CN"));
+        assert_eq!(maximize("zh-HK").as_deref(), Some("zh-Hant-HK"));
+        assert_eq!(maximize("zh-Hant").as_deref(), Some("zh-Hant-TW"));
+        assert_eq!(maximize("en").as_deref(), Some("en-Latn-US"));
+        assert_eq!(maximize("pt").as_deref(), Some("pt-Latn-BR"));
+        assert_eq!(maximize("ja").as_deref(), Some("ja-Jpan-JP"));
+        assert_eq!(maximize("sr-ME").as_deref(), Some("sr-Cyrl-ME"));
+        assert_eq!(
+            maximize("de-CH-1901-u-co-phonebk").as_deref(),
+            Some("de-Latn-CH-1901-u-co-phonebk")
+        );
+
+        // Tags which are already maximal are only canonicalized.
+        assert_eq!(maximize("zh-Hant-TW").as_deref(), Some("zh-Hant-TW"));
+        assert_eq!(maximize("sr-latn-rs").as_deref(), Some("sr-Latn-RS"));
+        assert_eq!(maximize("xx-Latn-US").as_deref(), Some("xx-Latn-US"));
+
+        assert_eq!(maximize("tlh"), None);
+        assert_eq!(maximize("en_US"), None);
+    }
+
+    #[test]
+    fn normalized_language_codes() {
+        use super::{normalize_language_code, CodeLength};
+
+        assert_eq!(
+            normalize_language_code("de", CodeLength::Three).as_deref(),
+            Some("deu")
+        );
+        assert_eq!(
+            normalize_language_code("DEU-at", CodeLength::Two).as_deref(),
+            Some("de-AT")
+        );
+        assert_eq!(
+            normalize_language_code("ger-Latn-CH-1901", CodeLength::Three).as_deref(),
+            Some("deu-Latn-CH-1901")
+        );
+        assert_eq!(
+            normalize_language_code("de", CodeLength::Two).as_deref(),
+            Some("de")
+        );
+
+        // Languages without a two-letter code, or which aren't covered, are kept as they are.
+        assert_eq!(
+            normalize_language_code("haw", CodeLength::Two).as_deref(),
+            Some("haw")
+        );
+        assert_eq!(
+            normalize_language_code("haw-US", CodeLength::Three).as_deref(),
+            Some("haw-US")
+        );
+        assert_eq!(
+            normalize_language_code("tlh", CodeLength::Two).as_deref(),
+            Some("tlh")
+        );
+        assert_eq!(normalize_language_code("not a tag", CodeLength::Two), None);
+    }
+
+    #[test]
+    fn available_locales_canonical() {
+        use super::{canonicalize, get_available_locales};
+
+        let locales = get_available_locales();
+        for (idx, locale) in locales.iter().enumerate() {
+            assert_eq!(*locale, canonicalize(locale.clone()));
+            assert!(!locales[..idx].contains(locale));
+        }
+    }
+
+    #[test]
+    fn canonical_tags() {
+        use super::canonicalize_tag;
+
+        assert_eq!(canonicalize_tag("iw"), "he");
+        assert_eq!(canonicalize_tag("iw-IL"), "he-IL");
+        assert_eq!(canonicalize_tag("in-id"), "id-ID");
+        assert_eq!(canonicalize_tag("no-NO"), "nb-NO");
+        assert_eq!(canonicalize_tag("sh"), "sr-Latn");
+        assert_eq!(canonicalize_tag("sh-Cyrl-BA"), "sr-Cyrl-BA");
+        assert_eq!(canonicalize_tag("de-DD-1901-x-foo"), "de-DE-1901-x-foo");
+        assert_eq!(canonicalize_tag("sr-YU"), "sr-YU");
+
+        // Modern tags only have their casing changed.
+        assert_eq!(canonicalize_tag("he-IL"), "he-IL");
+        assert_eq!(canonicalize_tag("zh-hant-tw"), "zh-Hant-TW");
+        assert_eq!(canonicalize_tag("not a tag"), "not a tag");
+    }
+
+    #[test]
+    fn minimized_tags() {
+        use super::minimize;
+
+        // The examples of minimizing in the CLDR's likely subtags.
+        assert_eq!(minimize("en-Latn-US").as_deref(), Some("en"));
+        assert_eq!(minimize("zh-Hans-CN").as_deref(), Some("zh"));
+        assert_eq!(minimize("zh-Hant-TW").as_deref(), Some("zh-TW"));
+        assert_eq!(minimize("zh-Hant-HK").as_deref(), Some("zh-HK"));
+        assert_eq!(minimize("zh-Hant").as_deref(), Some("zh-TW"));
+        assert_eq!(minimize("sr-Cyrl-RS").as_deref(), Some("sr"));
+        assert_eq!(minimize("sr-Latn-RS").as_deref(), Some("sr-Latn"));
+        assert_eq!(minimize("en-Latn-GB").as_deref(), Some("en-GB"));
+        assert_eq!(
+            minimize("de-latn-de-1901-u-co-phonebk").as_deref(),
+            Some("de-1901-u-co-phonebk")
+        );
+
+        // Tags with a language which isn't covered are only canonicalized.
+        assert_eq!(minimize("xx-latn-us").as_deref(), Some("xx-Latn-US"));
+        assert_eq!(minimize("tlh"), None);
+        assert_eq!(minimize("en_US"), None);
+    }
+
+    #[test]
+    fn cantonese() {
+        use super::is_cantonese;
+
+        assert!(is_cantonese("yue-Hant-HK"));
+        assert!(is_cantonese("zh-Hant-HK"));
+        assert!(is_cantonese("yue"));
+        assert!(is_cantonese("yue-Hans-CN"));
+        assert!(is_cantonese("zh-yue-HK"));
+        assert!(is_cantonese("ZH-hk"));
+        assert!(is_cantonese("zh-Hant-MO"));
+        assert!(is_cantonese("zh_Hant_HK"));
+        assert!(is_cantonese("yue_HK"));
+        assert!(is_cantonese("zh_yue"));
+        assert!(!is_cantonese("zh_Hant_TW"));
+        assert!(!is_cantonese("zh-Hans-HK"));
+        assert!(!is_cantonese("zh-Hant-TW"));
+        assert!(!is_cantonese("zh"));
+        assert!(!is_cantonese("en-HK"));
+        assert!(!is_cantonese(""));
+    }
+
+    #[test]
+    fn cantonese_is_kept() {
+        struct Cantonese;
+        impl LocaleProvider for Cantonese {
+            fn get(&self) -> Vec<String> {
+                vec![String::from("yue-Hant-HK"), String::from("zh-Hant-HK")]
+            }
+        }
+
+        let _provider = install_provider(&(&Cantonese as &dyn LocaleProvider));
+        let locales = get_locales().collect::<Vec<_>>();
+
+        // `yue` isn't collapsed into `zh`, so both are kept as they were reported.
+        assert_eq!(locales, ["yue-Hant-HK", "zh-Hant-HK"]);
+    }
+
+    #[test]
+    fn canonical_casing() {
+        for tag in ["en-us", "EN-US", "en-US"].iter() {
+            assert_eq!(canonicalize(String::from(*tag)), "en-US");
+        }
+        assert_eq!(canonicalize(String::from("zh-hant-hk")), "zh-Hant-HK");
+        assert_eq!(canonicalize(String::from("ZH-HANT-HK")), "zh-Hant-HK");
+
+        // Canonicalizing is idempotent, and leaves malformed tags alone.
+        assert_eq!(canonicalize(String::from("zh-Hant-HK")), "zh-Hant-HK");
+        assert_eq!(canonicalize(String::from("en_us")), "en_us");
+
+        struct Inconsistent;
+        impl LocaleProvider for Inconsistent {
+            fn get(&self) -> Vec<String> {
+                vec![
+                    String::from("en-us"),
+                    String::from("zh-hant-hk"),
+                    String::from("EN-US"),
+                ]
+            }
+        }
+
+        let _provider = install_provider(&(&Inconsistent as &dyn LocaleProvider));
+        let locales = get_locales().collect::<Vec<_>>();
+        let collected = try_get_locales();
+
+        assert_eq!(locales, ["en-US", "zh-Hant-HK"]);
+        assert_eq!(collected, Ok(locales));
+    }
+
+    #[test]
+    #[cfg(any(unix, windows))]
+    fn supported_platform() {
+        use super::{is_supported, SUPPORTED};
+
+        assert!(is_supported());
+        assert_eq!(is_supported(), SUPPORTED);
+    }
+
+    #[test]
+    fn top_locales() {
+        use super::get_top_locales;
+
+        struct Three;
+        impl LocaleProvider for Three {
+            fn get(&self) -> Vec<String> {
+                vec![
+                    String::from("it-IT"),
+                    String::from("it"),
+                    String::from("en-GB"),
+                ]
+            }
+        }
+
+        let _provider = install_provider(&(&Three as &dyn LocaleProvider));
+        let none = get_top_locales(0);
+        let one = get_top_locales(1);
+        let all = get_top_locales(10);
+
+        assert!(none.is_empty());
+        assert_eq!(one, ["it-IT"]);
+        assert_eq!(all, ["it-IT", "it", "en-GB"]);
+    }
+
+    #[test]
+    fn ranked_against_weights() {
+        use super::rank;
+
+        let preferences = || {
+            vec![
+                String::from("de-CH"),
+                String::from("en-US"),
+                String::from("en-GB"),
+                String::from("fr-FR"),
+            ]
+            .into_iter()
+        };
+
+        // The weights overlap with the preferences, with a region being more specific than a
+        // language, even if its weight is lower.
+        let weights = [("en-GB", 0.4), ("en", 0.9), ("DE", 0.9), ("fr-CA", 1.0)];
+        assert_eq!(
+            rank(preferences(), &weights),
+            [
+                (String::from("de-CH"), 0.9),
+                (String::from("en-US"), 0.9),
+                (String::from("en-GB"), 0.4),
+                (String::from("fr-FR"), 0.0),
+            ]
+        );
+
+        // The wildcard is the least specific, and the highest of equally specific weights is used.
+        let weights = [("*", 0.1), ("fr", 0.5), ("fr", 0.7), ("en-gb", 0.8)];
+        assert_eq!(
+            rank(preferences(), &weights),
+            [
+                (String::from("en-GB"), 0.8),
+                (String::from("fr-FR"), 0.7),
+                (String::from("de-CH"), 0.1),
+                (String::from("en-US"), 0.1),
+            ]
+        );
+
+        // None of the weights match, so every score ties and the order is kept.
+        let weights = [("ja", 1.0), ("ko-KR", 0.5), ("not a tag", 1.0)];
+        assert_eq!(
+            rank(preferences(), &weights),
+            [
+                (String::from("de-CH"), 0.0),
+                (String::from("en-US"), 0.0),
+                (String::from("en-GB"), 0.0),
+                (String::from("fr-FR"), 0.0),
+            ]
+        );
+        assert!(rank(preferences(), &[])
+            .iter()
+            .all(|(_, score)| *score == 0.0));
+    }
+
+    #[test]
+    fn accept_language() {
+        use super::{best_match_for, parse_accept_language, parse_accept_language_weights};
+
+        assert_eq!(
+            parse_accept_language("en-US,en;q=0.9,fr;q=0.8"),
+            ["en-US", "en", "fr"]
+        );
+        assert_eq!(
+            parse_accept_language_weights("fr;q=0.8, EN-us , *;q=0.1,en; Q = 0.9"),
+            [
+                (String::from("en-US"), 1.0),
+                (String::from("en"), 0.9),
+                (String::from("fr"), 0.8),
+                (String::from("*"), 0.1),
+            ]
+        );
+
+        // Malformed and out of range quality values default to 1, keeping the header's order.
+        assert_eq!(
+            parse_accept_language("de;q=0.5, fr;q=abc, it;q=, es;q=2, pt;q"),
+            ["fr", "it", "es", "pt", "de"]
+        );
+        // Unacceptable ranges and malformed tags are left out.
+        assert_eq!(
+            parse_accept_language("en;q=0, not a tag, , ;q=0.5, zh-hant-tw, ja;q=0.000"),
+            ["zh-Hant-TW"]
+        );
+        assert!(parse_accept_language("").is_empty());
+        assert!(parse_accept_language(" , ,").is_empty());
+
+        let preferences = parse_accept_language("de-AT, *;q=0.9, fr;q=0.5");
+        assert_eq!(
+            best_match_for(&preferences, &["en", "fr", "de"]).as_deref(),
+            Some("de")
+        );
+        assert_eq!(
+            best_match_for(&preferences, &["en", "fr-FR"]).as_deref(),
+            None
+        );
+    }
+
+    #[test]
+    fn lookup_supported() {
+        use super::lookup;
+
+        let preferences = |tags: &[&str]| {
+            tags.iter()
+                .map(|tag| String::from(*tag))
+                .collect::<Vec<_>>()
+                .into_iter()
+        };
+
+        // Exact matches, including the more specific of two supported tags.
+        let supported = ["en", "en-GB", "de-DE"];
+        assert_eq!(
+            lookup(preferences(&["en-GB"]), &supported).as_deref(),
+            Some("en-GB")
+        );
+        assert_eq!(
+            lookup(preferences(&["de-de"]), &supported).as_deref(),
+            Some("de-DE")
+        );
+
+        // Language-only matches, which come before any later preference.
+        assert_eq!(
+            lookup(preferences(&["en-AU", "de-DE"]), &supported).as_deref(),
+            Some("en")
+        );
+        assert_eq!(
+            lookup(preferences(&["zh-Hant-TW"]), &["zh-Hant", "zh"]).as_deref(),
+            Some("zh-Hant")
+        );
+
+        // A region alone doesn't match a different language, nor a more specific tag.
+        assert_eq!(lookup(preferences(&["fr-FR", "de"]), &supported), None);
+        assert_eq!(lookup(preferences(&[]), &supported), None);
+    }
+
+    #[test]
+    fn lookup_all_supported() {
+        use super::lookup_all;
+
+        let preferences = |tags: &[&str]| {
+            tags.iter()
+                .map(|tag| String::from(*tag))
+                .collect::<Vec<_>>()
+                .into_iter()
+        };
+        let supported = ["en", "de", "pt-BR"];
+
+        // Only the overlap is returned, in the order of the preferences, and each match once.
+        assert_eq!(
+            lookup_all(
+                preferences(&["fr-FR", "pt-BR", "en-GB", "en-US"]),
+                &supported
+            ),
+            ["pt-BR", "en"]
+        );
+        assert_eq!(
+            lookup_all(preferences(&["de-AT", "pt-PT"]), &supported),
+            ["de"]
+        );
+
+        assert!(lookup_all(preferences(&["fr-FR", "ja"]), &supported).is_empty());
+        assert!(lookup_all(preferences(&[]), &supported).is_empty());
+        assert!(lookup_all(preferences(&["en"]), &[]).is_empty());
+    }
+
+    #[test]
+    fn supported_locales() {
+        use super::{get_supported_locales, SupportedLocales};
+
+        const SUPPORTED: SupportedLocales = SupportedLocales::new(&["en", "de", "pt-BR"]);
+        const UNSUPPORTED: SupportedLocales = SupportedLocales::new(&["ja", "ko"]);
+
+        struct Preferences;
+        impl LocaleProvider for Preferences {
+            fn get(&self) -> Vec<String> {
+                vec![
+                    String::from("fr-CA"),
+                    String::from("de-CH"),
+                    String::from("en-US"),
+                ]
+            }
+        }
+
+        let _provider = install_provider(&(&Preferences as &dyn LocaleProvider));
+        let supported = get_supported_locales(&SUPPORTED);
+        let unsupported = get_supported_locales(&UNSUPPORTED);
+
+        assert_eq!(supported, ["de", "en"]);
+        assert!(unsupported.is_empty());
+    }
+
+    #[test]
+    fn dedup_locales() {
+        let provided = vec!["en-US", "fr-FR", "en-us", "de", "EN-US", "fr-FR", "en"];
+        let locales = dedup(provided.into_iter().map(String::from)).collect::<Vec<_>>();
+
+        assert_eq!(locales, ["en-US", "fr-FR", "de", "en"]);
+
+        // A duplicate of the latest locale is removed before it's added to those already seen.
+        let provided = vec!["de", "DE", "fr", "de"];
+        let locales = dedup(provided.into_iter().map(String::from)).collect::<Vec<_>>();
+        assert_eq!(locales, ["de", "fr"]);
+    }
+
+    #[test]
+    fn collect_locale_errors() {
+        let failed: Result<vec::IntoIter<String>, _> = Err(LocaleError::PlatformError { code: 5 });
+        assert_eq!(
+            collect_locales(failed),
+            Err(LocaleError::PlatformError { code: 5 })
+        );
+
+        let empty = Ok(Vec::<String>::new().into_iter());
+        assert_eq!(collect_locales(empty), Err(LocaleError::Empty));
+
+        let provided = vec!["en-US", "en-us", "fr"].into_iter().map(String::from);
+        assert_eq!(collect_locales(Ok(provided)).unwrap(), ["en-US", "fr"]);
+    }
+
+    #[test]
+    fn region_from_locale_tag() {
+        assert_eq!(region_from_tag("en-US").as_deref(), Some("US"));
+        assert_eq!(region_from_tag("zh-Hans-CN").as_deref(), Some("CN"));
+        assert_eq!(region_from_tag("de-ch").as_deref(), Some("CH"));
+        assert_eq!(region_from_tag("en"), None);
+        assert_eq!(region_from_tag("zh-Hant"), None);
+        // UN M.49 area codes aren't ISO 3166-1 alpha-2 codes.
+        assert_eq!(region_from_tag("es-419"), None);
+    }
+
+    #[test]
+    fn numbering_system_from_locale_tag() {
+        use super::numbering_system_from_tag;
+
+        assert_eq!(
+            numbering_system_from_tag("ar-EG-u-nu-arab").as_deref(),
+            Some("arab")
+        );
+        assert_eq!(numbering_system_from_tag("en-US").as_deref(), Some("latn"));
+        assert_eq!(numbering_system_from_tag("not a tag"), None);
+    }
+
+    #[test]
+    fn currency_code_canonical() {
+        // `int_curr_symbol` is padded with a separator.
+        assert_eq!(currency_code("USD ").as_deref(), Some("USD"));
+        assert_eq!(currency_code(" eur").as_deref(), Some("EUR"));
+        assert_eq!(currency_code("JPY").as_deref(), Some("JPY"));
+        assert_eq!(currency_code(""), None);
+        assert_eq!(currency_code("$"), None);
+        assert_eq!(currency_code("EURO"), None);
     }
 }
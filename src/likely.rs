@@ -0,0 +1,202 @@
+//! A curated subset of the CLDR likely subtags, for inferring the script and region of a locale
+//! without any platform support.
+
+/// The likely script and region of each language, sorted by language.
+///
+/// This covers the most widely used languages, rather than the whole of the CLDR.
+const LANGUAGES: &[(&str, &str, &str)] = &[
+    ("af", "Latn", "ZA"),
+    ("am", "Ethi", "ET"),
+    ("ar", "Arab", "EG"),
+    ("az", "Latn", "AZ"),
+    ("be", "Cyrl", "BY"),
+    ("bg", "Cyrl", "BG"),
+    ("bn", "Beng", "BD"),
+    ("bs", "Latn", "BA"),
+    ("ca", "Latn", "ES"),
+    ("cs", "Latn", "CZ"),
+    ("cy", "Latn", "GB"),
+    ("da", "Latn", "DK"),
+    ("de", "Latn", "DE"),
+    ("el", "Grek", "GR"),
+    ("en", "Latn", "US"),
+    ("es", "Latn", "ES"),
+    ("et", "Latn", "EE"),
+    ("eu", "Latn", "ES"),
+    ("fa", "Arab", "IR"),
+    ("fi", "Latn", "FI"),
+    ("fil", "Latn", "PH"),
+    ("fr", "Latn", "FR"),
+    ("ga", "Latn", "IE"),
+    ("gl", "Latn", "ES"),
+    ("gu", "Gujr", "IN"),
+    ("ha", "Latn", "NG"),
+    ("he", "Hebr", "IL"),
+    ("hi", "Deva", "IN"),
+    ("hr", "Latn", "HR"),
+    ("hu", "Latn", "HU"),
+    ("hy", "Armn", "AM"),
+    ("id", "Latn", "ID"),
+    ("ig", "Latn", "NG"),
+    ("is", "Latn", "IS"),
+    ("it", "Latn", "IT"),
+    ("ja", "Jpan", "JP"),
+    ("jv", "Latn", "ID"),
+    ("ka", "Geor", "GE"),
+    ("kk", "Cyrl", "KZ"),
+    ("km", "Khmr", "KH"),
+    ("kn", "Knda", "IN"),
+    ("ko", "Kore", "KR"),
+    ("ky", "Cyrl", "KG"),
+    ("lo", "Laoo", "LA"),
+    ("lt", "Latn", "LT"),
+    ("lv", "Latn", "LV"),
+    ("mk", "Cyrl", "MK"),
+    ("ml", "Mlym", "IN"),
+    ("mn", "Cyrl", "MN"),
+    ("mr", "Deva", "IN"),
+    ("ms", "Latn", "MY"),
+    ("my", "Mymr", "MM"),
+    ("nb", "Latn", "NO"),
+    ("ne", "Deva", "NP"),
+    ("nl", "Latn", "NL"),
+    ("nn", "Latn", "NO"),
+    ("no", "Latn", "NO"),
+    ("pa", "Guru", "IN"),
+    ("pl", "Latn", "PL"),
+    ("ps", "Arab", "AF"),
+    ("pt", "Latn", "BR"),
+    ("ro", "Latn", "RO"),
+    ("ru", "Cyrl", "RU"),
+    ("si", "Sinh", "LK"),
+    ("sk", "Latn", "SK"),
+    ("sl", "Latn", "SI"),
+    ("so", "Latn", "SO"),
+    ("sq", "Latn", "AL"),
+    ("sr", "Cyrl", "RS"),
+    ("sv", "Latn", "SE"),
+    ("sw", "Latn", "TZ"),
+    ("ta", "Taml", "IN"),
+    ("te", "Telu", "IN"),
+    ("th", "Thai", "TH"),
+    ("tr", "Latn", "TR"),
+    ("uk", "Cyrl", "UA"),
+    ("ur", "Arab", "PK"),
+    ("uz", "Latn", "UZ"),
+    ("vi", "Latn", "VN"),
+    ("yo", "Latn", "NG"),
+    ("yue", "Hant", "HK"),
+    ("zh", "Hans", "CN"),
+    ("zu", "Latn", "ZA"),
+];
+
+/// The likely region of languages which are also written in a script other than their
+/// likely one, such as Chinese in Traditional Han characters.
+const SCRIPT_REGIONS: &[(&str, &str, &str)] = &[
+    ("az", "Arab", "IR"),
+    ("pa", "Arab", "PK"),
+    ("uz", "Arab", "AF"),
+    ("yue", "Hans", "CN"),
+    ("zh", "Hant", "TW"),
+];
+
+/// The likely script of languages in regions where they aren't written in their likely one.
+const REGION_SCRIPTS: &[(&str, &str, &str)] = &[
+    ("pa", "PK", "Arab"),
+    ("zh", "HK", "Hant"),
+    ("zh", "MO", "Hant"),
+    ("zh", "TW", "Hant"),
+];
+
+/// Returns the likely script and region of a locale, keeping the ones which it already has.
+///
+/// The subtags must be in their canonical casing. Returns `None` if the locale doesn't already
+/// have both, and the language isn't one of those which are covered.
+pub(crate) fn likely_subtags<'a>(
+    language: &str,
+    script: Option<&'a str>,
+    region: Option<&'a str>,
+) -> Option<(&'a str, &'a str)> {
+    if let (Some(script), Some(region)) = (script, region) {
+        return Some((script, region));
+    }
+
+    let index = LANGUAGES
+        .binary_search_by_key(&language, |&(language, _, _)| language)
+        .ok()?;
+    let (_, likely_script, likely_region) = LANGUAGES[index];
+
+    let script = script.unwrap_or_else(|| {
+        region
+            .and_then(|region| find(REGION_SCRIPTS, language, region))
+            .unwrap_or(likely_script)
+    });
+    let region =
+        region.unwrap_or_else(|| find(SCRIPT_REGIONS, language, script).unwrap_or(likely_region));
+    Some((script, region))
+}
+
+/// Returns the script and region which are kept when a locale is minimized, which are those that
+/// aren't added back when it's maximized with [`likely_subtags`].
+///
+/// Returns `None` in the same cases as [`likely_subtags`].
+pub(crate) fn minimal_subtags<'a>(
+    language: &str,
+    script: Option<&'a str>,
+    region: Option<&'a str>,
+) -> Option<(Option<&'a str>, Option<&'a str>)> {
+    let maximal = likely_subtags(language, script, region)?;
+    let (script, region) = maximal;
+
+    // As in the CLDR, keeping the region is preferred over keeping the script.
+    let candidates = [(None, None), (None, Some(region)), (Some(script), None)];
+    let minimal = candidates
+        .iter()
+        .copied()
+        .find(|&(script, region)| likely_subtags(language, script, region) == Some(maximal));
+    Some(minimal.unwrap_or((Some(script), Some(region))))
+}
+
+/// Finds the value for a language and subtag in one of the tables of exceptions.
+fn find(
+    table: &[(&str, &str, &'static str)],
+    language: &str,
+    subtag: &str,
+) -> Option<&'static str> {
+    table
+        .iter()
+        .find(|&&(entry_language, entry_subtag, _)| {
+            entry_language == language && entry_subtag == subtag
+        })
+        .map(|&(_, _, value)| value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{likely_subtags, LANGUAGES};
+
+    #[test]
+    fn languages_sorted() {
+        assert!(LANGUAGES.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn exceptions() {
+        assert_eq!(likely_subtags("zh", None, Some("TW")), Some(("Hant", "TW")));
+        assert_eq!(
+            likely_subtags("zh", Some("Hant"), None),
+            Some(("Hant", "TW"))
+        );
+        assert_eq!(likely_subtags("zh", None, Some("SG")), Some(("Hans", "SG")));
+        assert_eq!(
+            likely_subtags("pa", Some("Arab"), None),
+            Some(("Arab", "PK"))
+        );
+        // A script without an exception is assumed to be used in the likely region.
+        assert_eq!(
+            likely_subtags("sr", Some("Latn"), None),
+            Some(("Latn", "RS"))
+        );
+        assert_eq!(likely_subtags("tlh", None, None), None);
+    }
+}
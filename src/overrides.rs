@@ -0,0 +1,390 @@
+//! Sources which take precedence over the platform's own locale preferences.
+use alloc::{borrow::Cow, string::String, vec::Vec};
+use core::{
+    fmt, ptr,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+/// A source of locale preferences, which can be installed with [`set_provider`] to
+/// replace the platform's own preferences.
+///
+/// This is useful when the locale of an application is controlled by something other than
+/// the operating system, such as a remote configuration service or a plugin host.
+///
+/// Providers are shared between every thread that obtains locales, so they must be [`Sync`].
+///
+/// # Example
+///
+/// ```
+/// use sys_locale::{get_locale, set_provider, LocaleProvider};
+///
+/// struct RemoteConfig;
+///
+/// impl LocaleProvider for RemoteConfig {
+///     fn get(&self) -> Vec<String> {
+///         vec![String::from("fr-CA")]
+///     }
+/// }
+///
+/// static PROVIDER: &dyn LocaleProvider = &RemoteConfig;
+/// set_provider(&PROVIDER);
+///
+/// assert_eq!(get_locale().as_deref(), Some("fr-CA"));
+/// ```
+pub trait LocaleProvider: Sync {
+    /// Returns the preferred locales, in descending order of preference.
+    ///
+    /// If the returned `Vec` is empty, the platform's own preferences are used instead.
+    fn get(&self) -> Vec<String>;
+
+    /// Returns the same locales as [`get`](LocaleProvider::get) for
+    /// [`get_locales_cow`](crate::get_locales_cow), borrowing any which live as long as the
+    /// program, such as those compiled into it, so that they don't need to be allocated.
+    ///
+    /// By default, this wraps each of the locales from `get` in [`Cow::Owned`].
+    fn get_cow(&self) -> Vec<Cow<'static, str>> {
+        self.get().into_iter().map(Cow::Owned).collect()
+    }
+}
+
+/// Where a locale returned by [`get_locales_with_source`](crate::get_locales_with_source) came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LocaleSource {
+    /// The user's preferences for the whole system, such as the preferred languages picked
+    /// in the settings of Windows, macOS, or Android.
+    System,
+    /// The preferences of the running application, such as the languages picked for it on
+    /// macOS, iOS, or Android 13 and above, or a [`LocaleProvider`] installed with [`set_provider`].
+    Application,
+    /// An environment variable, such as `LANG` on UNIX systems or `SYS_LOCALE_OVERRIDE`.
+    Environment,
+    /// A default which the platform used because no preferences were available, such as the
+    /// default locale of `Intl` on WebAssembly.
+    Fallback,
+}
+
+/// The platform's own provider of the locales, and the sources which it consulted, as returned by
+/// [`describe_provider`](crate::describe_provider).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ProviderInfo {
+    /// The name of the provider: `android`, `apple`, `unix`, `wasm`, or `windows`, or `stub` with
+    /// the `test-stub` feature. On platforms which aren't supported, it's `unsupported`.
+    pub name: &'static str,
+    /// The sources which the provider consults for the locales, in order of precedence, such as
+    /// the `LANG` environment variable or the `GetUserPreferredUILanguages` API. These depend on
+    /// the enabled features.
+    pub sources: Vec<&'static str>,
+    /// The first of the `sources` which provided any locales, or `None` if none of them did.
+    pub active_source: Option<&'static str>,
+}
+
+impl fmt::Display for ProviderInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (sources: {}; active: ",
+            self.name,
+            self.sources.join(", ")
+        )?;
+        f.write_str(self.active_source.unwrap_or("none"))?;
+        f.write_str(")")
+    }
+}
+
+/// The installed provider, or null if there isn't one. A `&dyn` reference is too wide to be
+/// stored in an atomic, so this points to one which lives as long as the program instead.
+static PROVIDER: AtomicPtr<&'static dyn LocaleProvider> = AtomicPtr::new(ptr::null_mut());
+
+/// Installs a global [`LocaleProvider`], which is consulted by [`get_locales`](crate::get_locales)
+/// before falling back to the platform's own preferences.
+///
+/// The provider is passed as a reference to a `&'static dyn LocaleProvider`, such as a `static`
+/// holding one, so that it fits in an atomic pointer.
+///
+/// The provider can be replaced at any time by calling this again, and it is visible
+/// to every thread as soon as this returns. Threads which are already obtaining locales
+/// may still finish using the previous provider.
+///
+/// Nothing is allocated, so providers can be swapped as often as needed, such as between tests.
+pub fn set_provider(provider: &'static &'static dyn LocaleProvider) {
+    PROVIDER.store(provider as *const _ as *mut _, Ordering::Release);
+}
+
+/// Removes the global [`LocaleProvider`] installed by [`set_provider`], returning to the
+/// platform's own preferences.
+pub fn clear_provider() {
+    PROVIDER.store(ptr::null_mut(), Ordering::Release);
+}
+
+fn installed_provider() -> Option<&'static dyn LocaleProvider> {
+    // SAFETY: The pointer is either null or was created from a `&'static` reference by
+    // `set_provider`, and it's never written through.
+    unsafe { PROVIDER.load(Ordering::Acquire).as_ref() }.copied()
+}
+
+#[cfg(feature = "mock")]
+std::thread_local! {
+    static MOCK: core::cell::RefCell<Option<Vec<String>>> =
+        const { core::cell::RefCell::new(None) };
+}
+
+/// Makes [`get_locales`](crate::get_locales) return `locales` on the current thread, until
+/// [`clear_mock_locales`] is called.
+///
+/// This is intended for testing code which depends on the locale. Since the mocked locales only
+/// apply to the thread which set them, tests running in parallel can each use their own. The mock
+/// takes precedence over `SYS_LOCALE_OVERRIDE` and [`LocaleProvider`], and an empty `Vec` makes
+/// it appear as if no locales are set. The shared cache of the `cache` feature doesn't use it.
+///
+/// This requires the `mock` feature.
+///
+/// # Example
+///
+/// ```
+/// use sys_locale::{clear_mock_locales, get_locale, set_mock_locales};
+///
+/// set_mock_locales(vec![String::from("nl-BE")]);
+/// assert_eq!(get_locale().as_deref(), Some("nl-BE"));
+///
+/// clear_mock_locales();
+/// ```
+#[cfg(feature = "mock")]
+pub fn set_mock_locales(locales: Vec<String>) {
+    MOCK.with(|mock| *mock.borrow_mut() = Some(locales));
+}
+
+/// Removes the locales set by [`set_mock_locales`] on the current thread, returning to the
+/// locales which would otherwise be used.
+///
+/// This requires the `mock` feature.
+#[cfg(feature = "mock")]
+pub fn clear_mock_locales() {
+    MOCK.with(|mock| *mock.borrow_mut() = None);
+}
+
+/// Returns the locales mocked on the current thread with [`set_mock_locales`], if any.
+#[cfg(feature = "mock")]
+fn mocked() -> Option<Vec<String>> {
+    MOCK.with(|mock| mock.borrow().clone())
+}
+
+#[cfg(all(feature = "std", not(feature = "mock")))]
+fn mocked() -> Option<Vec<String>> {
+    None
+}
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    #[allow(unknown_lints, clippy::missing_const_for_thread_local)]
+    static SCOPED: core::cell::RefCell<Vec<Vec<String>>> = core::cell::RefCell::new(Vec::new());
+}
+
+/// Makes [`get_locales`](crate::get_locales) return `tags` on the current thread, until the
+/// returned guard is dropped.
+///
+/// This is useful for temporarily using another locale, such as while rendering an email in
+/// its recipient's language on a server which handles many users on each thread. Calls can be
+/// nested, in which case the most recent guard which is still alive applies, and dropping it
+/// returns to the locales of the one before. Dropping a guard also ends any which were created
+/// after it on the same thread.
+///
+/// The scoped locales take precedence over every other source, including the locales of the
+/// `mock` feature, and an empty `Vec` makes it appear as if no locales are set. The shared cache
+/// of the `cache` feature doesn't use them.
+///
+/// This requires the `std` feature.
+///
+/// # Example
+///
+/// ```
+/// use sys_locale::{get_locale, scoped_locale};
+///
+/// {
+///     let _guard = scoped_locale(vec![String::from("ja-JP")]);
+///     assert_eq!(get_locale().as_deref(), Some("ja-JP"));
+/// }
+///
+/// assert_ne!(get_locale().as_deref(), Some("ja-JP"));
+/// ```
+#[cfg(feature = "std")]
+pub fn scoped_locale(tags: Vec<String>) -> LocaleGuard {
+    let depth = SCOPED.with(|scoped| {
+        let mut scoped = scoped.borrow_mut();
+        scoped.push(tags);
+        scoped.len() - 1
+    });
+
+    LocaleGuard {
+        depth,
+        _thread: core::marker::PhantomData,
+    }
+}
+
+/// Restores the locales which were used before [`scoped_locale`] was called when it's dropped.
+///
+/// The guard must be dropped on the thread which created it, so it isn't [`Send`].
+#[cfg(feature = "std")]
+#[derive(Debug)]
+#[must_use = "the locales are only overridden until the guard is dropped"]
+pub struct LocaleGuard {
+    depth: usize,
+    _thread: core::marker::PhantomData<*const ()>,
+}
+
+#[cfg(feature = "std")]
+impl Drop for LocaleGuard {
+    fn drop(&mut self) {
+        // The stack is already gone if this is dropped while the thread is exiting.
+        let _ = SCOPED.try_with(|scoped| scoped.borrow_mut().truncate(self.depth));
+    }
+}
+
+/// Returns the locales which only apply to the current thread, from [`scoped_locale`] or the
+/// `mock` feature, if any.
+#[cfg(feature = "std")]
+pub(crate) fn thread_locales() -> Option<Vec<String>> {
+    SCOPED
+        .with(|scoped| scoped.borrow().last().cloned())
+        .or_else(mocked)
+}
+
+/// The environment variable used to override the locales returned by the platform, unless the
+/// `no-env` feature is enabled.
+#[cfg(all(
+    feature = "env-override",
+    not(feature = "no-env"),
+    any(
+        feature = "std",
+        all(
+            unix,
+            not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+        )
+    )
+))]
+const OVERRIDE_VAR: &str = "SYS_LOCALE_OVERRIDE";
+
+/// Returns the locales which should be used instead of the platform's, if any
+/// have been configured.
+///
+/// The environment variable comes first, so the locales can always be controlled
+/// externally, followed by the provider installed by the application. Both of these
+/// are preceded by the locales scoped or mocked on the current thread.
+pub(crate) fn get() -> Option<Vec<String>> {
+    get_with_source().map(|(locales, _)| locales)
+}
+
+/// Returns the same locales as [`get`], along with where they came from.
+pub(crate) fn get_with_source() -> Option<(Vec<String>, LocaleSource)> {
+    get_before_provider().or_else(|| {
+        let locales = installed_provider()
+            .map(|provider| provider.get())
+            .filter(|locales| !locales.is_empty())?;
+        debug!(
+            "Using the locales {:?} from the installed provider",
+            locales
+        );
+        Some((locales, LocaleSource::Application))
+    })
+}
+
+/// Returns the same locales as [`get`], borrowing those which the installed provider returns
+/// from [`LocaleProvider::get_cow`].
+pub(crate) fn get_cow() -> Option<Vec<Cow<'static, str>>> {
+    match get_before_provider() {
+        Some((locales, _)) => Some(locales.into_iter().map(Cow::Owned).collect()),
+        None => {
+            let locales = installed_provider()
+                .map(|provider| provider.get_cow())
+                .filter(|locales| !locales.is_empty())?;
+            debug!(
+                "Using the locales {:?} from the installed provider",
+                locales
+            );
+            Some(locales)
+        }
+    }
+}
+
+/// Returns the locales which take precedence over the installed provider, if any.
+fn get_before_provider() -> Option<(Vec<String>, LocaleSource)> {
+    #[cfg(feature = "std")]
+    if let Some(locales) = thread_locales() {
+        debug!("Using the locales {:?} overridden on this thread", locales);
+        return Some((locales, LocaleSource::Application));
+    }
+
+    #[cfg(all(
+        feature = "env-override",
+        not(feature = "no-env"),
+        any(
+            feature = "std",
+            all(
+                unix,
+                not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+            )
+        )
+    ))]
+    if let Some(locales) = std::env::var(OVERRIDE_VAR)
+        .ok()
+        .map(|value| parse_override(&value))
+        .filter(|locales| !locales.is_empty())
+    {
+        debug!("Using the locales {:?} from {}", locales, OVERRIDE_VAR);
+        return Some((locales, LocaleSource::Environment));
+    }
+
+    None
+}
+
+/// Splits a comma-separated list of language tags, skipping any empty entries.
+#[cfg(all(
+    feature = "env-override",
+    not(feature = "no-env"),
+    any(
+        feature = "std",
+        all(
+            unix,
+            not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+        )
+    )
+))]
+fn parse_override(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+#[cfg(all(
+    test,
+    all(
+        feature = "env-override",
+        not(feature = "no-env"),
+        any(
+            feature = "std",
+            all(
+                unix,
+                not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+            )
+        )
+    )
+))]
+mod tests {
+    use super::parse_override;
+
+    #[test]
+    fn parse_override_list() {
+        assert_eq!(parse_override("de-DE"), ["de-DE"]);
+        assert_eq!(parse_override("de-DE,en-US,fr"), ["de-DE", "en-US", "fr"]);
+        assert_eq!(parse_override(" de-DE , en-US "), ["de-DE", "en-US"]);
+    }
+
+    #[test]
+    fn parse_override_skips_empty() {
+        assert_eq!(parse_override("de-DE,,en-US,"), ["de-DE", "en-US"]);
+        assert!(parse_override("").is_empty());
+        assert!(parse_override(", ,").is_empty());
+    }
+}
@@ -0,0 +1,801 @@
+//! Parsing of BCP-47 language tags into their individual components.
+use alloc::{string::String, vec::Vec};
+use core::{fmt, str::FromStr};
+
+/// A BCP-47 language tag, parsed into its individual subtags.
+///
+/// All subtags are stored in their canonical casing, so `EN-latn-us` and
+/// `en-Latn-US` parse into the same `Locale`.
+///
+/// # Example
+///
+/// ```
+/// use sys_locale::Locale;
+///
+/// let locale = Locale::from_tag("zh-hans-cn").unwrap();
+///
+/// assert_eq!(locale.language(), "zh");
+/// assert_eq!(locale.script(), Some("Hans"));
+/// assert_eq!(locale.region(), Some("CN"));
+/// assert_eq!(locale.to_tag(), "zh-Hans-CN");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Locale {
+    language: String,
+    script: Option<String>,
+    region: Option<String>,
+    variants: Vec<String>,
+    extensions: Vec<String>,
+}
+
+impl Locale {
+    /// Parses a BCP-47 language tag, such as `en-US` or `zh-Hans-CN`.
+    ///
+    /// Any extension or private use subtags (`-u-ca-buddhist`, `-x-foo`) following
+    /// the variants are kept as-is and included in [`Locale::to_tag`], but aren't
+    /// parsed any further.
+    ///
+    /// # Returns
+    ///
+    /// Returns `None` if the tag isn't well-formed, such as when it contains an empty
+    /// subtag or a subtag that is out of place.
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        let mut locale = Locale {
+            language: String::new(),
+            script: None,
+            region: None,
+            variants: Vec::new(),
+            extensions: Vec::new(),
+        };
+
+        let well_formed = visit_subtags(tag, |kind, subtag| match kind {
+            Subtag::Language => locale.language = subtag.to_ascii_lowercase(),
+            Subtag::Script => locale.script = Some(titlecase(subtag)),
+            Subtag::Region => locale.region = Some(subtag.to_ascii_uppercase()),
+            Subtag::Variant => locale.variants.push(subtag.to_ascii_lowercase()),
+            Subtag::Extension => locale.extensions.push(subtag.to_ascii_lowercase()),
+        });
+
+        if well_formed {
+            Some(locale)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the canonical BCP-47 language tag for this locale.
+    pub fn to_tag(&self) -> String {
+        let mut tag = self.language.clone();
+
+        let rest = self
+            .script
+            .iter()
+            .chain(self.region.iter())
+            .chain(self.variants.iter())
+            .chain(self.extensions.iter());
+        for subtag in rest {
+            tag.push('-');
+            tag.push_str(subtag);
+        }
+
+        tag
+    }
+
+    /// Returns the lowercase language subtag, such as `en` or `zh`.
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Returns the titlecase script subtag, such as `Hans` or `Latn`, if present.
+    pub fn script(&self) -> Option<&str> {
+        self.script.as_deref()
+    }
+
+    /// Returns the uppercase region subtag, such as `US` or `419`, if present.
+    pub fn region(&self) -> Option<&str> {
+        self.region.as_deref()
+    }
+
+    /// Returns the lowercase variant subtags, such as `1901`, in the order they appeared.
+    pub fn variants(&self) -> &[String] {
+        &self.variants
+    }
+
+    /// Returns the type subtags of a keyword in the Unicode extension (`-u-`), such as
+    /// `["islamic", "civil"]` for the `ca` keyword of `ar-SA-u-ca-islamic-civil`.
+    ///
+    /// Returns `None` if the locale doesn't have the keyword.
+    #[cfg(any(
+        test,
+        feature = "test-stub",
+        unix,
+        windows,
+        all(target_family = "wasm", any(feature = "js", feature = "web-sys"))
+    ))]
+    pub(crate) fn unicode_keyword(&self, key: &str) -> Option<&[String]> {
+        // Anything after the private use singleton (`x`) isn't an extension.
+        let start = self
+            .extensions
+            .iter()
+            .take_while(|subtag| *subtag != "x")
+            .position(|subtag| subtag == "u")?
+            + 1;
+        let extension = &self.extensions[start..];
+        let extension = match extension.iter().position(|subtag| subtag.len() == 1) {
+            Some(end) => &extension[..end],
+            None => extension,
+        };
+
+        // Keys are the only subtags with two characters, so they can't be mistaken for the
+        // attributes before them or the types after them.
+        let types = &extension[extension.iter().position(|subtag| subtag == key)? + 1..];
+        match types.iter().position(|subtag| subtag.len() == 2) {
+            Some(end) => Some(&types[..end]),
+            None => Some(types),
+        }
+    }
+
+    /// Returns a copy of this locale with the given language, which must be in its canonical
+    /// casing.
+    pub(crate) fn with_language(&self, language: &str) -> Locale {
+        Locale {
+            language: String::from(language),
+            ..self.clone()
+        }
+    }
+
+    /// Returns a copy of this locale with the given script and region, which must be in their
+    /// canonical casing.
+    pub(crate) fn with_script_and_region(
+        &self,
+        script: Option<&str>,
+        region: Option<&str>,
+    ) -> Locale {
+        Locale {
+            script: script.map(String::from),
+            region: region.map(String::from),
+            ..self.clone()
+        }
+    }
+}
+
+/// Formats the locale as its canonical BCP-47 language tag, the same as [`Locale::to_tag`].
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_tag())
+    }
+}
+
+/// Parses a BCP-47 language tag with [`Locale::from_tag`], so that `"en-US".parse::<Locale>()`
+/// can be used.
+impl FromStr for Locale {
+    type Err = ParseLocaleError;
+
+    fn from_str(tag: &str) -> Result<Locale, ParseLocaleError> {
+        Locale::from_tag(tag).ok_or(ParseLocaleError { _private: () })
+    }
+}
+
+/// The error returned when parsing a [`Locale`] from a string which isn't a well-formed BCP-47
+/// language tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ParseLocaleError {
+    _private: (),
+}
+
+impl fmt::Display for ParseLocaleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("the language tag isn't well-formed")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseLocaleError {}
+
+/// Serializes the locale as its canonical BCP-47 language tag, such as `"zh-Hans-CN"`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Locale {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_tag())
+    }
+}
+
+/// Deserializes the locale from a BCP-47 language tag with [`Locale::from_tag`].
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Locale {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct TagVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for TagVisitor {
+            type Value = Locale;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a well-formed BCP-47 language tag")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, tag: &str) -> Result<Locale, E> {
+                Locale::from_tag(tag)
+                    .ok_or_else(|| E::invalid_value(serde::de::Unexpected::Str(tag), &self))
+            }
+        }
+
+        deserializer.deserialize_str(TagVisitor)
+    }
+}
+
+/// A language tag which compares and hashes the same regardless of its casing or whether its
+/// subtags are separated with `-` or `_`, for using locales from different sources as the keys of
+/// a `HashMap`.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use sys_locale::LocaleKey;
+///
+/// let mut greetings = HashMap::new();
+/// greetings.insert(LocaleKey::new("en-US"), "Howdy");
+///
+/// assert_eq!(greetings.get(&LocaleKey::new("en_us")), Some(&"Howdy"));
+/// assert_eq!(greetings.get(&LocaleKey::new("en-GB")), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LocaleKey(String);
+
+impl LocaleKey {
+    /// Normalizes a language tag, such as `en_us` or `EN-US`, into a key.
+    ///
+    /// Well-formed tags are put into their canonical casing, so the key is the same as the
+    /// [`Locale::to_tag`] of the tag. Any other tag is kept in lowercase, so it's only equal to
+    /// the same tag in another casing.
+    pub fn new(tag: &str) -> LocaleKey {
+        let tag = tag.replace('_', "-");
+        match Locale::from_tag(&tag) {
+            Some(locale) => LocaleKey(locale.to_tag()),
+            None => LocaleKey(tag.to_ascii_lowercase()),
+        }
+    }
+
+    /// Returns the normalized language tag, such as `en-US`.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for LocaleKey {
+    fn from(tag: &str) -> LocaleKey {
+        LocaleKey::new(tag)
+    }
+}
+
+impl From<Locale> for LocaleKey {
+    fn from(locale: Locale) -> LocaleKey {
+        LocaleKey(locale.to_tag())
+    }
+}
+
+/// Formats the key as its normalized language tag, the same as [`LocaleKey::as_str`].
+impl fmt::Display for LocaleKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Checks whether `tag` is a well-formed BCP-47 language tag, following the `Language-Tag`
+/// grammar of RFC 5646, without regard to its casing.
+///
+/// This only checks the syntax of the tag, such as the lengths and characters of its subtags, so
+/// a well-formed tag may still use a language or region which doesn't exist. Unlike
+/// [`Locale::from_tag`], this doesn't allocate, and also accepts:
+/// - Extended language subtags, such as the `yue` of `zh-yue-HK`.
+/// - Four letter language subtags, which are reserved for future use.
+/// - Private use tags without a language, such as `x-whatever`.
+///
+/// Grandfathered tags which match the grammar, such as `art-lojban` and `zh-min-nan`, are
+/// accepted since they're indistinguishable from other well-formed tags. The irregular ones which
+/// don't, such as `i-klingon` and `en-GB-oed`, are rejected, as they have all been deprecated in
+/// favor of tags which do.
+///
+/// # Example
+///
+/// ```
+/// use sys_locale::is_well_formed_bcp47;
+///
+/// assert!(is_well_formed_bcp47("zh-Hant-TW"));
+/// assert!(!is_well_formed_bcp47("en_US.UTF-8"));
+/// ```
+pub fn is_well_formed_bcp47(tag: &str) -> bool {
+    let mut subtags = tag.split('-');
+
+    let language = match subtags.next() {
+        Some(language) => language,
+        None => return false,
+    };
+    if language.eq_ignore_ascii_case("x") {
+        return is_private_use(subtags);
+    }
+    if !matches!(language.len(), 2..=8) || !is_alphabetic(language) {
+        return false;
+    }
+
+    let mut next = subtags.next();
+
+    // Up to three extended language subtags may follow a language of two or three letters.
+    if language.len() <= 3 {
+        for _ in 0..3 {
+            if !matches!(next, Some(s) if s.len() == 3 && is_alphabetic(s)) {
+                break;
+            }
+            next = subtags.next();
+        }
+    }
+
+    if matches!(next, Some(s) if is_script(s)) {
+        next = subtags.next();
+    }
+    if matches!(next, Some(s) if is_region(s)) {
+        next = subtags.next();
+    }
+    while matches!(next, Some(s) if is_variant(s)) {
+        next = subtags.next();
+    }
+
+    // Each extension is a singleton followed by at least one subtag, such as `u-ca-buddhist`.
+    while let Some(singleton) = next {
+        if singleton.len() != 1 || !is_alphanumeric(singleton) {
+            return false;
+        }
+        if singleton.eq_ignore_ascii_case("x") {
+            return is_private_use(subtags);
+        }
+
+        next = subtags.next();
+        if !matches!(next, Some(s) if is_extension(s)) {
+            return false;
+        }
+        while matches!(next, Some(s) if is_extension(s)) {
+            next = subtags.next();
+        }
+    }
+
+    true
+}
+
+/// Checks the subtags after the private use singleton (`x`), of which there must be at least one.
+fn is_private_use<'a>(subtags: impl Iterator<Item = &'a str>) -> bool {
+    let mut any = false;
+    for subtag in subtags {
+        if !matches!(subtag.len(), 1..=8) || !is_alphanumeric(subtag) {
+            return false;
+        }
+        any = true;
+    }
+    any
+}
+
+/// The kinds of subtags which a [`Locale`] is parsed into.
+#[derive(Clone, Copy)]
+enum Subtag {
+    Language,
+    Script,
+    Region,
+    Variant,
+    Extension,
+}
+
+/// Calls `visit` with each subtag of `tag` and its kind, in order, returning whether the tag is
+/// well-formed as [`Locale::from_tag`] parses it. Some subtags may already have been visited when
+/// it isn't.
+fn visit_subtags<'a>(tag: &'a str, mut visit: impl FnMut(Subtag, &'a str)) -> bool {
+    let mut subtags = tag.split('-');
+
+    match subtags.next().filter(|s| is_language(s)) {
+        Some(language) => visit(Subtag::Language, language),
+        None => return false,
+    }
+
+    let mut next = subtags.next();
+
+    if let Some(script) = next.filter(|s| is_script(s)) {
+        visit(Subtag::Script, script);
+        next = subtags.next();
+    }
+
+    if let Some(region) = next.filter(|s| is_region(s)) {
+        visit(Subtag::Region, region);
+        next = subtags.next();
+    }
+
+    while let Some(variant) = next.filter(|s| is_variant(s)) {
+        visit(Subtag::Variant, variant);
+        next = subtags.next();
+    }
+
+    if let Some(singleton) = next {
+        if singleton.len() != 1 || !is_alphanumeric(singleton) {
+            return false;
+        }
+
+        // Every singleton must be followed by at least one subtag, although
+        // anything is allowed after the private use singleton (`x`).
+        let mut expects_subtag = false;
+        let mut private_use = false;
+        for subtag in core::iter::once(singleton).chain(subtags) {
+            if subtag.is_empty() || subtag.len() > 8 || !is_alphanumeric(subtag) {
+                return false;
+            }
+
+            if subtag.len() == 1 && !private_use {
+                if expects_subtag {
+                    return false;
+                }
+                private_use = subtag.eq_ignore_ascii_case("x");
+                expects_subtag = true;
+            } else {
+                expects_subtag = false;
+            }
+
+            visit(Subtag::Extension, subtag);
+        }
+
+        if expects_subtag {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Checks whether a tag is already the same as the [`Locale::to_tag`] of its [`Locale`], without
+/// allocating. Tags which aren't well-formed are also unchanged by canonicalizing them, so they
+/// count as well.
+pub(crate) fn is_canonical(tag: &str) -> bool {
+    let mut canonical = true;
+    let well_formed = visit_subtags(tag, |kind, subtag| {
+        canonical &= match kind {
+            Subtag::Script => {
+                let mut bytes = subtag.bytes();
+                matches!(bytes.next(), Some(b) if !b.is_ascii_lowercase())
+                    && bytes.all(|b| !b.is_ascii_uppercase())
+            }
+            Subtag::Region => subtag.bytes().all(|b| !b.is_ascii_lowercase()),
+            Subtag::Language | Subtag::Variant | Subtag::Extension => {
+                subtag.bytes().all(|b| !b.is_ascii_uppercase())
+            }
+        };
+    });
+    canonical || !well_formed
+}
+
+fn is_extension(subtag: &str) -> bool {
+    matches!(subtag.len(), 2..=8) && is_alphanumeric(subtag)
+}
+
+fn is_alphabetic(subtag: &str) -> bool {
+    subtag.bytes().all(|b| b.is_ascii_alphabetic())
+}
+
+fn is_alphanumeric(subtag: &str) -> bool {
+    subtag.bytes().all(|b| b.is_ascii_alphanumeric())
+}
+
+fn is_language(subtag: &str) -> bool {
+    matches!(subtag.len(), 2..=3 | 5..=8) && is_alphabetic(subtag)
+}
+
+fn is_script(subtag: &str) -> bool {
+    subtag.len() == 4 && is_alphabetic(subtag)
+}
+
+fn is_region(subtag: &str) -> bool {
+    (subtag.len() == 2 && is_alphabetic(subtag))
+        || (subtag.len() == 3 && subtag.bytes().all(|b| b.is_ascii_digit()))
+}
+
+fn is_variant(subtag: &str) -> bool {
+    let starts_with_digit = matches!(subtag.bytes().next(), Some(b) if b.is_ascii_digit());
+
+    is_alphanumeric(subtag)
+        && (matches!(subtag.len(), 5..=8) || (subtag.len() == 4 && starts_with_digit))
+}
+
+fn titlecase(subtag: &str) -> String {
+    let mut subtag = subtag.to_ascii_lowercase();
+    if let Some(first) = subtag.get_mut(0..1) {
+        first.make_ascii_uppercase();
+    }
+    subtag
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_canonical, is_well_formed_bcp47, Locale, LocaleKey};
+
+    #[test]
+    fn well_formed_tags() {
+        let tags = [
+            "en",
+            "EN-us",
+            "zh-Hant-TW",
+            "es-419",
+            "de-CH-1901",
+            "sl-rozaj-biske",
+            "hy-Latn-IT-arevela",
+            // Extended language subtags.
+            "zh-yue-HK",
+            "zh-yue-abc-def",
+            // Reserved and registered language subtags of every allowed length.
+            "abcd",
+            "abcdefgh",
+            // Extensions and private use.
+            "th-TH-u-ca-buddhist",
+            "en-a-bbb-x-a-ccc",
+            "en-US-u-ca-gregory-t-de",
+            "x-whatever",
+            "X-a-b",
+            "qaa-Qaaa-QM-x-southern",
+            // Regular grandfathered tags, which match the grammar.
+            "art-lojban",
+            "cel-gaulish",
+            "zh-min-nan",
+            "no-bok",
+        ];
+        for tag in tags.iter() {
+            assert!(is_well_formed_bcp47(tag), "{} should be well-formed", tag);
+        }
+    }
+
+    #[test]
+    fn malformed_tags() {
+        let tags = [
+            "",
+            "-",
+            "e",
+            "en-",
+            "-US",
+            "en--US",
+            "en_US",
+            "en-US.UTF-8",
+            "de_DE@euro",
+            "C",
+            "abcdefghi",
+            "e1",
+            // Too many extended language subtags, or after a long language.
+            "zh-yue-abc-def-ghi",
+            "abcde-yue",
+            // Subtags which are out of place or too long.
+            "en-US-GB",
+            "en-Latn-Hant",
+            "en-US-toolongvariant",
+            "en-u",
+            "en-u-x-foo",
+            "en-u-a",
+            "en-u-ca-toolongvalue",
+            "x",
+            "en-x",
+            "en-x-toolongvalue",
+            // Irregular grandfathered tags, which don't match the grammar.
+            "i-klingon",
+            "en-GB-oed",
+            "sgn-BE-FR",
+        ];
+        for tag in tags.iter() {
+            assert!(!is_well_formed_bcp47(tag), "{} should be malformed", tag);
+        }
+    }
+
+    #[test]
+    fn well_formed_agrees_with_parser() {
+        for tag in [
+            "en-US",
+            "zh-Hant-HK",
+            "de-CH-1901",
+            "en-x-a-b",
+            "en",
+            "en-US-",
+        ]
+        .iter()
+        {
+            assert_eq!(
+                is_well_formed_bcp47(tag),
+                Locale::from_tag(tag).is_some(),
+                "{}",
+                tag
+            );
+        }
+    }
+
+    #[test]
+    fn parse_script_and_region() {
+        let locale = Locale::from_tag("zh-Hans-CN").unwrap();
+        assert_eq!(locale.language(), "zh");
+        assert_eq!(locale.script(), Some("Hans"));
+        assert_eq!(locale.region(), Some("CN"));
+        assert!(locale.variants().is_empty());
+        assert_eq!(locale.to_tag(), "zh-Hans-CN");
+    }
+
+    #[test]
+    fn parse_variant() {
+        let locale = Locale::from_tag("de-CH-1901").unwrap();
+        assert_eq!(locale.language(), "de");
+        assert_eq!(locale.script(), None);
+        assert_eq!(locale.region(), Some("CH"));
+        assert_eq!(locale.variants(), ["1901"]);
+        assert_eq!(locale.to_tag(), "de-CH-1901");
+    }
+
+    #[test]
+    fn parse_language_only() {
+        let locale = Locale::from_tag("en").unwrap();
+        assert_eq!(locale.language(), "en");
+        assert_eq!(locale.script(), None);
+        assert_eq!(locale.region(), None);
+        assert!(locale.variants().is_empty());
+        assert_eq!(locale.to_tag(), "en");
+    }
+
+    #[test]
+    fn parse_canonical_casing() {
+        assert_eq!(
+            Locale::from_tag("ZH-hans-cn").unwrap().to_tag(),
+            "zh-Hans-CN"
+        );
+        assert_eq!(Locale::from_tag("en-us").unwrap().to_tag(), "en-US");
+        assert_eq!(Locale::from_tag("es-419").unwrap().to_tag(), "es-419");
+        assert_eq!(
+            Locale::from_tag("th-TH-U-CA-Buddhist").unwrap().to_tag(),
+            "th-TH-u-ca-buddhist"
+        );
+        assert_eq!(Locale::from_tag("en-x-Foo").unwrap().to_tag(), "en-x-foo");
+        assert_eq!(Locale::from_tag("en-x-a-b").unwrap().to_tag(), "en-x-a-b");
+    }
+
+    #[test]
+    fn reject_malformed() {
+        assert_eq!(Locale::from_tag(""), None);
+        assert_eq!(Locale::from_tag("en-"), None);
+        assert_eq!(Locale::from_tag("en--US"), None);
+        assert_eq!(Locale::from_tag("-US"), None);
+        assert_eq!(Locale::from_tag("en_US"), None);
+        assert_eq!(Locale::from_tag("en-US.UTF-8"), None);
+        assert_eq!(Locale::from_tag("e"), None);
+        assert_eq!(Locale::from_tag("en-u"), None);
+        assert_eq!(Locale::from_tag("en-u-x-foo"), None);
+        assert_eq!(Locale::from_tag("en-US-toolongvariant"), None);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_round_trip() {
+        for tag in [
+            "en",
+            "en-US",
+            "zh-Hant-HK",
+            "de-CH-1901",
+            "th-TH-u-ca-buddhist",
+        ]
+        .iter()
+        {
+            let locale = Locale::from_tag(tag).unwrap();
+            let json = serde_json::to_string(&locale).unwrap();
+            assert_eq!(json, alloc::format!("\"{}\"", tag));
+            assert_eq!(serde_json::from_str::<Locale>(&json).unwrap(), locale);
+        }
+
+        // Tags are canonicalized when they're deserialized.
+        let locale = serde_json::from_str::<Locale>("\"zh-hant-hk\"").unwrap();
+        assert_eq!(serde_json::to_string(&locale).unwrap(), "\"zh-Hant-HK\"");
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for tag in [
+            "en",
+            "en-US",
+            "zh-Hant-HK",
+            "es-419",
+            "de-CH-1901",
+            "th-TH-u-ca-buddhist",
+        ]
+        .iter()
+        {
+            let locale = tag.parse::<Locale>().unwrap();
+            let displayed = alloc::format!("{}", locale);
+            assert_eq!(displayed, *tag);
+            assert_eq!(displayed.parse::<Locale>(), Ok(locale));
+        }
+
+        // Tags are displayed in their canonical casing.
+        let locale = "zh-hant-hk".parse::<Locale>().unwrap();
+        assert_eq!(alloc::format!("{}", locale), "zh-Hant-HK");
+    }
+
+    #[test]
+    fn parse_rejects_malformed() {
+        for tag in ["", "en_US", "en-", "en--US", "1234", "en-US-x"].iter() {
+            let err = tag.parse::<Locale>().unwrap_err();
+            assert_eq!(
+                alloc::format!("{}", err),
+                "the language tag isn't well-formed"
+            );
+        }
+    }
+
+    #[test]
+    fn locale_keys_ignore_formatting() {
+        extern crate std;
+        use std::collections::HashMap;
+
+        let mut names = HashMap::new();
+        names.insert(LocaleKey::new("en-US"), "English (United States)");
+        names.insert(LocaleKey::new("zh_hant_tw"), "Chinese (Taiwan)");
+
+        for tag in ["en-US", "en_us", "EN-US", "en_US"].iter() {
+            assert_eq!(
+                names.get(&LocaleKey::new(tag)),
+                Some(&"English (United States)")
+            );
+        }
+        assert_eq!(
+            names.get(&LocaleKey::new("zh-Hant-TW")),
+            Some(&"Chinese (Taiwan)")
+        );
+        assert_eq!(names.get(&LocaleKey::new("en")), None);
+        assert_eq!(names.get(&LocaleKey::new("en-GB")), None);
+        assert_ne!(LocaleKey::new("en"), LocaleKey::new("en-GB"));
+
+        assert_eq!(LocaleKey::new("EN_us").as_str(), "en-US");
+        assert_eq!(
+            LocaleKey::from(Locale::from_tag("sr-latn").unwrap()),
+            LocaleKey::new("SR_Latn")
+        );
+        // Malformed tags are only compared without their casing.
+        assert_eq!(LocaleKey::new("en_US.UTF-8"), LocaleKey::new("EN-us.utf-8"));
+        assert_ne!(LocaleKey::new("en_US.UTF-8"), LocaleKey::new("en-US"));
+    }
+
+    #[test]
+    fn canonical_tags() {
+        let canonical = [
+            "en",
+            "en-US",
+            "zh-Hant-TW",
+            "es-419",
+            "de-CH-1901",
+            "th-TH-u-ca-buddhist",
+            "en-x-foo",
+            // Tags which aren't well-formed are returned unchanged.
+            "en_US.UTF-8",
+            "EN-",
+        ];
+        for tag in canonical.iter() {
+            assert!(is_canonical(tag), "{} should be canonical", tag);
+        }
+
+        let uncanonical = [
+            "EN",
+            "en-us",
+            "zh-hant-TW",
+            "zh-HANT-TW",
+            "de-CH-1901A",
+            "th-TH-u-CA-buddhist",
+            "en-X-foo",
+        ];
+        for tag in uncanonical.iter() {
+            assert!(!is_canonical(tag), "{} shouldn't be canonical", tag);
+            assert_ne!(Locale::from_tag(tag).unwrap().to_tag(), *tag);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_rejects_malformed() {
+        let err = serde_json::from_str::<Locale>("\"en_US\"").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("expected a well-formed BCP-47 language tag"));
+
+        assert!(serde_json::from_str::<Locale>("{\"language\":\"en\"}").is_err());
+    }
+}
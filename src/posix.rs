@@ -0,0 +1,385 @@
+//! Parsing of POSIX locale names, such as `en_US.UTF-8`, which are used by UNIX
+//! environment variables.
+use alloc::{string::String, vec::Vec};
+
+/// A category of locale settings, which UNIX platforms allow to be configured separately
+/// with their own `LC_*` environment variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LocaleCategory {
+    /// The language of messages and the user interface (`LC_MESSAGES`).
+    Messages,
+    /// The formatting of dates and times (`LC_TIME`).
+    Time,
+    /// The formatting of numbers (`LC_NUMERIC`).
+    Numeric,
+    /// The formatting of monetary amounts (`LC_MONETARY`).
+    Monetary,
+    /// The sorting and comparison of text (`LC_COLLATE`).
+    Collate,
+    /// The units of measurement (`LC_MEASUREMENT`).
+    Measurement,
+}
+
+impl LocaleCategory {
+    /// Every category, in the order they're declared.
+    pub const ALL: [LocaleCategory; 6] = [
+        LocaleCategory::Messages,
+        LocaleCategory::Time,
+        LocaleCategory::Numeric,
+        LocaleCategory::Monetary,
+        LocaleCategory::Collate,
+        LocaleCategory::Measurement,
+    ];
+
+    /// Returns the name of the environment variable which configures this category.
+    #[cfg(all(
+        unix,
+        not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+    ))]
+    pub(crate) fn env_var(self) -> &'static str {
+        match self {
+            LocaleCategory::Messages => "LC_MESSAGES",
+            LocaleCategory::Time => "LC_TIME",
+            LocaleCategory::Numeric => "LC_NUMERIC",
+            LocaleCategory::Monetary => "LC_MONETARY",
+            LocaleCategory::Collate => "LC_COLLATE",
+            LocaleCategory::Measurement => "LC_MEASUREMENT",
+        }
+    }
+}
+
+/// Converts a POSIX locale name, `language[_territory][.codeset][@modifier]`,
+/// into a BCP-47 language tag.
+///
+/// The modifiers which select a script or variant are kept as the equivalent
+/// subtag, so `sr_RS@latin` becomes `sr-Latn-RS` and `ca_ES@valencia` becomes
+/// `ca-ES-valencia`. Any other modifier, such as `@euro`, is dropped.
+///
+/// The `C` and `POSIX` locales (including `C.UTF-8`) aren't languages, so they
+/// return `None` and leave the fallback to the caller.
+#[cfg(any(
+    test,
+    all(
+        unix,
+        not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+    ),
+    all(target_family = "wasm", feature = "js")
+))]
+pub(crate) fn parse_locale_code(code: &str) -> Option<String> {
+    // TODO: Once we bump MSRV >= 1.52, remove these allows and clean up
+    #[allow(clippy::manual_split_once)]
+    #[allow(clippy::needless_splitn)]
+    let (name, modifier) = {
+        let mut parts = code.splitn(2, '@');
+        let name = parts.next()?;
+        // Some locales are returned with the char encoding too: `en_US.UTF-8`
+        (name.splitn(2, '.').next()?, parts.next())
+    };
+
+    if name == "C" || name == "POSIX" {
+        return None;
+    }
+
+    let (script, variant) = modifier_subtags(modifier);
+
+    let mut subtags = name.split(&['_', '-'][..]);
+    let mut tag = String::from(subtags.next()?);
+    for subtag in script.into_iter().chain(subtags).chain(variant) {
+        tag.push('-');
+        tag.push_str(subtag);
+    }
+
+    Some(tag)
+}
+
+/// Returns the script or variant subtag which a modifier selects, such as `Latn` for `latin`.
+fn modifier_subtags(modifier: Option<&str>) -> (Option<&'static str>, Option<&'static str>) {
+    match modifier {
+        Some("latin") => (Some("Latn"), None),
+        Some("cyrillic") => (Some("Cyrl"), None),
+        Some("devanagari") => (Some("Deva"), None),
+        Some("valencia") => (None, Some("valencia")),
+        _ => (None, None),
+    }
+}
+
+/// The transformations which `get_locales_with_options` applies to the locales which the
+/// platform provides, such as `sr_RS.UTF-8@latin`.
+///
+/// Each one is independent of the others, so they can be picked individually. The default
+/// enables all of them, which turns the locales into BCP-47 language tags in the same way as
+/// `get_locales`, such as `sr-Latn-RS`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NormalizationOptions {
+    /// Removes the codeset, such as the `.UTF-8` of `en_US.UTF-8`.
+    pub strip_encoding: bool,
+    /// Replaces the `_` between subtags with `-`, such as `en-US` for `en_US`.
+    pub rewrite_separator: bool,
+    /// Converts the subtags into their canonical casing, such as `zh_Hant_TW` for `ZH_hant_tw`:
+    /// lowercase languages, titlecase scripts, and uppercase regions. The codeset and modifier
+    /// are kept as they are.
+    pub canonical_case: bool,
+    /// Removes the modifier, such as the `@euro` of `de_DE@euro`. The modifiers which select a
+    /// script or variant are replaced by the equivalent subtag as they're removed, so
+    /// `sr_RS@latin` becomes `sr_Latn_RS` and `ca_ES@valencia` becomes `ca_ES_valencia`.
+    pub drop_modifier: bool,
+}
+
+impl Default for NormalizationOptions {
+    fn default() -> NormalizationOptions {
+        NormalizationOptions {
+            strip_encoding: true,
+            rewrite_separator: true,
+            canonical_case: true,
+            drop_modifier: true,
+        }
+    }
+}
+
+/// Applies the transformations of `options` to a locale, in the format of POSIX locale names or
+/// BCP-47 language tags.
+///
+/// The `C` and `POSIX` locales (including `C.UTF-8`) aren't languages, so they return `None`.
+pub(crate) fn normalize_locale_code(code: &str, options: &NormalizationOptions) -> Option<String> {
+    let (name, modifier) = match code.find('@') {
+        Some(at) => (&code[..at], Some(&code[at + 1..])),
+        None => (code, None),
+    };
+    let (name, encoding) = match name.find('.') {
+        Some(dot) => (&name[..dot], Some(&name[dot + 1..])),
+        None => (name, None),
+    };
+    if name.is_empty() || name == "C" || name == "POSIX" {
+        return None;
+    }
+
+    // Each subtag along with the separator before it, which the first doesn't have.
+    let mut subtags = Vec::new();
+    let (mut start, mut separator) = (0, "");
+    for (idx, next) in name.match_indices(&['_', '-'][..]) {
+        subtags.push((String::from(separator), String::from(&name[start..idx])));
+        start = idx + 1;
+        separator = next;
+    }
+    subtags.push((String::from(separator), String::from(&name[start..])));
+
+    if options.drop_modifier {
+        let separator = subtags
+            .get(1)
+            .map_or("_", |(separator, _)| separator.as_str());
+        let separator = String::from(separator);
+        let (script, variant) = modifier_subtags(modifier);
+        if let Some(script) = script {
+            subtags.insert(1, (separator.clone(), String::from(script)));
+        }
+        if let Some(variant) = variant {
+            subtags.push((separator, String::from(variant)));
+        }
+    }
+    if options.rewrite_separator {
+        for (separator, _) in subtags.iter_mut().skip(1) {
+            *separator = String::from("-");
+        }
+    }
+    if options.canonical_case {
+        canonicalize_case(subtags.iter_mut().map(|(_, subtag)| subtag));
+    }
+
+    let mut normalized = subtags
+        .into_iter()
+        .map(|(separator, subtag)| separator + &subtag)
+        .collect::<String>();
+    if let (Some(encoding), false) = (encoding, options.strip_encoding) {
+        normalized.push('.');
+        normalized.push_str(encoding);
+    }
+    if let (Some(modifier), false) = (modifier, options.drop_modifier) {
+        normalized.push('@');
+        normalized.push_str(modifier);
+    }
+    Some(normalized)
+}
+
+/// Converts subtags into their canonical casing, following RFC 5646: scripts are titlecased and
+/// regions are uppercased, while everything else, including the subtags after a singleton such
+/// as `u` or `x`, is lowercased.
+fn canonicalize_case<'a>(subtags: impl Iterator<Item = &'a mut String>) {
+    let mut in_extension = false;
+    for (idx, subtag) in subtags.enumerate() {
+        subtag.make_ascii_lowercase();
+        if idx == 0 || in_extension {
+            continue;
+        }
+
+        let alphabetic = subtag.bytes().all(|b| b.is_ascii_alphabetic());
+        match subtag.len() {
+            1 => in_extension = true,
+            2 if alphabetic => subtag.make_ascii_uppercase(),
+            4 if alphabetic => subtag[..1].make_ascii_uppercase(),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{normalize_locale_code, parse_locale_code, NormalizationOptions};
+
+    #[test]
+    fn parse_posix_locale() {
+        assert_eq!(parse_locale_code("C"), None);
+        assert_eq!(parse_locale_code("POSIX"), None);
+        assert_eq!(parse_locale_code("C.UTF-8"), None);
+        assert_eq!(parse_locale_code("C.utf8"), None);
+    }
+
+    const PARSE_LOCALE: &str = "fr-FR";
+    const LANG_PARSE_LOCALE: &str = "fr_FR";
+
+    #[test]
+    fn parse_identifier() {
+        let identifier = "fr_FR.UTF-8";
+        assert_eq!(parse_locale_code(identifier).as_deref(), Some(PARSE_LOCALE));
+    }
+
+    #[test]
+    fn parse_non_suffixed_identifier() {
+        assert_eq!(
+            parse_locale_code(PARSE_LOCALE).as_deref(),
+            Some(PARSE_LOCALE)
+        );
+
+        assert_eq!(
+            parse_locale_code(LANG_PARSE_LOCALE).as_deref(),
+            Some(PARSE_LOCALE)
+        );
+    }
+
+    #[test]
+    fn parse_encoding_suffix() {
+        assert_eq!(parse_locale_code("en_US.UTF-8").as_deref(), Some("en-US"));
+        assert_eq!(
+            parse_locale_code("de_DE.ISO-8859-1").as_deref(),
+            Some("de-DE")
+        );
+        assert_eq!(
+            parse_locale_code("de_DE.ISO-8859-1@euro").as_deref(),
+            Some("de-DE")
+        );
+        assert_eq!(parse_locale_code("de_DE").as_deref(), Some("de-DE"));
+        assert_eq!(parse_locale_code("de").as_deref(), Some("de"));
+    }
+
+    #[test]
+    fn normalization_options() {
+        const RAW: &str = "sr_RS.UTF-8@latin";
+        let none = NormalizationOptions {
+            strip_encoding: false,
+            rewrite_separator: false,
+            canonical_case: false,
+            drop_modifier: false,
+        };
+        let normalize =
+            |code: &str, options: NormalizationOptions| normalize_locale_code(code, &options);
+
+        assert_eq!(normalize(RAW, none).as_deref(), Some(RAW));
+        assert_eq!(
+            normalize(RAW, NormalizationOptions::default()).as_deref(),
+            Some("sr-Latn-RS")
+        );
+        assert_eq!(
+            normalize(
+                RAW,
+                NormalizationOptions {
+                    strip_encoding: true,
+                    ..none
+                }
+            )
+            .as_deref(),
+            Some("sr_RS@latin")
+        );
+        assert_eq!(
+            normalize(
+                RAW,
+                NormalizationOptions {
+                    rewrite_separator: true,
+                    ..none
+                }
+            )
+            .as_deref(),
+            Some("sr-RS.UTF-8@latin")
+        );
+        assert_eq!(
+            normalize(
+                "SR_rs.UTF-8@latin",
+                NormalizationOptions {
+                    canonical_case: true,
+                    ..none
+                }
+            )
+            .as_deref(),
+            Some(RAW)
+        );
+        assert_eq!(
+            normalize(
+                RAW,
+                NormalizationOptions {
+                    drop_modifier: true,
+                    ..none
+                }
+            )
+            .as_deref(),
+            Some("sr_Latn_RS.UTF-8")
+        );
+
+        // Modifiers without an equivalent subtag are only removed.
+        assert_eq!(
+            normalize(
+                "de_DE@euro",
+                NormalizationOptions {
+                    drop_modifier: true,
+                    ..none
+                }
+            )
+            .as_deref(),
+            Some("de_DE")
+        );
+        assert_eq!(
+            normalize("ca_ES.UTF-8@valencia", NormalizationOptions::default()).as_deref(),
+            Some("ca-ES-valencia")
+        );
+        assert_eq!(
+            normalize("ZH-hant-tw-U-CA-chinese", NormalizationOptions::default()).as_deref(),
+            Some("zh-Hant-TW-u-ca-chinese")
+        );
+        assert_eq!(normalize("C.UTF-8", none), None);
+        assert_eq!(normalize("POSIX", NormalizationOptions::default()), None);
+    }
+
+    #[test]
+    fn parse_modifier() {
+        assert_eq!(
+            parse_locale_code("sr_RS@latin").as_deref(),
+            Some("sr-Latn-RS")
+        );
+        assert_eq!(
+            parse_locale_code("sr_RS.UTF-8@latin").as_deref(),
+            Some("sr-Latn-RS")
+        );
+        assert_eq!(
+            parse_locale_code("uz_UZ@cyrillic").as_deref(),
+            Some("uz-Cyrl-UZ")
+        );
+        assert_eq!(
+            parse_locale_code("ca_ES.UTF-8@valencia").as_deref(),
+            Some("ca-ES-valencia")
+        );
+
+        // Modifiers without a BCP-47 equivalent are dropped.
+        assert_eq!(parse_locale_code("de_DE@euro").as_deref(), Some("de-DE"));
+        assert_eq!(
+            parse_locale_code("de_DE.ISO-8859-15@euro").as_deref(),
+            Some("de-DE")
+        );
+    }
+}
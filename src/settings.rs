@@ -0,0 +1,1298 @@
+//! Settings which are derived from the locale, such as the measurement system.
+use crate::Locale;
+use alloc::string::String;
+#[cfg(any(
+    test,
+    any(target_os = "macos", target_os = "ios"),
+    all(target_os = "linux", target_env = "gnu"),
+    windows
+))]
+use alloc::vec::Vec;
+#[cfg(any(test, target_os = "macos", target_os = "ios"))]
+use core::convert::TryFrom;
+
+/// A system of measurement units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MeasurementSystem {
+    /// The metric system, using units such as meters and kilograms.
+    Metric,
+    /// United States customary units, such as miles and pounds.
+    UsCustomary,
+    /// The mix of metric and imperial units used in the United Kingdom, such as miles and kilograms.
+    Uk,
+}
+
+impl MeasurementSystem {
+    /// Returns the measurement system most commonly used in a region, following the
+    /// CLDR `measurementData`.
+    #[cfg(any(
+        test,
+        feature = "test-stub",
+        all(unix, not(any(target_os = "macos", target_os = "ios"))),
+        all(target_family = "wasm", any(feature = "js", feature = "web-sys"))
+    ))]
+    pub(crate) fn for_region(region: &str) -> MeasurementSystem {
+        match region {
+            "US" | "LR" | "MM" => MeasurementSystem::UsCustomary,
+            "GB" => MeasurementSystem::Uk,
+            _ => MeasurementSystem::Metric,
+        }
+    }
+}
+
+/// A standard size of paper for printing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PaperSize {
+    /// ISO 216 A4, which is 210 × 297 mm.
+    A4,
+    /// US Letter, which is 8.5 × 11 inches (216 × 279 mm).
+    Letter,
+}
+
+impl PaperSize {
+    /// Returns the paper size most commonly used in a region, following the CLDR `paperSize`.
+    #[cfg(any(
+        test,
+        feature = "test-stub",
+        unix,
+        all(target_family = "wasm", any(feature = "js", feature = "web-sys"))
+    ))]
+    pub(crate) fn for_region(region: &str) -> PaperSize {
+        match region {
+            "BZ" | "CA" | "CL" | "CO" | "CR" | "GT" | "MX" | "NI" | "PA" | "PH" | "PR" | "SV"
+            | "US" | "VE" => PaperSize::Letter,
+            _ => PaperSize::A4,
+        }
+    }
+
+    /// Returns the paper size with the given dimensions in millimeters, which are rounded as
+    /// in glibc's `LC_PAPER`.
+    ///
+    /// Returns `None` if the dimensions don't match a known paper size.
+    #[cfg(any(test, all(target_os = "linux", target_env = "gnu"),))]
+    pub(crate) fn from_millimeters(width: u32, height: u32) -> Option<PaperSize> {
+        match (width, height) {
+            (210, 297) => Some(PaperSize::A4),
+            (216, 279) => Some(PaperSize::Letter),
+            _ => None,
+        }
+    }
+}
+
+/// The characters used to separate the parts of a number, such as `1,234.5`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NumberSeparators {
+    /// The character between the integer and fractional parts of a number, such as `.` in `1.5`.
+    pub decimal: char,
+    /// The character between groups of digits, such as `,` in `1,234`.
+    ///
+    /// This is `None` if digits aren't grouped. Some locales group digits with whitespace,
+    /// such as the narrow no-break space (U+202F) in French, so this isn't always visible.
+    pub grouping: Option<char>,
+}
+
+impl NumberSeparators {
+    /// Creates the separators from the strings provided by the platform.
+    ///
+    /// Returns `None` if there is no decimal separator.
+    #[cfg(any(
+        test,
+        any(target_os = "macos", target_os = "ios"),
+        all(target_os = "linux", any(target_env = "gnu", target_env = "musl")),
+        windows
+    ))]
+    pub(crate) fn from_strings(decimal: &str, grouping: &str) -> Option<NumberSeparators> {
+        Some(NumberSeparators {
+            decimal: decimal.chars().next()?,
+            grouping: grouping.chars().next(),
+        })
+    }
+
+    /// Returns the separator of the items of a list which doesn't conflict with the decimal
+    /// separator, as in spreadsheets: `,` unless it's the decimal separator, and then `;`.
+    #[cfg(any(
+        test,
+        any(target_os = "macos", target_os = "ios"),
+        all(
+            unix,
+            not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+        )
+    ))]
+    pub(crate) fn list_separator(&self) -> char {
+        if self.decimal == ',' {
+            ';'
+        } else {
+            ','
+        }
+    }
+}
+
+/// Converts the sizes of the groups of digits provided by the platform, from the one closest to
+/// the decimal separator outwards, into those returned by `get_grouping_sizes`.
+///
+/// The sizes end at the first `0`, or at the `CHAR_MAX` which glibc uses to stop grouping, which
+/// is 127 or 255 depending on the platform. The last size is repeated for the rest of the number,
+/// so a trailing run of the same size is only kept once, and `[3, 3]` becomes `[3]`.
+#[cfg(any(
+    test,
+    any(target_os = "macos", target_os = "ios"),
+    all(target_os = "linux", target_env = "gnu"),
+    windows
+))]
+pub(crate) fn grouping_sizes(sizes: impl IntoIterator<Item = u8>) -> Vec<u8> {
+    let mut grouping = Vec::new();
+    for size in sizes {
+        if size == 0 || size >= 127 {
+            break;
+        }
+        grouping.push(size);
+    }
+    while grouping.len() > 1 && grouping[grouping.len() - 1] == grouping[grouping.len() - 2] {
+        grouping.pop();
+    }
+    grouping
+}
+
+/// Finds the sizes of the groups of digits in an ICU number pattern, such as `#,##,##0.###`,
+/// where the primary size is the number of digits after the last `,`, and the secondary size
+/// is the number between the last two.
+#[cfg(any(test, target_os = "macos", target_os = "ios"))]
+pub(crate) fn pattern_grouping_sizes(pattern: &str) -> Vec<u8> {
+    // Only the integer digits of the positive pattern are grouped.
+    let integer = pattern
+        .split(&[';', '.', 'E'][..])
+        .next()
+        .unwrap_or_default();
+    let mut groups = integer
+        .chars()
+        .filter(|c| matches!(c, '#' | '0'..='9' | '@' | ','))
+        .collect::<String>()
+        .rsplit(',')
+        .map(|group| u8::try_from(group.len()).unwrap_or(u8::MAX))
+        .collect::<Vec<_>>();
+
+    // The group before the first `,` is only as long as the pattern, so it isn't a size.
+    groups.pop();
+    grouping_sizes(groups.into_iter().take(2))
+}
+
+/// Returns the CLDR numbering system of a locale, which is picked by its `nu` keyword if it has
+/// one, such as in `ar-EG-u-nu-latn`, or is otherwise the default of its language and region.
+#[cfg(any(
+    test,
+    feature = "test-stub",
+    unix,
+    windows,
+    all(target_family = "wasm", any(feature = "js", feature = "web-sys"))
+))]
+pub(crate) fn numbering_system_for_locale(locale: &Locale) -> String {
+    if let Some(types) = locale.unicode_keyword("nu") {
+        return types.join("-");
+    }
+
+    let system = match (locale.language(), locale.region()) {
+        // Arabic is written with Latin digits in the Maghreb.
+        ("ar", Some("DZ"))
+        | ("ar", Some("EH"))
+        | ("ar", Some("LY"))
+        | ("ar", Some("MA"))
+        | ("ar", Some("TN")) => "latn",
+        ("ar", _) | ("ckb", _) => "arab",
+        ("fa", _) | ("ps", _) => "arabext",
+        ("as", _) | ("bn", _) => "beng",
+        ("mr", _) | ("ne", _) => "deva",
+        ("my", _) => "mymr",
+        ("dz", _) => "tibt",
+        _ => "latn",
+    };
+    String::from(system)
+}
+
+/// Returns the CLDR numbering system whose digits start with `zero`, such as `arab` for `٠`
+/// (U+0660), or `None` if it isn't one of those which are known.
+#[cfg(any(test, windows))]
+pub(crate) fn numbering_system_for_zero(zero: char) -> Option<&'static str> {
+    match zero {
+        '0' => Some("latn"),
+        '\u{0660}' => Some("arab"),
+        '\u{06F0}' => Some("arabext"),
+        '\u{0966}' => Some("deva"),
+        '\u{09E6}' => Some("beng"),
+        '\u{0E50}' => Some("thai"),
+        '\u{0F20}' => Some("tibt"),
+        '\u{1040}' => Some("mymr"),
+        _ => None,
+    }
+}
+
+/// The strings used when formatting dates and times, such as the `PM` in `1:30 PM`.
+///
+/// Each string is `None` if the locale doesn't define it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct DateTimeSymbols {
+    /// The designator for times before noon on a 12-hour clock, such as `AM`.
+    pub am: Option<String>,
+    /// The designator for times after noon on a 12-hour clock, such as `PM`.
+    pub pm: Option<String>,
+    /// The string between the parts of a date, such as `/` in `12/31/1999`.
+    pub date_separator: Option<String>,
+    /// The string between the parts of a time, such as `:` in `23:59`.
+    pub time_separator: Option<String>,
+}
+
+impl DateTimeSymbols {
+    /// Creates the symbols from the strings provided by the platform, trimming any surrounding
+    /// whitespace and treating empty strings as missing.
+    ///
+    /// Returns `None` if none of the strings are available.
+    #[cfg(any(test, windows))]
+    pub(crate) fn from_strings(
+        am: Option<String>,
+        pm: Option<String>,
+        date_separator: Option<String>,
+        time_separator: Option<String>,
+    ) -> Option<DateTimeSymbols> {
+        fn trimmed(value: Option<String>) -> Option<String> {
+            value
+                .map(|value| String::from(value.trim()))
+                .filter(|value| !value.is_empty())
+        }
+
+        let symbols = DateTimeSymbols {
+            am: trimmed(am),
+            pm: trimmed(pm),
+            date_separator: trimmed(date_separator),
+            time_separator: trimmed(time_separator),
+        };
+        if symbols == DateTimeSymbols::default() {
+            None
+        } else {
+            Some(symbols)
+        }
+    }
+}
+
+/// How the user's locale formats amounts of money, such as `$1,234.50` or `-1.234,50 €`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CurrencyFormat {
+    /// Whether the currency symbol comes before or after the amount.
+    pub symbol_position: CurrencySymbolPosition,
+    /// Whether the digits of the amount are grouped, such as `1,234` rather than `1234`.
+    pub grouping: bool,
+    /// How negative amounts are shown.
+    pub negative_style: NegativeStyle,
+}
+
+/// Where the currency symbol is placed relative to an amount of money.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CurrencySymbolPosition {
+    /// The symbol leads the amount, such as `$1.50`.
+    Before,
+    /// The symbol trails the amount, such as `1,50 €`.
+    After,
+}
+
+/// How a negative amount of money is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NegativeStyle {
+    /// With a minus sign, such as `-$1.50`.
+    Minus,
+    /// Between parentheses, such as `($1.50)`, as is common in accounting.
+    Parentheses,
+}
+
+impl CurrencyFormat {
+    /// Reads the format from an ICU number pattern, such as `¤#,##0.00;(¤#,##0.00)`.
+    ///
+    /// In these patterns, `¤` is the currency symbol, an optional second pattern after `;` is
+    /// used for negative amounts, and text between single quotes is literal.
+    ///
+    /// Returns `None` if the pattern doesn't contain both the currency symbol and a digit.
+    #[cfg(any(test, target_os = "macos", target_os = "ios"))]
+    pub(crate) fn from_pattern(pattern: &str) -> Option<CurrencyFormat> {
+        let (positive, negative) = match unquoted_chars(pattern).find(|(_, c)| *c == ';') {
+            Some((i, _)) => (&pattern[..i], Some(&pattern[i + 1..])),
+            None => (pattern, None),
+        };
+
+        let symbol = unquoted_chars(positive).find(|(_, c)| *c == '¤')?.0;
+        let digit = unquoted_chars(positive)
+            .find(|(_, c)| matches!(c, '#' | '0'..='9' | '@'))?
+            .0;
+
+        let negative_style = match negative {
+            Some(negative) if unquoted_chars(negative).any(|(_, c)| c == '(') => {
+                NegativeStyle::Parentheses
+            }
+            _ => NegativeStyle::Minus,
+        };
+
+        Some(CurrencyFormat {
+            symbol_position: if symbol < digit {
+                CurrencySymbolPosition::Before
+            } else {
+                CurrencySymbolPosition::After
+            },
+            grouping: unquoted_chars(positive).any(|(_, c)| c == ','),
+            negative_style,
+        })
+    }
+}
+
+/// The symbols used when formatting percentages, such as the `%` in `50 %`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PercentSymbol {
+    /// The percent sign, such as `%`. Some locales use a different character, such as `٪`
+    /// in Arabic.
+    pub symbol: String,
+    /// The per-mille sign, such as `‰`, or `None` if the locale doesn't define it.
+    pub per_mille: Option<String>,
+    /// Whether the symbol is separated from the number by a space, such as the no-break space
+    /// in the French `50 %`, or `None` if this isn't known.
+    pub space_separated: Option<bool>,
+}
+
+impl PercentSymbol {
+    /// Creates the symbols from the strings provided by the platform, trimming any surrounding
+    /// whitespace and treating an empty per-mille sign as missing.
+    ///
+    /// If the percent sign itself contained the space which separates it from the number, and
+    /// the platform doesn't otherwise say whether it's separated, it's taken to be separated.
+    /// Returns `None` if there is no percent sign.
+    #[cfg(any(test, target_os = "macos", target_os = "ios", windows))]
+    pub(crate) fn from_strings(
+        symbol: &str,
+        per_mille: &str,
+        space_separated: Option<bool>,
+    ) -> Option<PercentSymbol> {
+        let trimmed = symbol.trim();
+        if trimmed.is_empty() {
+            return None;
+        }
+
+        let per_mille = per_mille.trim();
+        Some(PercentSymbol {
+            symbol: String::from(trimmed),
+            per_mille: if per_mille.is_empty() {
+                None
+            } else {
+                Some(String::from(per_mille))
+            },
+            space_separated: match space_separated {
+                Some(separated) => Some(separated),
+                None if trimmed.len() != symbol.len() => Some(true),
+                None => None,
+            },
+        })
+    }
+}
+
+/// The signs written before or after a number to show whether it's positive or negative, such as
+/// the `-` in `-5`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SignSymbols {
+    /// The plus sign, such as `+`.
+    pub positive: String,
+    /// The minus sign, such as `-`. Some locales use a different character, such as the minus
+    /// sign (U+2212) in Swedish, or include a bidirectional mark, as in Arabic.
+    pub negative: String,
+}
+
+impl SignSymbols {
+    /// Creates the symbols from the strings provided by the platform, trimming any surrounding
+    /// whitespace but keeping bidirectional marks.
+    ///
+    /// Some platforms leave the plus sign empty, since positive numbers aren't usually signed, so
+    /// it's `+` when it's missing. Returns `None` if there is no minus sign.
+    #[cfg(any(
+        test,
+        any(target_os = "macos", target_os = "ios"),
+        all(target_os = "linux", target_env = "gnu"),
+        windows
+    ))]
+    pub(crate) fn from_strings(positive: &str, negative: &str) -> Option<SignSymbols> {
+        let negative = negative.trim();
+        if negative.is_empty() {
+            return None;
+        }
+
+        let positive = positive.trim();
+        Some(SignSymbols {
+            positive: String::from(if positive.is_empty() { "+" } else { positive }),
+            negative: String::from(negative),
+        })
+    }
+}
+
+/// Checks whether the percent sign in an ICU percent pattern, such as `#,##0 %`, is separated
+/// from the number by whitespace.
+///
+/// Returns `None` if the pattern doesn't contain both the percent sign and a digit.
+#[cfg(any(test, target_os = "macos", target_os = "ios"))]
+pub(crate) fn pattern_separates_percent(pattern: &str) -> Option<bool> {
+    let positive = match unquoted_chars(pattern).find(|(_, c)| *c == ';') {
+        Some((i, _)) => &pattern[..i],
+        None => pattern,
+    };
+
+    let percent = unquoted_chars(positive).find(|(_, c)| *c == '%')?.0;
+    let mut digits =
+        unquoted_chars(positive).filter(|(_, c)| matches!(c, '#' | '0'..='9' | '@' | ','));
+    let first = digits.next()?.0;
+    let last = digits.last().map_or(first, |(i, _)| i);
+
+    // The digits are all ASCII, so the number ends one byte after the last of them.
+    let between = if percent < first {
+        &positive[percent + '%'.len_utf8()..first]
+    } else {
+        &positive[last + 1..percent]
+    };
+    Some(between.chars().any(char::is_whitespace))
+}
+
+/// Iterates over the chars of an ICU pattern which aren't between single quotes, along with
+/// their byte offsets.
+#[cfg(any(test, target_os = "macos", target_os = "ios"))]
+fn unquoted_chars(pattern: &str) -> impl Iterator<Item = (usize, char)> + '_ {
+    let mut quoted = false;
+    pattern.char_indices().filter(move |(_, c)| {
+        if *c == '\'' {
+            quoted = !quoted;
+            false
+        } else {
+            !quoted
+        }
+    })
+}
+
+/// A day of the week.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Weekday {
+    /// Monday, the first day of the week in ISO 8601.
+    Monday,
+    /// Tuesday.
+    Tuesday,
+    /// Wednesday.
+    Wednesday,
+    /// Thursday.
+    Thursday,
+    /// Friday.
+    Friday,
+    /// Saturday.
+    Saturday,
+    /// Sunday.
+    Sunday,
+}
+
+impl Weekday {
+    /// Returns the day which is `days` after Monday, wrapping around at the end of the week.
+    #[cfg(any(
+        test,
+        any(target_os = "macos", target_os = "ios"),
+        all(target_os = "linux", target_env = "gnu"),
+        windows
+    ))]
+    pub(crate) fn from_monday_offset(days: u32) -> Weekday {
+        match days % 7 {
+            0 => Weekday::Monday,
+            1 => Weekday::Tuesday,
+            2 => Weekday::Wednesday,
+            3 => Weekday::Thursday,
+            4 => Weekday::Friday,
+            5 => Weekday::Saturday,
+            _ => Weekday::Sunday,
+        }
+    }
+
+    /// Returns the first and last days of the weekend in a region, following the CLDR
+    /// `weekData`. Both are the same day if the weekend is only one day long.
+    #[cfg(any(
+        test,
+        feature = "test-stub",
+        unix,
+        windows,
+        all(target_family = "wasm", any(feature = "js", feature = "web-sys"))
+    ))]
+    pub(crate) fn weekend_for_region(region: &str) -> (Weekday, Weekday) {
+        match region {
+            "AE" | "BH" | "DZ" | "EG" | "IL" | "IQ" | "JO" | "KW" | "LY" | "OM" | "QA" | "SA"
+            | "SD" | "SY" | "YE" => (Weekday::Friday, Weekday::Saturday),
+            "AF" => (Weekday::Thursday, Weekday::Friday),
+            "IR" => (Weekday::Friday, Weekday::Friday),
+            "IN" | "UG" => (Weekday::Sunday, Weekday::Sunday),
+            _ => (Weekday::Saturday, Weekday::Sunday),
+        }
+    }
+}
+
+/// Checks if a date format pattern, such as `h:mm a`, shows the hour on a 24-hour clock.
+///
+/// This understands the patterns of ICU (as used on Apple platforms) and Windows, where `H`
+/// and `k` are hours on a 24-hour clock, `h` and `K` are hours on a 12-hour clock, and text
+/// between single quotes is literal.
+///
+/// Returns `None` if the pattern doesn't contain an hour.
+#[cfg(any(test, target_os = "macos", target_os = "ios", windows))]
+pub(crate) fn pattern_uses_24_hour_time(pattern: &str) -> Option<bool> {
+    let mut quoted = false;
+    for c in pattern.chars() {
+        match c {
+            '\'' => quoted = !quoted,
+            'H' | 'k' if !quoted => return Some(true),
+            'h' | 'K' if !quoted => return Some(false),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Checks if a `strftime` format, such as `%I:%M:%S %p`, shows the hour on a 24-hour clock.
+///
+/// Returns `None` if the format doesn't contain an hour.
+#[cfg(any(
+    test,
+    all(target_os = "linux", any(target_env = "gnu", target_env = "musl")),
+))]
+pub(crate) fn strftime_uses_24_hour_time(format: &str) -> Option<bool> {
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            continue;
+        }
+
+        // Skip any flags or width, such as in `%-H` or `%02H`, and the `E` and `O` modifiers.
+        let conversion =
+            chars.find(|c| !matches!(c, '_' | '-' | '0'..='9' | '^' | '#' | 'E' | 'O'));
+        match conversion {
+            Some('H') | Some('k') | Some('R') | Some('T') => return Some(true),
+            Some('I') | Some('l') | Some('r') | Some('p') | Some('P') => return Some(false),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The order in which the day, month, and year are written in a date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DateOrder {
+    /// Day, month, then year, such as `31.12.2024` in German.
+    Dmy,
+    /// Month, day, then year, such as `12/31/2024` in US English.
+    Mdy,
+    /// Year, month, then day, such as `2024/12/31` in Japanese.
+    Ymd,
+}
+
+impl DateOrder {
+    /// Returns the order of `fields`, which are `'d'`, `'m'`, and `'y'` for each field of a date
+    /// format in the order they're written. Only the first of each field is considered.
+    ///
+    /// Returns `None` if a field is missing or they're in another order, such as `y-d-m`.
+    #[cfg(any(
+        test,
+        any(target_os = "macos", target_os = "ios"),
+        all(target_os = "linux", any(target_env = "gnu", target_env = "musl")),
+        windows
+    ))]
+    fn from_fields(fields: impl Iterator<Item = char>) -> Option<DateOrder> {
+        let mut order = String::new();
+        for field in fields {
+            if !order.contains(field) {
+                order.push(field);
+            }
+        }
+        match order.as_str() {
+            "dmy" => Some(DateOrder::Dmy),
+            "mdy" => Some(DateOrder::Mdy),
+            "ymd" => Some(DateOrder::Ymd),
+            _ => None,
+        }
+    }
+}
+
+/// Finds the order of the fields in a date format pattern, such as `dd.MM.y`.
+///
+/// This understands the patterns of ICU (as used on Apple platforms) and Windows, where `d` is
+/// the day, `M` and `L` are the month, `y` is the year, and text between single quotes is
+/// literal. Windows writes the name of the weekday as `ddd` or `dddd`, which is skipped.
+#[cfg(any(test, target_os = "macos", target_os = "ios", windows))]
+pub(crate) fn pattern_date_order(pattern: &str) -> Option<DateOrder> {
+    let mut fields = String::new();
+    let mut quoted = false;
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        let mut len = 1;
+        while chars.peek() == Some(&c) {
+            chars.next();
+            len += 1;
+        }
+        match c {
+            '\'' => quoted = !quoted,
+            _ if quoted => {}
+            'd' if len <= 2 => fields.push('d'),
+            'M' | 'L' => fields.push('m'),
+            'y' => fields.push('y'),
+            _ => {}
+        }
+    }
+    DateOrder::from_fields(fields.chars())
+}
+
+/// Finds the order of the fields in a `strftime` date format, such as `%d.%m.%Y`.
+#[cfg(any(
+    test,
+    all(target_os = "linux", any(target_env = "gnu", target_env = "musl")),
+))]
+pub(crate) fn strftime_date_order(format: &str) -> Option<DateOrder> {
+    let mut fields = String::new();
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            continue;
+        }
+
+        // Skip any flags or width, such as in `%-d` or `%02d`, and the `E` and `O` modifiers.
+        let conversion =
+            chars.find(|c| !matches!(c, '_' | '-' | '0'..='9' | '^' | '#' | 'E' | 'O'));
+        match conversion {
+            Some('d') | Some('e') => fields.push('d'),
+            Some('m') | Some('b') | Some('B') | Some('h') => fields.push('m'),
+            Some('y') | Some('Y') | Some('C') | Some('G') | Some('g') => fields.push('y'),
+            Some('D') => fields.push_str("mdy"),
+            Some('F') => fields.push_str("ymd"),
+            _ => {}
+        }
+    }
+    DateOrder::from_fields(fields.chars())
+}
+
+/// A snapshot of the settings derived from the user's locale, as returned by
+/// `get_locale_settings`.
+///
+/// Each setting is the same as the one returned by its own function, such as `get_region`, and is
+/// `None` if the platform doesn't provide it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct LocaleSettings {
+    /// The user's most preferred locale, from `get_locale`.
+    pub locale: Option<String>,
+    /// The user's region, from `get_region`.
+    pub region: Option<String>,
+    /// The user's system of measurement units, from `get_measurement_system`.
+    pub measurement_system: Option<MeasurementSystem>,
+    /// The ISO 4217 code of the user's currency, from `get_currency_code`.
+    pub currency_code: Option<String>,
+    /// The first day of the user's week, from `get_first_day_of_week`.
+    pub first_day_of_week: Option<Weekday>,
+    /// Whether the user's times are written with a 24-hour clock, from `uses_24_hour_time`.
+    pub uses_24_hour_time: Option<bool>,
+    /// The separators of the user's numbers, from `get_number_separators`.
+    pub number_separators: Option<NumberSeparators>,
+}
+
+/// A system for reckoning dates, such as the Gregorian or Buddhist calendar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Calendar {
+    /// The Gregorian calendar, which is the default in most regions.
+    Gregorian,
+    /// The Thai solar calendar, which is the Gregorian calendar with years counted in the
+    /// Buddhist era, starting in 543 BC.
+    Buddhist,
+    /// The traditional Chinese lunisolar calendar.
+    Chinese,
+    /// The Hebrew lunisolar calendar.
+    Hebrew,
+    /// The Indian national calendar.
+    Indian,
+    /// One of the Islamic (Hijri) lunar calendars, such as Saudi Arabia's Umm al-Qura calendar.
+    Islamic,
+    /// The Gregorian calendar with years counted in the eras of the Japanese emperors.
+    Japanese,
+    /// The Persian (Solar Hijri) calendar.
+    Persian,
+    /// The Minguo calendar used in Taiwan, which is the Gregorian calendar with years counted
+    /// from 1912.
+    RepublicOfChina,
+}
+
+impl Calendar {
+    /// Returns the calendar with an identifier from the CLDR, such as the `buddhist` in
+    /// `th-TH-u-ca-buddhist`, or from CoreFoundation, which uses `gregorian` and `iso8601` for the
+    /// Gregorian calendar.
+    ///
+    /// Returns `None` if the calendar isn't one of those which are supported.
+    #[cfg(any(
+        test,
+        feature = "test-stub",
+        unix,
+        all(target_family = "wasm", any(feature = "js", feature = "web-sys"))
+    ))]
+    pub(crate) fn from_identifier(identifier: &str) -> Option<Calendar> {
+        let identifier = identifier.to_ascii_lowercase();
+        match identifier.as_str() {
+            "gregory" | "gregorian" | "iso8601" => Some(Calendar::Gregorian),
+            "buddhist" => Some(Calendar::Buddhist),
+            "chinese" => Some(Calendar::Chinese),
+            "hebrew" => Some(Calendar::Hebrew),
+            "indian" => Some(Calendar::Indian),
+            "japanese" => Some(Calendar::Japanese),
+            "persian" => Some(Calendar::Persian),
+            "roc" => Some(Calendar::RepublicOfChina),
+            // Such as `islamic-civil` and `islamic-umalqura`.
+            _ if identifier == "islamic" || identifier.starts_with("islamic-") => {
+                Some(Calendar::Islamic)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the calendar which is used by default in a region, following the first calendar
+    /// of the CLDR `calendarPreferenceData`.
+    #[cfg(any(
+        test,
+        feature = "test-stub",
+        all(unix, not(any(target_os = "macos", target_os = "ios"))),
+        all(target_family = "wasm", any(feature = "js", feature = "web-sys"))
+    ))]
+    pub(crate) fn for_region(region: &str) -> Calendar {
+        match region {
+            "TH" => Calendar::Buddhist,
+            "AF" | "IR" => Calendar::Persian,
+            "SA" => Calendar::Islamic,
+            _ => Calendar::Gregorian,
+        }
+    }
+
+    /// Returns the calendar of a locale, which is picked by its `ca` keyword if it has one, such
+    /// as in `th-TH-u-ca-buddhist`, or is otherwise the default of its region.
+    ///
+    /// Returns `None` if the locale picks a calendar which isn't supported, or if it has neither
+    /// the keyword nor a region.
+    #[cfg(any(
+        test,
+        feature = "test-stub",
+        all(unix, not(any(target_os = "macos", target_os = "ios"))),
+        all(target_family = "wasm", any(feature = "js", feature = "web-sys"))
+    ))]
+    pub(crate) fn for_locale(locale: &Locale) -> Option<Calendar> {
+        match locale.unicode_keyword("ca") {
+            Some(types) => Calendar::from_identifier(&types.join("-")),
+            None => locale.region().map(Calendar::for_region),
+        }
+    }
+}
+
+/// The direction in which text is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextDirection {
+    /// Text starts on the left, such as in English.
+    LeftToRight,
+    /// Text starts on the right, such as in Arabic and Hebrew.
+    RightToLeft,
+}
+
+impl TextDirection {
+    /// Returns the direction of the locale's script, following the CLDR `characterOrder`.
+    ///
+    /// If the locale doesn't have a script subtag, the direction of the script which its
+    /// language is most commonly written in is used instead.
+    pub(crate) fn for_locale(locale: &Locale) -> TextDirection {
+        let right_to_left = match locale.script() {
+            Some(script) => matches!(
+                script,
+                "Adlm"
+                    | "Arab"
+                    | "Aran"
+                    | "Hebr"
+                    | "Mand"
+                    | "Mend"
+                    | "Nkoo"
+                    | "Rohg"
+                    | "Samr"
+                    | "Syrc"
+                    | "Thaa"
+                    | "Yezi"
+            ),
+            None => matches!(
+                locale.language(),
+                "ar" | "arc"
+                    | "ckb"
+                    | "dv"
+                    | "fa"
+                    | "he"
+                    | "iw"
+                    | "ks"
+                    | "lrc"
+                    | "mzn"
+                    | "nqo"
+                    | "ps"
+                    | "sd"
+                    | "syr"
+                    | "ug"
+                    | "ur"
+                    | "yi"
+            ),
+        };
+
+        if right_to_left {
+            TextDirection::RightToLeft
+        } else {
+            TextDirection::LeftToRight
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        grouping_sizes, numbering_system_for_locale, numbering_system_for_zero, pattern_date_order,
+        pattern_grouping_sizes, pattern_uses_24_hour_time, strftime_date_order,
+        strftime_uses_24_hour_time, Calendar, CurrencyFormat, CurrencySymbolPosition, DateOrder,
+        DateTimeSymbols, MeasurementSystem, NegativeStyle, NumberSeparators, PaperSize,
+        TextDirection, Weekday,
+    };
+    use crate::Locale;
+    use crate::PercentSymbol;
+    use alloc::{string::String, vec, vec::Vec};
+
+    fn direction(tag: &str) -> TextDirection {
+        TextDirection::for_locale(&Locale::from_tag(tag).unwrap())
+    }
+
+    #[test]
+    fn datetime_symbols_from_strings() {
+        let symbols = DateTimeSymbols::from_strings(
+            Some(String::from(" vorm. ")),
+            Some(String::from("")),
+            Some(String::from(".")),
+            None,
+        );
+        assert_eq!(
+            symbols,
+            Some(DateTimeSymbols {
+                am: Some(String::from("vorm.")),
+                pm: None,
+                date_separator: Some(String::from(".")),
+                time_separator: None,
+            })
+        );
+
+        assert_eq!(
+            DateTimeSymbols::from_strings(Some(String::from(" ")), None, None, None),
+            None
+        );
+    }
+
+    #[test]
+    fn grouping_sizes_normalized() {
+        assert_eq!(grouping_sizes(vec![3, 2]), [3, 2]);
+        assert_eq!(grouping_sizes(vec![3, 3]), [3]);
+        assert_eq!(grouping_sizes(vec![3, 2, 2, 0]), [3, 2]);
+        // glibc stops grouping with `CHAR_MAX`.
+        assert_eq!(grouping_sizes(vec![3, 127]), [3]);
+        assert!(grouping_sizes(vec![0]).is_empty());
+        assert!(grouping_sizes(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn grouping_sizes_from_pattern() {
+        // en-IN
+        assert_eq!(pattern_grouping_sizes("#,##,##0.###"), [3, 2]);
+        // en-US
+        assert_eq!(pattern_grouping_sizes("#,##0.###"), [3]);
+        assert_eq!(pattern_grouping_sizes("¤#,##0.00;(¤#,##0.00)"), [3]);
+        assert_eq!(pattern_grouping_sizes("#,##,###"), [3, 2]);
+        assert!(pattern_grouping_sizes("0.###").is_empty());
+        assert!(pattern_grouping_sizes("").is_empty());
+    }
+
+    #[test]
+    fn weekday_from_monday_offset() {
+        assert_eq!(Weekday::from_monday_offset(0), Weekday::Monday);
+        assert_eq!(Weekday::from_monday_offset(5), Weekday::Saturday);
+        assert_eq!(Weekday::from_monday_offset(6), Weekday::Sunday);
+        assert_eq!(Weekday::from_monday_offset(7), Weekday::Monday);
+        assert_eq!(Weekday::from_monday_offset(13), Weekday::Sunday);
+    }
+
+    #[test]
+    fn weekend_for_region() {
+        assert_eq!(
+            Weekday::weekend_for_region("SA"),
+            (Weekday::Friday, Weekday::Saturday)
+        );
+        assert_eq!(
+            Weekday::weekend_for_region("EG"),
+            (Weekday::Friday, Weekday::Saturday)
+        );
+        assert_eq!(
+            Weekday::weekend_for_region("IN"),
+            (Weekday::Sunday, Weekday::Sunday)
+        );
+        assert_eq!(
+            Weekday::weekend_for_region("DE"),
+            (Weekday::Saturday, Weekday::Sunday)
+        );
+    }
+
+    #[test]
+    fn hour_cycle_from_pattern() {
+        // ICU skeletons resolved for `j`.
+        assert_eq!(pattern_uses_24_hour_time("h a"), Some(false));
+        assert_eq!(pattern_uses_24_hour_time("HH"), Some(true));
+        assert_eq!(pattern_uses_24_hour_time("K:mm a"), Some(false));
+        assert_eq!(pattern_uses_24_hour_time("a h"), Some(false));
+
+        // Windows `LOCALE_STIMEFORMAT`.
+        assert_eq!(pattern_uses_24_hour_time("h:mm:ss tt"), Some(false));
+        assert_eq!(pattern_uses_24_hour_time("HH:mm:ss"), Some(true));
+        assert_eq!(pattern_uses_24_hour_time("H' h 'mm"), Some(true));
+
+        // Quoted text is literal.
+        assert_eq!(pattern_uses_24_hour_time("'h'HH"), Some(true));
+        assert_eq!(pattern_uses_24_hour_time("mm:ss"), None);
+    }
+
+    #[test]
+    fn hour_cycle_from_strftime() {
+        // en_US
+        assert_eq!(strftime_uses_24_hour_time("%r"), Some(false));
+        assert_eq!(strftime_uses_24_hour_time("%I:%M:%S %p"), Some(false));
+        // de_DE
+        assert_eq!(strftime_uses_24_hour_time("%T"), Some(true));
+        assert_eq!(strftime_uses_24_hour_time("%H:%M:%S"), Some(true));
+        assert_eq!(strftime_uses_24_hour_time("%-H.%M"), Some(true));
+        assert_eq!(strftime_uses_24_hour_time("%l:%M %P"), Some(false));
+
+        assert_eq!(strftime_uses_24_hour_time("%%H"), None);
+        assert_eq!(strftime_uses_24_hour_time("%M:%S"), None);
+        assert_eq!(strftime_uses_24_hour_time(""), None);
+    }
+
+    #[test]
+    fn date_order_from_pattern() {
+        // en-US
+        assert_eq!(pattern_date_order("M/d/y"), Some(DateOrder::Mdy));
+        assert_eq!(pattern_date_order("M/d/yyyy"), Some(DateOrder::Mdy));
+        // de-DE
+        assert_eq!(pattern_date_order("dd.MM.y"), Some(DateOrder::Dmy));
+        assert_eq!(
+            pattern_date_order("dddd, d. MMMM yyyy"),
+            Some(DateOrder::Dmy)
+        );
+        // ja-JP
+        assert_eq!(pattern_date_order("y/MM/dd"), Some(DateOrder::Ymd));
+        assert_eq!(pattern_date_order("y'年'M'月'd'日'"), Some(DateOrder::Ymd));
+
+        // Quoted text is literal.
+        assert_eq!(pattern_date_order("'d 'M/y"), None);
+        assert_eq!(pattern_date_order("MM/yy"), None);
+        assert_eq!(pattern_date_order("y-dd-MM"), None);
+    }
+
+    #[test]
+    fn date_order_from_strftime() {
+        // en_US
+        assert_eq!(strftime_date_order("%m/%d/%Y"), Some(DateOrder::Mdy));
+        assert_eq!(strftime_date_order("%D"), Some(DateOrder::Mdy));
+        // de_DE
+        assert_eq!(strftime_date_order("%d.%m.%Y"), Some(DateOrder::Dmy));
+        assert_eq!(strftime_date_order("%-d. %B %Y"), Some(DateOrder::Dmy));
+        // ja_JP
+        assert_eq!(strftime_date_order("%Y年%m月%d日"), Some(DateOrder::Ymd));
+        assert_eq!(strftime_date_order("%F"), Some(DateOrder::Ymd));
+
+        assert_eq!(strftime_date_order("%%d/%m/%Y"), None);
+        assert_eq!(strftime_date_order("%H:%M"), None);
+        assert_eq!(strftime_date_order(""), None);
+    }
+
+    #[test]
+    fn numbering_system() {
+        let system = |tag| numbering_system_for_locale(&Locale::from_tag(tag).unwrap());
+
+        assert_eq!(system("ar-EG-u-nu-arab"), "arab");
+        assert_eq!(system("ar-EG-u-nu-latn"), "latn");
+        assert_eq!(system("en-US-u-nu-arab"), "arab");
+        assert_eq!(system("en-US"), "latn");
+        assert_eq!(system("ar-EG"), "arab");
+        assert_eq!(system("ar-MA"), "latn");
+        assert_eq!(system("fa-IR"), "arabext");
+        assert_eq!(system("ja"), "latn");
+
+        assert_eq!(numbering_system_for_zero('0'), Some("latn"));
+        assert_eq!(numbering_system_for_zero('\u{0660}'), Some("arab"));
+        assert_eq!(numbering_system_for_zero('\u{06F0}'), Some("arabext"));
+        assert_eq!(numbering_system_for_zero('a'), None);
+    }
+
+    #[test]
+    fn calendar_for_locale() {
+        let calendar = |tag| Calendar::for_locale(&Locale::from_tag(tag).unwrap());
+
+        assert_eq!(calendar("th-TH-u-ca-buddhist"), Some(Calendar::Buddhist));
+        assert_eq!(calendar("ja-JP-u-ca-japanese"), Some(Calendar::Japanese));
+        assert_eq!(
+            calendar("ar-SA-u-ca-islamic-umalqura"),
+            Some(Calendar::Islamic)
+        );
+        assert_eq!(
+            calendar("zh-TW-u-nu-hanidec-ca-roc"),
+            Some(Calendar::RepublicOfChina)
+        );
+        // The keyword takes priority over the region.
+        assert_eq!(calendar("th-TH-u-ca-gregory"), Some(Calendar::Gregorian));
+        assert_eq!(calendar("en-US-u-ca-coptic"), None);
+
+        // Otherwise, the region's default is used.
+        assert_eq!(calendar("th-TH"), Some(Calendar::Buddhist));
+        assert_eq!(calendar("fa-IR"), Some(Calendar::Persian));
+        assert_eq!(calendar("ja-JP"), Some(Calendar::Gregorian));
+        assert_eq!(calendar("en-US-x-ca-buddhist"), Some(Calendar::Gregorian));
+        assert_eq!(calendar("th"), None);
+
+        assert_eq!(
+            Calendar::from_identifier("gregorian"),
+            Some(Calendar::Gregorian)
+        );
+        assert_eq!(
+            Calendar::from_identifier("islamic-civil"),
+            Some(Calendar::Islamic)
+        );
+        assert_eq!(Calendar::from_identifier("islamicx"), None);
+    }
+
+    #[test]
+    fn text_direction_for_locale() {
+        assert_eq!(direction("ar"), TextDirection::RightToLeft);
+        assert_eq!(direction("he-IL"), TextDirection::RightToLeft);
+        assert_eq!(direction("fa-IR"), TextDirection::RightToLeft);
+        assert_eq!(direction("en-US"), TextDirection::LeftToRight);
+        assert_eq!(direction("zh-Hans-CN"), TextDirection::LeftToRight);
+
+        // An explicit script takes priority over the language.
+        assert_eq!(direction("az-Arab"), TextDirection::RightToLeft);
+        assert_eq!(direction("az-Latn"), TextDirection::LeftToRight);
+        assert_eq!(direction("sd-Deva-IN"), TextDirection::LeftToRight);
+    }
+
+    #[test]
+    fn measurement_system_for_region() {
+        assert_eq!(
+            MeasurementSystem::for_region("US"),
+            MeasurementSystem::UsCustomary
+        );
+        assert_eq!(
+            MeasurementSystem::for_region("LR"),
+            MeasurementSystem::UsCustomary
+        );
+        assert_eq!(MeasurementSystem::for_region("GB"), MeasurementSystem::Uk);
+        assert_eq!(
+            MeasurementSystem::for_region("DE"),
+            MeasurementSystem::Metric
+        );
+        assert_eq!(
+            MeasurementSystem::for_region("CA"),
+            MeasurementSystem::Metric
+        );
+    }
+
+    #[test]
+    fn currency_format_from_pattern() {
+        // en-US
+        assert_eq!(
+            CurrencyFormat::from_pattern("¤#,##0.00"),
+            Some(CurrencyFormat {
+                symbol_position: CurrencySymbolPosition::Before,
+                grouping: true,
+                negative_style: NegativeStyle::Minus,
+            })
+        );
+        // de-DE
+        assert_eq!(
+            CurrencyFormat::from_pattern("#,##0.00\u{a0}¤"),
+            Some(CurrencyFormat {
+                symbol_position: CurrencySymbolPosition::After,
+                grouping: true,
+                negative_style: NegativeStyle::Minus,
+            })
+        );
+        // en-US accounting
+        assert_eq!(
+            CurrencyFormat::from_pattern("¤#,##0.00;(¤#,##0.00)"),
+            Some(CurrencyFormat {
+                symbol_position: CurrencySymbolPosition::Before,
+                grouping: true,
+                negative_style: NegativeStyle::Parentheses,
+            })
+        );
+        assert_eq!(
+            CurrencyFormat::from_pattern("¤0.00;-¤0.00"),
+            Some(CurrencyFormat {
+                symbol_position: CurrencySymbolPosition::Before,
+                grouping: false,
+                negative_style: NegativeStyle::Minus,
+            })
+        );
+    }
+
+    #[test]
+    fn percent_symbol_from_strings() {
+        assert_eq!(
+            PercentSymbol::from_strings("%", "‰", Some(false)),
+            Some(PercentSymbol {
+                symbol: String::from("%"),
+                per_mille: Some(String::from("‰")),
+                space_separated: Some(false),
+            })
+        );
+
+        // A no-break space around the sign is trimmed, and means that it's separated.
+        let symbol = PercentSymbol::from_strings("\u{a0}%", " ", None).unwrap();
+        assert_eq!(symbol.symbol, "%");
+        assert_eq!(symbol.per_mille, None);
+        assert_eq!(symbol.space_separated, Some(true));
+
+        // The platform's own knowledge of the spacing takes precedence.
+        let symbol = PercentSymbol::from_strings("\u{202f}٪ ", "؉", Some(false)).unwrap();
+        assert_eq!(symbol.symbol, "٪");
+        assert_eq!(symbol.space_separated, Some(false));
+
+        assert_eq!(
+            PercentSymbol::from_strings("%", "", None)
+                .unwrap()
+                .space_separated,
+            None
+        );
+        assert_eq!(PercentSymbol::from_strings(" ", "‰", Some(true)), None);
+    }
+
+    #[test]
+    fn sign_symbols_from_strings() {
+        use super::SignSymbols;
+
+        assert_eq!(
+            SignSymbols::from_strings("+", "-"),
+            Some(SignSymbols {
+                positive: String::from("+"),
+                negative: String::from("-"),
+            })
+        );
+
+        // The minus sign isn't always ASCII, and marks around it are kept.
+        let symbols = SignSymbols::from_strings("", "\u{2212}").unwrap();
+        assert_eq!(symbols.positive, "+");
+        assert_eq!(symbols.negative, "\u{2212}");
+        let symbols = SignSymbols::from_strings("\u{61c}+", " \u{61c}-").unwrap();
+        assert_eq!(symbols.positive, "\u{61c}+");
+        assert_eq!(symbols.negative, "\u{61c}-");
+
+        assert_eq!(SignSymbols::from_strings("+", ""), None);
+        assert_eq!(SignSymbols::from_strings("+", " "), None);
+    }
+
+    #[test]
+    fn percent_separation_from_pattern() {
+        use super::pattern_separates_percent;
+
+        assert_eq!(pattern_separates_percent("#,##0%"), Some(false));
+        assert_eq!(pattern_separates_percent("#,##0\u{a0}%"), Some(true));
+        assert_eq!(pattern_separates_percent("%#,##0"), Some(false));
+        assert_eq!(pattern_separates_percent("% #,##0;-% #,##0"), Some(true));
+        assert_eq!(pattern_separates_percent("#,##0' '%"), Some(true));
+        assert_eq!(pattern_separates_percent("#,##0'%'"), None);
+        assert_eq!(pattern_separates_percent("%"), None);
+    }
+
+    #[test]
+    fn currency_format_quoted_text() {
+        // Quoted text is literal, so this has neither a leading symbol nor a separate
+        // negative pattern.
+        let format = CurrencyFormat::from_pattern("'¤;('#,##0.00 ¤").unwrap();
+        assert_eq!(format.symbol_position, CurrencySymbolPosition::After);
+        assert_eq!(format.negative_style, NegativeStyle::Minus);
+
+        assert_eq!(CurrencyFormat::from_pattern("#,##0.00"), None);
+        assert_eq!(CurrencyFormat::from_pattern("'#'¤"), None);
+        assert_eq!(CurrencyFormat::from_pattern(""), None);
+    }
+
+    #[test]
+    fn paper_size_for_region() {
+        for region in ["US", "CA", "MX", "PH"].iter() {
+            assert_eq!(
+                PaperSize::for_region(region),
+                PaperSize::Letter,
+                "{}",
+                region
+            );
+        }
+        for region in ["GB", "DE", "JP", "AU", "BR", "419"].iter() {
+            assert_eq!(PaperSize::for_region(region), PaperSize::A4, "{}", region);
+        }
+    }
+
+    #[test]
+    fn paper_size_from_millimeters() {
+        assert_eq!(PaperSize::from_millimeters(210, 297), Some(PaperSize::A4));
+        assert_eq!(
+            PaperSize::from_millimeters(216, 279),
+            Some(PaperSize::Letter)
+        );
+
+        // US Legal, and dimensions which have been swapped.
+        assert_eq!(PaperSize::from_millimeters(216, 356), None);
+        assert_eq!(PaperSize::from_millimeters(297, 210), None);
+    }
+
+    #[test]
+    fn number_separators_from_strings() {
+        // en-US
+        assert_eq!(
+            NumberSeparators::from_strings(".", ","),
+            Some(NumberSeparators {
+                decimal: '.',
+                grouping: Some(',')
+            })
+        );
+
+        // de-DE
+        assert_eq!(
+            NumberSeparators::from_strings(",", "."),
+            Some(NumberSeparators {
+                decimal: ',',
+                grouping: Some('.')
+            })
+        );
+
+        // fr-FR uses a narrow no-break space for grouping.
+        assert_eq!(
+            NumberSeparators::from_strings(",", "\u{202f}"),
+            Some(NumberSeparators {
+                decimal: ',',
+                grouping: Some('\u{202f}')
+            })
+        );
+
+        // The POSIX locale doesn't group digits.
+        assert_eq!(
+            NumberSeparators::from_strings(".", ""),
+            Some(NumberSeparators {
+                decimal: '.',
+                grouping: None
+            })
+        );
+
+        assert_eq!(NumberSeparators::from_strings("", ","), None);
+    }
+
+    #[test]
+    fn list_separators() {
+        // en-US
+        let separators = NumberSeparators::from_strings(".", ",").unwrap();
+        assert_eq!(separators.list_separator(), ',');
+
+        // de-DE
+        let separators = NumberSeparators::from_strings(",", ".").unwrap();
+        assert_eq!(separators.list_separator(), ';');
+    }
+}
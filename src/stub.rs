@@ -0,0 +1,200 @@
+//! A provider which returns a fixed list of locales on every platform, with the `test-stub`
+//! feature, so that tests don't depend on the locale of the machine running them.
+//!
+//! The locales are the comma-separated list in the `SYS_LOCALE_TEST_STUB` environment variable
+//! when the crate is compiled, or `en-US` if it isn't set. Every other setting is derived from
+//! the first of them, or is unavailable if it can't be.
+use crate::{
+    Calendar, CurrencyFormat, DateOrder, DateTimeSymbols, LocaleCategory, LocaleError,
+    LocaleSettings, LocaleSource, MeasurementSystem, NumberSeparators, PaperSize, PercentSymbol,
+    ProviderInfo, SignSymbols, Weekday,
+};
+use alloc::{borrow::Cow, string::String, vec, vec::Vec};
+
+/// The locales which are returned if `SYS_LOCALE_TEST_STUB` isn't set at compile time.
+const DEFAULT_LOCALES: &str = "en-US";
+
+pub(crate) const SUPPORTED: bool = true;
+
+pub(crate) fn get() -> impl Iterator<Item = String> {
+    get_cow().map(String::from)
+}
+
+/// The locales are compiled into the crate, so they're all borrowed.
+pub(crate) fn get_cow() -> impl Iterator<Item = Cow<'static, str>> {
+    option_env!("SYS_LOCALE_TEST_STUB")
+        .unwrap_or(DEFAULT_LOCALES)
+        .split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(Cow::Borrowed)
+}
+
+pub(crate) fn get_with_source() -> (impl Iterator<Item = String>, LocaleSource) {
+    (get(), LocaleSource::System)
+}
+
+pub(crate) fn get_raw() -> impl Iterator<Item = String> {
+    get()
+}
+
+pub(crate) fn try_get() -> Result<impl Iterator<Item = String>, LocaleError> {
+    Ok(get())
+}
+
+/// The variable is read when the crate is compiled, so changing it at runtime has no effect.
+pub(crate) fn describe() -> ProviderInfo {
+    const VARIABLE: &str = "SYS_LOCALE_TEST_STUB";
+    const DEFAULT: &str = "default en-US";
+
+    ProviderInfo {
+        name: "stub",
+        sources: vec![VARIABLE, DEFAULT],
+        active_source: Some(if option_env!("SYS_LOCALE_TEST_STUB").is_some() {
+            VARIABLE
+        } else {
+            DEFAULT
+        }),
+    }
+}
+
+pub(crate) fn get_region() -> Option<String> {
+    get().next().and_then(|tag| crate::region_from_tag(&tag))
+}
+
+pub(crate) fn get_format_locale() -> Option<String> {
+    get().next()
+}
+
+pub(crate) fn get_default_locale() -> Option<String> {
+    get().next()
+}
+
+pub(crate) fn get_collation_locale() -> Option<String> {
+    get().next()
+}
+
+pub(crate) fn get_locale_for(_category: LocaleCategory) -> Option<String> {
+    get().next()
+}
+
+pub(crate) fn get_all_categories() -> Vec<(LocaleCategory, Option<String>)> {
+    LocaleCategory::ALL
+        .iter()
+        .map(|&category| (category, get_locale_for(category)))
+        .collect()
+}
+
+pub(crate) fn get_measurement_system() -> Option<MeasurementSystem> {
+    get_region().map(|region| MeasurementSystem::for_region(&region))
+}
+
+pub(crate) fn get_paper_size() -> Option<PaperSize> {
+    get_region().map(|region| PaperSize::for_region(&region))
+}
+
+pub(crate) fn get_number_separators() -> Option<NumberSeparators> {
+    None
+}
+
+pub(crate) fn get_grouping_sizes() -> Option<Vec<u8>> {
+    None
+}
+
+pub(crate) fn get_list_separator() -> Option<char> {
+    None
+}
+
+pub(crate) fn get_datetime_symbols() -> Option<DateTimeSymbols> {
+    None
+}
+
+pub(crate) fn get_first_day_of_week() -> Option<Weekday> {
+    None
+}
+
+pub(crate) fn get_weekend_days() -> Option<(Weekday, Weekday)> {
+    get_region().map(|region| Weekday::weekend_for_region(&region))
+}
+
+pub(crate) fn uses_24_hour_time() -> Option<bool> {
+    None
+}
+
+pub(crate) fn get_date_field_order() -> Option<DateOrder> {
+    None
+}
+
+pub(crate) fn get_calendar() -> Option<Calendar> {
+    get().next().and_then(|tag| crate::calendar_from_tag(&tag))
+}
+
+pub(crate) fn get_numbering_system() -> Option<String> {
+    get()
+        .next()
+        .and_then(|tag| crate::numbering_system_from_tag(&tag))
+}
+
+pub(crate) fn get_locale_settings() -> LocaleSettings {
+    LocaleSettings {
+        region: get_region(),
+        measurement_system: get_measurement_system(),
+        currency_code: get_currency_code(),
+        first_day_of_week: get_first_day_of_week(),
+        uses_24_hour_time: uses_24_hour_time(),
+        number_separators: get_number_separators(),
+        ..LocaleSettings::default()
+    }
+}
+
+pub(crate) fn get_currency_code() -> Option<String> {
+    None
+}
+
+pub(crate) fn get_currency_format() -> Option<CurrencyFormat> {
+    None
+}
+
+pub(crate) fn get_percent_symbol() -> Option<PercentSymbol> {
+    None
+}
+
+pub(crate) fn get_sign_symbols() -> Option<SignSymbols> {
+    None
+}
+
+/// The time zone can't be derived from the stubbed locales.
+#[cfg(feature = "time-zone")]
+pub(crate) fn get_time_zone() -> Option<String> {
+    None
+}
+
+pub(crate) fn get_locale_display_name() -> Option<String> {
+    None
+}
+
+pub(crate) fn display_name_of(_tag: &str, _in_locale: Option<&str>) -> Option<String> {
+    None
+}
+
+pub(crate) fn native_name_of(_tag: &str) -> Option<String> {
+    None
+}
+
+/// Only the stubbed locales are available.
+pub(crate) fn is_locale_available(tag: &str) -> Option<bool> {
+    Some(crate::is_tag_available(tag, get()))
+}
+
+pub(crate) fn get_available_locales() -> Vec<String> {
+    get().collect()
+}
+
+/// The stubbed locales never change.
+#[cfg(feature = "notify")]
+pub(crate) enum Watcher {}
+
+#[cfg(feature = "notify")]
+pub(crate) fn watch(_callback: alloc::boxed::Box<dyn Fn() + Send + Sync>) -> Option<Watcher> {
+    None
+}
@@ -0,0 +1,32 @@
+//! The locales which an application declares that it supports when it's built.
+
+/// The locales which an application supports, such as those it has translations for, which the
+/// user's preferences are matched against by [`get_supported_locales`](crate::get_supported_locales).
+///
+/// These are a `'static` slice, so they can be baked into the binary as a constant.
+///
+/// # Example
+///
+/// ```
+/// use sys_locale::SupportedLocales;
+///
+/// const SUPPORTED: SupportedLocales = SupportedLocales::new(&["en", "de", "pt-BR"]);
+///
+/// assert_eq!(SUPPORTED.tags(), ["en", "de", "pt-BR"]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SupportedLocales {
+    tags: &'static [&'static str],
+}
+
+impl SupportedLocales {
+    /// Declares the supported locales, which are BCP-47 language tags such as `en` or `pt-BR`.
+    pub const fn new(tags: &'static [&'static str]) -> SupportedLocales {
+        SupportedLocales { tags }
+    }
+
+    /// Returns the supported locales, in the order in which they were declared.
+    pub fn tags(&self) -> &'static [&'static str] {
+        self.tags
+    }
+}
@@ -1,9 +1,36 @@
 #![allow(unknown_lints)]
-use std::{env, ffi::OsStr};
+use crate::posix::parse_locale_code;
+use crate::{
+    Calendar, CurrencyFormat, DateOrder, DateTimeSymbols, LocaleCategory, LocaleError,
+    LocaleSettings, LocaleSource, MeasurementSystem, NumberSeparators, PaperSize, PercentSymbol,
+    ProviderInfo, SignSymbols, Weekday,
+};
+use std::{
+    borrow::Cow,
+    env,
+    ffi::{OsStr, OsString},
+};
+
+#[cfg(feature = "desktop")]
+mod desktop;
+#[cfg(all(target_os = "linux", any(target_env = "gnu", target_env = "musl")))]
+mod langinfo;
+#[cfg(feature = "time-zone")]
+mod time_zone;
+#[cfg(feature = "time-zone")]
+pub(crate) use time_zone::get_time_zone;
+mod user;
+pub(crate) use user::get_locales_for_user;
 
 const LC_ALL: &str = "LC_ALL";
-const LC_CTYPE: &str = "LC_CTYPE";
+/// The paper size, which glibc supports in addition to the categories of POSIX.
+const LC_PAPER: &str = "LC_PAPER";
+/// The description of the locale itself, which glibc supports in addition to the categories
+/// of POSIX.
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+const LC_IDENTIFICATION: &str = "LC_IDENTIFICATION";
 const LANG: &str = "LANG";
+const LANGUAGE: &str = "LANGUAGE";
 
 /// Environment variable access abstraction to allow testing without
 /// mutating env variables.
@@ -12,40 +39,809 @@ const LANG: &str = "LANG";
 trait EnvAccess {
     /// See also [std::env::var]
     fn get(&self, key: impl AsRef<OsStr>) -> Option<String>;
+
+    /// Returns the locale which the process has already picked for a category, such as
+    /// `LC_MESSAGES`, with `setlocale`, which takes precedence over the variables.
+    ///
+    /// Only [StdEnv] reads it, with the `live-c-locale` feature.
+    fn live_code(&self, _category: &str) -> Option<String> {
+        None
+    }
+}
+
+/// The files which contain the system's default locale, in order of precedence: the first is
+/// used by systemd, and the second by Debian and its derivatives.
+const LOCALE_CONF_PATHS: [&str; 2] = ["/etc/locale.conf", "/etc/default/locale"];
+
+/// Proxy to [std::env], or to the system's default locale configuration when the environment
+/// doesn't configure the locale at all, such as for a daemon started with an empty environment.
+///
+/// With the `no-env` feature, the environment is never read, and only the system's configuration
+/// is used.
+enum StdEnv {
+    Process,
+    /// A configuration file, which is one of [`LOCALE_CONF_PATHS`] unless none of them could
+    /// be read.
+    Config(Option<&'static str>, LocaleConf),
+    /// The settings of the desktop environment, with the `desktop` feature, which take
+    /// precedence over the variables they don't configure.
+    #[cfg(feature = "desktop")]
+    Desktop(LocaleConf, Box<StdEnv>),
+}
+
+impl StdEnv {
+    fn new() -> StdEnv {
+        // The desktop is found through the environment, so it isn't used with `no-env`.
+        #[cfg(feature = "desktop")]
+        if !cfg!(feature = "no-env") {
+            if let Some(settings) = desktop::settings() {
+                return StdEnv::Desktop(settings, Box::new(StdEnv::from_environment()));
+            }
+        }
+        StdEnv::from_environment()
+    }
+
+    fn from_environment() -> StdEnv {
+        if cfg!(feature = "no-env") {
+            return match StdEnv::system_conf() {
+                Some((path, conf)) => StdEnv::Config(Some(path), conf),
+                None => StdEnv::Config(None, LocaleConf(Vec::new())),
+            };
+        }
+
+        if configures_locale(env::vars_os()) {
+            return StdEnv::Process;
+        }
+
+        StdEnv::system_conf().map_or(StdEnv::Process, |(path, conf)| {
+            StdEnv::Config(Some(path), conf)
+        })
+    }
+
+    /// Reads the first of [`LOCALE_CONF_PATHS`] which configures the locale, along with its path.
+    fn system_conf() -> Option<(&'static str, LocaleConf)> {
+        LOCALE_CONF_PATHS
+            .iter()
+            .filter_map(|&path| Some((path, std::fs::read_to_string(path).ok()?)))
+            .map(|(path, contents)| (path, LocaleConf::parse(&contents)))
+            .find(|(_, conf)| !conf.0.is_empty())
+    }
+
+    /// Returns where a variable is read from, which is its own name in the environment.
+    fn source_of(&self, variable: &'static str) -> Option<&'static str> {
+        match self {
+            StdEnv::Process => Some(variable),
+            StdEnv::Config(path, _) => *path,
+            #[cfg(feature = "desktop")]
+            StdEnv::Desktop(settings, fallback) => {
+                if settings.get(variable).is_some() {
+                    Some(desktop::SOURCE)
+                } else {
+                    fallback.source_of(variable)
+                }
+            }
+        }
+    }
+}
+
+/// Checks whether any of the environment variables configure the locale, ignoring those which are
+/// set to an empty value, since they're treated as if they were unset.
+fn configures_locale(mut vars: impl Iterator<Item = (OsString, OsString)>) -> bool {
+    vars.any(|(key, value)| match key.to_str() {
+        Some(key) if !value.is_empty() => key == LANG || key == LANGUAGE || key.starts_with("LC_"),
+        _ => false,
+    })
 }
 
-/// Proxy to [std::env]
-struct StdEnv;
 impl EnvAccess for StdEnv {
     fn get(&self, key: impl AsRef<OsStr>) -> Option<String> {
-        env::var(key).ok()
+        match self {
+            StdEnv::Process => env::var(key).ok(),
+            StdEnv::Config(_, conf) => conf.get(key),
+            #[cfg(feature = "desktop")]
+            StdEnv::Desktop(settings, fallback) => settings.get(&key).or_else(|| fallback.get(key)),
+        }
+    }
+
+    /// The process's own locale usually comes from the environment too, so it isn't used with
+    /// `no-env`. Until the process calls `setlocale`, it's the `C` locale, which is ignored.
+    #[cfg(all(
+        feature = "live-c-locale",
+        not(feature = "no-env"),
+        target_os = "linux"
+    ))]
+    fn live_code(&self, category: &str) -> Option<String> {
+        if category != LocaleCategory::Messages.env_var() {
+            return None;
+        }
+        query_c_locale(libc::LC_MESSAGES).filter(|code| !is_posix_locale(code))
+    }
+}
+
+/// Returns the name of the C locale which the process has set for a category, without
+/// changing it.
+#[cfg(all(
+    feature = "live-c-locale",
+    not(feature = "no-env"),
+    target_os = "linux"
+))]
+fn query_c_locale(category: libc::c_int) -> Option<String> {
+    // SAFETY: Passing a null locale only queries the category's locale. The returned string is
+    // null-terminated, and is copied straight away, before this thread could call `setlocale`
+    // again and replace it. Like any use of `setlocale`, this isn't safe against another thread
+    // changing the locale at the same time.
+    unsafe {
+        let name = libc::setlocale(category, core::ptr::null());
+        if name.is_null() {
+            return None;
+        }
+        std::ffi::CStr::from_ptr(name)
+            .to_str()
+            .ok()
+            .map(String::from)
+    }
+}
+
+/// The variables of a locale configuration file, such as `/etc/locale.conf`.
+///
+/// These contain `KEY=value` lines, where the value may be quoted and lines starting with
+/// `#` are comments.
+struct LocaleConf(Vec<(String, String)>);
+
+impl LocaleConf {
+    fn parse(contents: &str) -> LocaleConf {
+        let variables = contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut parts = line.splitn(2, '=');
+                let key = parts.next()?.trim();
+                let value = unquote(parts.next()?.trim());
+                if key.is_empty() || value.is_empty() {
+                    None
+                } else {
+                    Some((String::from(key), String::from(value)))
+                }
+            })
+            .collect();
+        LocaleConf(variables)
+    }
+}
+
+impl EnvAccess for LocaleConf {
+    fn get(&self, key: impl AsRef<OsStr>) -> Option<String> {
+        // Like a shell script, a later assignment replaces an earlier one.
+        self.0
+            .iter()
+            .rev()
+            .find(|(name, _)| OsStr::new(name) == key.as_ref())
+            .map(|(_, value)| value.clone())
     }
 }
 
+/// Removes a pair of matching single or double quotes around a value.
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''].iter() {
+        if value.len() >= 2 && value.starts_with(*quote) && value.ends_with(*quote) {
+            return &value[1..value.len() - 1];
+        }
+    }
+    value
+}
+
+pub(crate) const SUPPORTED: bool = true;
+
 pub(crate) fn get() -> impl Iterator<Item = String> {
-    _get(&StdEnv).into_iter()
+    get_with_source().0
+}
+
+/// The locales are converted from the POSIX format, so none can be borrowed.
+pub(crate) fn get_cow() -> impl Iterator<Item = Cow<'static, str>> {
+    get().map(Cow::Owned)
+}
+
+pub(crate) fn get_with_source() -> (impl Iterator<Item = String>, LocaleSource) {
+    let env = StdEnv::new();
+    let source = match env {
+        StdEnv::Process => LocaleSource::Environment,
+        StdEnv::Config(..) => LocaleSource::System,
+        #[cfg(feature = "desktop")]
+        StdEnv::Desktop(..) => LocaleSource::System,
+    };
+
+    #[cfg(any(feature = "log", feature = "tracing"))]
+    for name in MESSAGE_VARIABLES.iter() {
+        debug!("Read {}={:?} from {:?}", name, env.get(name), source);
+    }
+    (_get_locales(&env).into_iter(), source)
+}
+
+pub(crate) fn get_raw() -> impl Iterator<Item = String> {
+    _get_raw_locales(&StdEnv::new()).into_iter()
+}
+
+pub(crate) fn describe() -> ProviderInfo {
+    let env = StdEnv::new();
+    let active_source = if env.live_code(LocaleCategory::Messages.env_var()).is_some() {
+        Some(SETLOCALE_SOURCE)
+    } else {
+        message_variable(&env).and_then(|variable| env.source_of(variable))
+    };
+
+    let mut sources = Vec::new();
+    if cfg!(all(
+        feature = "live-c-locale",
+        not(feature = "no-env"),
+        target_os = "linux"
+    )) {
+        sources.push(SETLOCALE_SOURCE);
+    }
+    #[cfg(feature = "desktop")]
+    if !cfg!(feature = "no-env") {
+        sources.push(desktop::SOURCE);
+    }
+    if !cfg!(feature = "no-env") {
+        sources.extend(MESSAGE_VARIABLES.iter());
+    }
+    sources.extend(LOCALE_CONF_PATHS.iter());
+
+    ProviderInfo {
+        name: "unix",
+        sources,
+        active_source,
+    }
+}
+
+/// The source of the locale which the process has set for `LC_MESSAGES`, with the
+/// `live-c-locale` feature.
+const SETLOCALE_SOURCE: &str = "setlocale(LC_MESSAGES)";
+
+/// The variables which the locales for messages are read from, in order of precedence.
+const MESSAGE_VARIABLES: [&str; 4] = [LANGUAGE, LC_ALL, "LC_MESSAGES", LANG];
+
+/// Returns the variable which the first of the [`message_codes`] comes from.
+fn message_variable(env: &impl EnvAccess) -> Option<&'static str> {
+    let messages = MESSAGE_VARIABLES[1..]
+        .iter()
+        .copied()
+        .find(|variable| matches!(env.get(variable), Some(code) if !code.is_empty()));
+    let disables_language = match messages.and_then(|variable| env.get(variable)) {
+        Some(code) => is_posix_locale(&code),
+        None => false,
+    };
+    let has_language = match env.get(LANGUAGE) {
+        Some(language) => language.split(':').any(|code| !code.is_empty()),
+        None => false,
+    };
+
+    if has_language && !disables_language {
+        Some(LANGUAGE)
+    } else {
+        messages
+    }
+}
+
+/// Reading environment variables can't fail, so the locales are only ever missing.
+pub(crate) fn try_get() -> Result<impl Iterator<Item = String>, LocaleError> {
+    Ok(get())
+}
+
+fn _get_locales(env: &impl EnvAccess) -> Vec<String> {
+    let mut locales = Vec::new();
+    for tag in message_codes(env)
+        .iter()
+        .filter_map(|code| parse_locale_code(code))
+    {
+        if !locales.contains(&tag) {
+            locales.push(tag);
+        }
+    }
+    locales
+}
+
+/// Returns the same POSIX locale names as [`_get_locales`], before they're converted
+/// into language tags.
+fn _get_raw_locales(env: &impl EnvAccess) -> Vec<String> {
+    message_codes(env)
 }
 
 fn _get(env: &impl EnvAccess) -> Option<String> {
-    let code = env
-        .get(LC_ALL)
-        .or_else(|| env.get(LC_CTYPE))
-        .or_else(|| env.get(LANG))?;
+    get_category(env, LocaleCategory::Messages.env_var())
+}
+
+/// Returns the locale for a single category, such as `LC_NUMERIC`, from [`category_code`].
+fn get_category(env: &impl EnvAccess, category: &str) -> Option<String> {
+    parse_locale_code(&category_code(env, category)?)
+}
+
+/// Returns the POSIX locale name for a single category, such as `de_DE.UTF-8`,
+/// before it's converted into a language tag.
+///
+/// As specified by POSIX, this is the value of the first of these which is set:
+/// 1. `LC_ALL`, which overrides every category.
+/// 2. The variable of the category itself, such as `LC_NUMERIC`.
+/// 3. `LANG`, which is the default for every category.
+///
+/// A variable which is set to an empty value is treated as if it were unset. With the
+/// `live-c-locale` feature, the locale which the process has already set for `LC_MESSAGES` with
+/// `setlocale` takes precedence over all of them.
+fn category_code(env: &impl EnvAccess, category: &str) -> Option<String> {
+    if let Some(code) = env.live_code(category) {
+        return Some(code);
+    }
+
+    [LC_ALL, category, LANG]
+        .iter()
+        .filter_map(|key| env.get(key))
+        .find(|code| !code.is_empty())
+}
+
+/// Returns the POSIX locale names used for translating messages, in order of preference.
+///
+/// This is the `LC_MESSAGES` locale from [`category_code`], preceded by the entries of
+/// `LANGUAGE`. That's a GNU extension with a colon-separated list of fallback languages,
+/// such as `de:en:fr`, which only applies to messages, so it never affects any other category.
+///
+/// Like GNU gettext, `LANGUAGE` is ignored when the `LC_MESSAGES` locale is `C` or `POSIX`, so
+/// that `LC_ALL=C` disables translations even when `LANGUAGE` is set. It's still used when no
+/// locale is set at all.
+fn message_codes(env: &impl EnvAccess) -> Vec<String> {
+    let messages = category_code(env, LocaleCategory::Messages.env_var());
+    if let Some(messages) = &messages {
+        if is_posix_locale(messages) {
+            return vec![messages.clone()];
+        }
+    }
+
+    env.get(LANGUAGE)
+        .unwrap_or_default()
+        .split(':')
+        .filter(|code| !code.is_empty())
+        .map(String::from)
+        .chain(messages)
+        .collect()
+}
+
+/// Returns `true` for the `C` and `POSIX` locales, which don't have a language, including with
+/// a codeset such as `C.UTF-8`.
+fn is_posix_locale(code: &str) -> bool {
+    let name = code.split(&['.', '@'][..]).next().unwrap_or(code);
+    name == "C" || name == "POSIX"
+}
+
+pub(crate) fn get_region() -> Option<String> {
+    _get_region(&StdEnv::new())
+}
+
+pub(crate) fn get_collation_locale() -> Option<String> {
+    _get_collation_locale(&StdEnv::new())
+}
+
+fn _get_collation_locale(env: &impl EnvAccess) -> Option<String> {
+    _get_locale_for(env, LocaleCategory::Collate)
+}
+
+pub(crate) fn get_locale_for(category: LocaleCategory) -> Option<String> {
+    _get_locale_for(&StdEnv::new(), category)
+}
+
+fn _get_locale_for(env: &impl EnvAccess, category: LocaleCategory) -> Option<String> {
+    get_category(env, category.env_var())
+}
+
+/// The environment is only read once, rather than once for every category.
+pub(crate) fn get_all_categories() -> Vec<(LocaleCategory, Option<String>)> {
+    _get_all_categories(&StdEnv::new())
+}
+
+fn _get_all_categories(env: &impl EnvAccess) -> Vec<(LocaleCategory, Option<String>)> {
+    LocaleCategory::ALL
+        .iter()
+        .map(|&category| (category, _get_locale_for(env, category)))
+        .collect()
+}
+
+fn _get_region(env: &impl EnvAccess) -> Option<String> {
+    // The region is most closely tied to monetary formatting, which can be
+    // configured separately from the language with `LC_MONETARY`.
+    _get_locale_for(env, LocaleCategory::Monetary).and_then(|tag| crate::region_from_tag(&tag))
+}
+
+pub(crate) fn get_format_locale() -> Option<String> {
+    _get_format_locale(&StdEnv::new())
+}
+
+pub(crate) fn get_default_locale() -> Option<String> {
+    _get_default_locale(&StdEnv::new())
+}
+
+/// The default is the locale of `LANG`, which applies to every category which isn't set.
+fn _get_default_locale(env: &impl EnvAccess) -> Option<String> {
+    env.get(LANG)
+        .filter(|code| !code.is_empty())
+        .and_then(|code| parse_locale_code(&code))
+}
+
+fn _get_format_locale(env: &impl EnvAccess) -> Option<String> {
+    _get_locale_for(env, LocaleCategory::Numeric)
+}
+
+pub(crate) fn get_number_separators() -> Option<NumberSeparators> {
+    _get_number_separators(&StdEnv::new())
+}
+
+#[cfg(all(target_os = "linux", any(target_env = "gnu", target_env = "musl")))]
+fn _get_number_separators(env: &impl EnvAccess) -> Option<NumberSeparators> {
+    let data = langinfo::LocaleData::new(
+        libc::LC_NUMERIC_MASK,
+        &category_code(env, LocaleCategory::Numeric.env_var())?,
+    )?;
+
+    NumberSeparators::from_strings(&data.item(libc::RADIXCHAR)?, &data.item(libc::THOUSEP)?)
+}
+
+#[cfg(not(all(target_os = "linux", any(target_env = "gnu", target_env = "musl"))))]
+fn _get_number_separators(_env: &impl EnvAccess) -> Option<NumberSeparators> {
+    None
+}
+
+/// POSIX locales don't define a list separator, so it's derived from the decimal separator.
+pub(crate) fn get_list_separator() -> Option<char> {
+    get_number_separators().map(|separators| separators.list_separator())
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+pub(crate) fn get_grouping_sizes() -> Option<Vec<u8>> {
+    let data = langinfo::LocaleData::new(
+        libc::LC_NUMERIC_MASK,
+        &category_code(&StdEnv::new(), LocaleCategory::Numeric.env_var())?,
+    )?;
+
+    Some(crate::settings::grouping_sizes(
+        data.bytes(langinfo::GROUPING)?,
+    ))
+}
+
+#[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+pub(crate) fn get_grouping_sizes() -> Option<Vec<u8>> {
+    None
+}
+
+pub(crate) fn get_first_day_of_week() -> Option<Weekday> {
+    _get_first_day_of_week(&StdEnv::new())
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn _get_first_day_of_week(env: &impl EnvAccess) -> Option<Weekday> {
+    let data = langinfo::LocaleData::new(
+        libc::LC_TIME_MASK,
+        &category_code(env, LocaleCategory::Time.env_var())?,
+    )?;
+
+    first_day_of_week(
+        data.word(langinfo::_NL_TIME_WEEK_1STDAY)?,
+        data.byte(langinfo::_NL_TIME_FIRST_WEEKDAY)?,
+    )
+}
+
+#[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+fn _get_first_day_of_week(_env: &impl EnvAccess) -> Option<Weekday> {
+    None
+}
+
+pub(crate) fn get_weekend_days() -> Option<(Weekday, Weekday)> {
+    _get_weekend_days(&StdEnv::new())
+}
+
+fn _get_weekend_days(env: &impl EnvAccess) -> Option<(Weekday, Weekday)> {
+    // glibc doesn't describe the weekend, so it's inferred from the region of the calendar.
+    _get_locale_for(env, LocaleCategory::Time)
+        .and_then(|tag| crate::region_from_tag(&tag))
+        .map(|region| Weekday::weekend_for_region(&region))
+}
+
+pub(crate) fn get_calendar() -> Option<Calendar> {
+    _get_calendar(&StdEnv::new())
+}
+
+fn _get_calendar(env: &impl EnvAccess) -> Option<Calendar> {
+    // glibc only uses the Gregorian calendar, but ICU understands the calendar in a modifier,
+    // such as `th_TH.UTF-8@calendar=buddhist`.
+    let code = category_code(env, LocaleCategory::Time.env_var())?;
+    match modifier_keyword(&code, "calendar") {
+        Some(calendar) => Calendar::from_identifier(calendar),
+        None => _get_locale_for(env, LocaleCategory::Time)
+            .and_then(|tag| crate::calendar_from_tag(&tag)),
+    }
+}
+
+pub(crate) fn get_numbering_system() -> Option<String> {
+    _get_numbering_system(&StdEnv::new())
+}
+
+fn _get_numbering_system(env: &impl EnvAccess) -> Option<String> {
+    // As with the calendar, ICU understands the numbering system in a modifier, such as
+    // `ar_EG.UTF-8@numbers=latn`.
+    let code = category_code(env, LocaleCategory::Numeric.env_var())?;
+    match modifier_keyword(&code, "numbers") {
+        Some(numbers) => Some(numbers.to_ascii_lowercase()),
+        None => _get_locale_for(env, LocaleCategory::Numeric)
+            .and_then(|tag| crate::numbering_system_from_tag(&tag)),
+    }
+}
+
+/// Returns the value of an ICU keyword in the modifier of a locale code, such as `buddhist` for
+/// `calendar` in `th_TH.UTF-8@calendar=buddhist`.
+fn modifier_keyword<'a>(code: &'a str, key: &str) -> Option<&'a str> {
+    let at = code.find('@')?;
+    code[at + 1..]
+        .split(';')
+        .find_map(|keyword| keyword.strip_prefix(key)?.strip_prefix('='))
+}
+
+/// Converts glibc's `week` and `first_weekday` values of `LC_TIME` into a weekday.
+///
+/// `week_1stday` is a date in the form `YYYYMMDD`, which is the first day of the list of
+/// weekday names. Most locales use `19971130`, which is a Sunday. `first_weekday` counts
+/// the days from that date, starting at 1 for the date itself.
+#[cfg(any(test, all(target_os = "linux", target_env = "gnu")))]
+fn first_day_of_week(week_1stday: u32, first_weekday: u8) -> Option<Weekday> {
+    let (year, month, day) = (
+        week_1stday / 10000,
+        week_1stday / 100 % 100,
+        week_1stday % 100,
+    );
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || !(1..=7).contains(&first_weekday) {
+        return None;
+    }
+
+    // Sakamoto's method, which gives the days since Sunday.
+    const MONTH_OFFSETS: [u32; 12] = [0, 3, 2, 5, 0, 3, 5, 1, 4, 6, 2, 4];
+    let year = if month < 3 {
+        year.checked_sub(1)?
+    } else {
+        year
+    };
+    let since_sunday =
+        (year + year / 4 - year / 100 + year / 400 + MONTH_OFFSETS[month as usize - 1] + day) % 7;
+
+    Some(Weekday::from_monday_offset(
+        since_sunday + 6 + u32::from(first_weekday) - 1,
+    ))
+}
+
+pub(crate) fn get_currency_code() -> Option<String> {
+    _get_currency_code(&StdEnv::new())
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn _get_currency_code(env: &impl EnvAccess) -> Option<String> {
+    let data = langinfo::LocaleData::new(
+        libc::LC_MONETARY_MASK,
+        &category_code(env, LocaleCategory::Monetary.env_var())?,
+    )?;
+
+    crate::currency_code(&data.item(langinfo::__INT_CURR_SYMBOL)?)
+}
+
+#[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+fn _get_currency_code(_env: &impl EnvAccess) -> Option<String> {
+    None
+}
+
+pub(crate) fn get_currency_format() -> Option<CurrencyFormat> {
+    None
+}
+
+/// Neither POSIX nor glibc define the percent sign in `LC_NUMERIC`.
+pub(crate) fn get_percent_symbol() -> Option<PercentSymbol> {
+    None
+}
+
+pub(crate) fn get_sign_symbols() -> Option<SignSymbols> {
+    _get_sign_symbols(&StdEnv::new())
+}
+
+/// glibc only defines the signs in `LC_MONETARY`, so they're read from that category of the
+/// `LC_NUMERIC` locale.
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn _get_sign_symbols(env: &impl EnvAccess) -> Option<SignSymbols> {
+    let data = langinfo::LocaleData::new(
+        libc::LC_MONETARY_MASK,
+        &category_code(env, LocaleCategory::Numeric.env_var())?,
+    )?;
+    SignSymbols::from_strings(
+        &data.item(langinfo::__POSITIVE_SIGN).unwrap_or_default(),
+        &data.item(langinfo::__NEGATIVE_SIGN)?,
+    )
+}
+
+#[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+fn _get_sign_symbols(_env: &impl EnvAccess) -> Option<SignSymbols> {
+    None
+}
+
+pub(crate) fn display_name_of(_tag: &str, _in_locale: Option<&str>) -> Option<String> {
+    None
+}
+
+pub(crate) fn native_name_of(_tag: &str) -> Option<String> {
+    None
+}
+
+pub(crate) fn is_locale_available(tag: &str) -> Option<bool> {
+    let available = available_locales()?;
+    Some(crate::is_tag_available(tag, available.into_iter()))
+}
+
+pub(crate) fn get_available_locales() -> Vec<String> {
+    available_locales().unwrap_or_default()
+}
+
+/// Lists the installed locales with `locale -a`, or returns `None` if it can't be run.
+fn available_locales() -> Option<Vec<String>> {
+    let output = std::process::Command::new("locale")
+        .arg("-a")
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let output = String::from_utf8_lossy(&output.stdout);
+    Some(installed_locales(&output).collect())
+}
+
+/// Parses the locales printed by `locale -a`, such as `en_US.utf8`, skipping `C` and `POSIX`.
+fn installed_locales(output: &str) -> impl Iterator<Item = String> + '_ {
+    output
+        .lines()
+        .filter_map(|line| parse_locale_code(line.trim()))
+}
+
+pub(crate) fn get_locale_display_name() -> Option<String> {
+    _get_locale_display_name(&StdEnv::new())
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn _get_locale_display_name(env: &impl EnvAccess) -> Option<String> {
+    let data = langinfo::LocaleData::new(
+        libc::LC_IDENTIFICATION_MASK,
+        &category_code(env, LC_IDENTIFICATION)?,
+    )?;
+
+    display_name(
+        data.item(langinfo::_NL_IDENTIFICATION_TITLE),
+        data.item(langinfo::_NL_IDENTIFICATION_LANGUAGE),
+    )
+}
+
+#[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+fn _get_locale_display_name(_env: &impl EnvAccess) -> Option<String> {
+    None
+}
+
+/// Picks the name of a locale from its `LC_IDENTIFICATION` data, which is its title if it has
+/// one, or otherwise the name of its language.
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn display_name(title: Option<String>, language: Option<String>) -> Option<String> {
+    title
+        .into_iter()
+        .chain(language)
+        .map(|name| String::from(name.trim()))
+        .find(|name| !name.is_empty())
+}
+
+pub(crate) fn get_datetime_symbols() -> Option<DateTimeSymbols> {
+    None
+}
+
+pub(crate) fn uses_24_hour_time() -> Option<bool> {
+    _uses_24_hour_time(&StdEnv::new())
+}
+
+#[cfg(all(target_os = "linux", any(target_env = "gnu", target_env = "musl")))]
+fn _uses_24_hour_time(env: &impl EnvAccess) -> Option<bool> {
+    let data = langinfo::LocaleData::new(
+        libc::LC_TIME_MASK,
+        &category_code(env, LocaleCategory::Time.env_var())?,
+    )?;
+
+    crate::settings::strftime_uses_24_hour_time(&data.item(libc::T_FMT)?)
+}
+
+#[cfg(not(all(target_os = "linux", any(target_env = "gnu", target_env = "musl"))))]
+fn _uses_24_hour_time(_env: &impl EnvAccess) -> Option<bool> {
+    None
+}
+
+#[cfg(all(target_os = "linux", any(target_env = "gnu", target_env = "musl")))]
+pub(crate) fn get_date_field_order() -> Option<DateOrder> {
+    let data = langinfo::LocaleData::new(
+        libc::LC_TIME_MASK,
+        &category_code(&StdEnv::new(), LocaleCategory::Time.env_var())?,
+    )?;
+
+    crate::settings::strftime_date_order(&data.item(libc::D_FMT)?)
+}
+
+#[cfg(not(all(target_os = "linux", any(target_env = "gnu", target_env = "musl"))))]
+pub(crate) fn get_date_field_order() -> Option<DateOrder> {
+    None
+}
+
+pub(crate) fn get_locale_settings() -> LocaleSettings {
+    _get_locale_settings(&StdEnv::new())
+}
+
+fn _get_locale_settings(env: &impl EnvAccess) -> LocaleSettings {
+    LocaleSettings {
+        region: _get_region(env),
+        measurement_system: _get_measurement_system(env),
+        currency_code: _get_currency_code(env),
+        first_day_of_week: _get_first_day_of_week(env),
+        uses_24_hour_time: _uses_24_hour_time(env),
+        number_separators: _get_number_separators(env),
+        ..LocaleSettings::default()
+    }
+}
+
+pub(crate) fn get_measurement_system() -> Option<MeasurementSystem> {
+    _get_measurement_system(&StdEnv::new())
+}
+
+fn _get_measurement_system(env: &impl EnvAccess) -> Option<MeasurementSystem> {
+    _get_locale_for(env, LocaleCategory::Measurement)
+        .and_then(|tag| crate::region_from_tag(&tag))
+        .map(|region| MeasurementSystem::for_region(&region))
+}
+
+pub(crate) fn get_paper_size() -> Option<PaperSize> {
+    let env = StdEnv::new();
+    paper_size_from_langinfo(&env).or_else(|| _get_paper_size(&env))
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn paper_size_from_langinfo(env: &impl EnvAccess) -> Option<PaperSize> {
+    let data = langinfo::LocaleData::new(libc::LC_PAPER_MASK, &category_code(env, LC_PAPER)?)?;
 
-    parse_locale_code(&code)
+    PaperSize::from_millimeters(
+        data.word(langinfo::_NL_PAPER_WIDTH)?,
+        data.word(langinfo::_NL_PAPER_HEIGHT)?,
+    )
 }
 
-fn parse_locale_code(code: &str) -> Option<String> {
-    // Some locales are returned with the char encoding too: `en_US.UTF-8`
-    // TODO: Once we bump MSRV >= 1.52, remove this allow and clean up
-    #[allow(clippy::manual_split_once)]
-    #[allow(clippy::needless_splitn)]
-    code.splitn(2, '.').next().map(|s| s.replace('_', "-"))
+#[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+fn paper_size_from_langinfo(_env: &impl EnvAccess) -> Option<PaperSize> {
+    None
+}
+
+/// Infers the paper size from the region of the `LC_PAPER` locale, for when its data can't
+/// be read.
+fn _get_paper_size(env: &impl EnvAccess) -> Option<PaperSize> {
+    get_category(env, LC_PAPER)
+        .and_then(|tag| crate::region_from_tag(&tag))
+        .map(|region| PaperSize::for_region(&region))
+}
+
+/// Locale changes can't be watched on this platform.
+#[cfg(feature = "notify")]
+pub(crate) enum Watcher {}
+
+#[cfg(feature = "notify")]
+pub(crate) fn watch(_callback: Box<dyn Fn() + Send + Sync>) -> Option<Watcher> {
+    None
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{parse_locale_code, EnvAccess, _get, LANG, LC_ALL, LC_CTYPE};
+    use super::{
+        _get, _get_calendar, _get_collation_locale, _get_format_locale, _get_locale_for,
+        _get_locales, _get_measurement_system, _get_raw_locales, _get_region, _get_weekend_days,
+        first_day_of_week, EnvAccess, LANG, LANGUAGE, LC_ALL,
+    };
+    use crate::{Calendar, LocaleCategory, LocaleSettings, MeasurementSystem, Weekday};
     use std::{
         collections::HashMap,
         ffi::{OsStr, OsString},
@@ -58,44 +854,647 @@ mod tests {
         }
     }
 
-    const PARSE_LOCALE: &str = "fr-FR";
-    const LANG_PARSE_LOCALE: &str = "fr_FR";
+    #[test]
+    fn env_priority() {
+        let mut env = MockEnv::new();
+        assert_eq!(_get(&env), None);
+
+        // These locale names are technically allowed and some systems may still
+        // defined aliases such as these but the glibc sources mention that this
+        // should be considered deprecated
+
+        env.insert(LANG.into(), "invalid".to_owned());
+        assert_eq!(_get(&env).as_deref(), Some("invalid"));
+
+        env.insert(
+            LocaleCategory::Messages.env_var().into(),
+            "invalid-also".to_owned(),
+        );
+        assert_eq!(_get(&env).as_deref(), Some("invalid-also"));
+
+        env.insert(LC_ALL.into(), "invalid-again".to_owned());
+        assert_eq!(_get(&env).as_deref(), Some("invalid-again"));
+    }
+
+    #[test]
+    fn posix_locale() {
+        let mut env = MockEnv::new();
+
+        for code in ["C", "POSIX", "C.UTF-8"].iter() {
+            env.insert(LANG.into(), code.to_string());
+            assert_eq!(_get(&env), None);
+            assert!(_get_locales(&env).is_empty());
+        }
+
+        // `LC_ALL=C` still overrides `LANG`, rather than being skipped as if it were unset.
+        env.insert(LANG.into(), "fr_FR.UTF-8".to_owned());
+        env.insert(LC_ALL.into(), "C".to_owned());
+        assert_eq!(_get(&env), None);
+    }
 
+    #[cfg(all(
+        feature = "live-c-locale",
+        not(feature = "no-env"),
+        target_os = "linux"
+    ))]
     #[test]
-    fn parse_identifier() {
-        let identifier = "fr_FR.UTF-8";
-        assert_eq!(parse_locale_code(identifier).as_deref(), Some(PARSE_LOCALE));
+    fn live_c_locale() {
+        use super::{query_c_locale, StdEnv};
+
+        // `C.UTF-8` is the only locale other than `C` which is always installed.
+        let name = std::ffi::CString::new("C.UTF-8").unwrap();
+        // SAFETY: `name` is a null-terminated string. No other test changes the C locale.
+        let set = unsafe { libc::setlocale(libc::LC_MESSAGES, name.as_ptr()) };
+        assert!(!set.is_null());
+
+        let queried = query_c_locale(libc::LC_MESSAGES);
+        assert_eq!(queried.as_deref(), Some("C.UTF-8"));
+        // Querying the locale doesn't change it.
+        assert_eq!(query_c_locale(libc::LC_MESSAGES), queried);
+        // The `C` locales don't name a language, so the variables are used instead.
+        assert_eq!(StdEnv::Process.live_code("LC_MESSAGES"), None);
+
+        let name = std::ffi::CString::new("C").unwrap();
+        // SAFETY: `name` is a null-terminated string.
+        unsafe { libc::setlocale(libc::LC_MESSAGES, name.as_ptr()) };
+        assert_eq!(query_c_locale(libc::LC_MESSAGES).as_deref(), Some("C"));
     }
 
     #[test]
-    fn parse_non_suffixed_identifier() {
+    fn installed_locales() {
+        use crate::is_tag_available;
+
+        let output = "C\nC.utf8\nPOSIX\nde_DE@euro\nen_US.utf8\nsr_RS@latin\n";
+        let installed = || super::installed_locales(output);
         assert_eq!(
-            parse_locale_code(PARSE_LOCALE).as_deref(),
-            Some(PARSE_LOCALE)
+            installed().collect::<Vec<_>>(),
+            ["de-DE", "en-US", "sr-Latn-RS"]
         );
 
+        assert!(is_tag_available("en-US", installed()));
+        assert!(is_tag_available("en-us", installed()));
+        assert!(is_tag_available("en", installed()));
+        assert!(is_tag_available("sr-Latn", installed()));
+        assert!(!is_tag_available("en-GB", installed()));
+        assert!(!is_tag_available("e", installed()));
+        assert!(!is_tag_available("", installed()));
+        assert!(!is_tag_available("fr", installed()));
+        assert!(!is_tag_available("fr", super::installed_locales("")));
+    }
+
+    #[test]
+    fn message_precedence() {
+        // Every combination of the variables which apply to messages, with each set to a
+        // different locale, and the locales which are expected to be returned.
+        type Row = (
+            Option<&'static str>,
+            Option<&'static str>,
+            Option<&'static str>,
+            Option<&'static str>,
+            &'static [&'static str],
+        );
+
+        #[rustfmt::skip]
+        let table: &[Row] = &[
+            // LC_ALL,        LC_MESSAGES,    LANGUAGE,       LANG
+            (None,            None,           None,           None,           &[]),
+            (None,            None,           None,           Some("en_US"),  &["en-US"]),
+            (None,            None,           Some("de:fr"),  None,           &["de", "fr"]),
+            (None,            None,           Some("de:fr"),  Some("en_US"),  &["de", "fr", "en-US"]),
+            (None,            Some("es_ES"),  None,           None,           &["es-ES"]),
+            (None,            Some("es_ES"),  None,           Some("en_US"),  &["es-ES"]),
+            (None,            Some("es_ES"),  Some("de:fr"),  None,           &["de", "fr", "es-ES"]),
+            (None,            Some("es_ES"),  Some("de:fr"),  Some("en_US"),  &["de", "fr", "es-ES"]),
+            (Some("it_IT"),   None,           None,           None,           &["it-IT"]),
+            (Some("it_IT"),   None,           None,           Some("en_US"),  &["it-IT"]),
+            (Some("it_IT"),   None,           Some("de:fr"),  None,           &["de", "fr", "it-IT"]),
+            (Some("it_IT"),   None,           Some("de:fr"),  Some("en_US"),  &["de", "fr", "it-IT"]),
+            (Some("it_IT"),   Some("es_ES"),  None,           None,           &["it-IT"]),
+            (Some("it_IT"),   Some("es_ES"),  None,           Some("en_US"),  &["it-IT"]),
+            (Some("it_IT"),   Some("es_ES"),  Some("de:fr"),  None,           &["de", "fr", "it-IT"]),
+            (Some("it_IT"),   Some("es_ES"),  Some("de:fr"),  Some("en_US"),  &["de", "fr", "it-IT"]),
+            // The `C` locale disables `LANGUAGE`, wherever it comes from.
+            (Some("C"),       Some("es_ES"),  Some("de:fr"),  Some("en_US"),  &[]),
+            (Some("C.UTF-8"), None,           Some("de:fr"),  None,           &[]),
+            (None,            Some("POSIX"),  Some("de:fr"),  Some("en_US"),  &[]),
+            (None,            None,           Some("de:fr"),  Some("C"),      &[]),
+            // But not when it's overridden by a more specific variable.
+            (None,            Some("es_ES"),  Some("de:fr"),  Some("C"),      &["de", "fr", "es-ES"]),
+            // Empty variables are treated as unset.
+            (Some(""),        Some(""),       Some(""),       Some("en_US"),  &["en-US"]),
+            (Some(""),        None,           Some("de:fr"),  Some(""),       &["de", "fr"]),
+            (Some(""),        None,           None,           Some("en_US.UTF-8"), &["en-US"]),
+            (Some(""),        Some("es_ES"),  None,           Some("en_US"),  &["es-ES"]),
+            (None,            Some(""),       None,           Some("en_US"),  &["en-US"]),
+            (Some(""),        Some(""),       Some(""),       Some(""),       &[]),
+        ];
+
+        for (lc_all, lc_messages, language, lang, expected) in table.iter() {
+            let mut env = MockEnv::new();
+            let vars = [
+                (LC_ALL, lc_all),
+                (LocaleCategory::Messages.env_var(), lc_messages),
+                (LANGUAGE, language),
+                (LANG, lang),
+            ];
+            for (key, value) in vars.iter() {
+                if let Some(value) = value {
+                    env.insert(key.into(), value.to_string());
+                }
+            }
+
+            assert_eq!(_get_locales(&env), *expected, "{:?}", vars);
+        }
+    }
+
+    #[test]
+    fn language_only_applies_to_messages() {
+        let mut env = MockEnv::new();
+        env.insert(LANGUAGE.into(), "de:fr".to_owned());
+        env.insert(LANG.into(), "en_US.UTF-8".to_owned());
+
+        assert_eq!(_get(&env).as_deref(), Some("en-US"));
         assert_eq!(
-            parse_locale_code(LANG_PARSE_LOCALE).as_deref(),
-            Some(PARSE_LOCALE)
+            _get_locale_for(&env, LocaleCategory::Messages).as_deref(),
+            Some("en-US")
         );
+        assert_eq!(_get_format_locale(&env).as_deref(), Some("en-US"));
     }
 
     #[test]
-    fn env_priority() {
+    fn language_list() {
         let mut env = MockEnv::new();
-        assert_eq!(_get(&env), None);
+        assert!(_get_locales(&env).is_empty());
 
-        // These locale names are technically allowed and some systems may still
-        // defined aliases such as these but the glibc sources mention that this
-        // should be considered deprecated
+        env.insert(LANG.into(), "fr_FR.UTF-8".to_owned());
+        assert_eq!(_get_locales(&env), ["fr-FR"]);
 
-        env.insert(LANG.into(), "invalid".to_owned());
-        assert_eq!(_get(&env).as_deref(), Some("invalid"));
+        // An empty list is ignored.
+        env.insert(LANGUAGE.into(), "".to_owned());
+        assert_eq!(_get_locales(&env), ["fr-FR"]);
 
-        env.insert(LC_CTYPE.into(), "invalid-also".to_owned());
-        assert_eq!(_get(&env).as_deref(), Some("invalid-also"));
+        env.insert(LANGUAGE.into(), "de:en_GB:fr".to_owned());
+        assert_eq!(_get_locales(&env), ["de", "en-GB", "fr", "fr-FR"]);
 
-        env.insert(LC_ALL.into(), "invalid-again".to_owned());
-        assert_eq!(_get(&env).as_deref(), Some("invalid-again"));
+        // Duplicates keep their first position, including the `LANG` locale.
+        env.insert(LANGUAGE.into(), "fr_FR:de::en_GB:de".to_owned());
+        assert_eq!(_get_locales(&env), ["fr-FR", "de", "en-GB"]);
+    }
+
+    #[test]
+    fn raw_locales() {
+        let mut env = MockEnv::new();
+        assert!(_get_raw_locales(&env).is_empty());
+
+        env.insert(LANG.into(), "de_DE.UTF-8@euro".to_owned());
+        assert_eq!(_get_raw_locales(&env), ["de_DE.UTF-8@euro"]);
+        assert_eq!(_get_locales(&env), ["de-DE"]);
+
+        env.insert(LANGUAGE.into(), "pt_BR:de_DE.UTF-8@euro".to_owned());
+        assert_eq!(
+            _get_raw_locales(&env),
+            ["pt_BR", "de_DE.UTF-8@euro", "de_DE.UTF-8@euro"]
+        );
+        assert_eq!(_get_locales(&env), ["pt-BR", "de-DE"]);
+
+        // The `C` locale is kept, as it's what the platform provides.
+        env.remove(&OsString::from(LANGUAGE));
+        env.insert(LC_ALL.into(), "C".to_owned());
+        assert_eq!(_get_raw_locales(&env), ["C"]);
+        assert!(_get_locales(&env).is_empty());
+    }
+
+    #[test]
+    fn locale_conf() {
+        use super::LocaleConf;
+
+        let conf = LocaleConf::parse(
+            "# Written by systemd-localed\n\
+             LANG=\"de_DE.UTF-8\"\n\
+             \n\
+             LC_TIME='en_GB.UTF-8'\n\
+             LANGUAGE=de:en\n\
+             LC_PAPER=\n\
+             not a variable\n",
+        );
+        assert_eq!(_get(&conf).as_deref(), Some("de-DE"));
+        assert_eq!(_get_locales(&conf), ["de", "en", "de-DE"]);
+        assert_eq!(
+            EnvAccess::get(&conf, "LC_TIME").as_deref(),
+            Some("en_GB.UTF-8")
+        );
+        assert_eq!(EnvAccess::get(&conf, "LC_PAPER"), None);
+
+        // `LC_ALL` takes precedence, and later assignments replace earlier ones.
+        let conf = LocaleConf::parse("LANG=fr_FR.UTF-8\nLC_ALL=it_IT.UTF-8\nLC_ALL=nl_NL.UTF-8");
+        assert_eq!(_get(&conf).as_deref(), Some("nl-NL"));
+
+        // Comments and blank files don't configure anything.
+        assert!(LocaleConf::parse("# LANG=C\n\n").0.is_empty());
+    }
+
+    #[test]
+    fn first_weekday() {
+        // Most locales count from Sunday, 1997-11-30.
+        assert_eq!(first_day_of_week(19971130, 1), Some(Weekday::Sunday));
+        assert_eq!(first_day_of_week(19971130, 2), Some(Weekday::Monday));
+        assert_eq!(first_day_of_week(19971130, 7), Some(Weekday::Saturday));
+
+        // Some count from Monday, 1997-12-01.
+        assert_eq!(first_day_of_week(19971201, 1), Some(Weekday::Monday));
+        assert_eq!(first_day_of_week(19971201, 7), Some(Weekday::Sunday));
+
+        assert_eq!(first_day_of_week(19971130, 0), None);
+        assert_eq!(first_day_of_week(19971130, 8), None);
+        assert_eq!(first_day_of_week(0, 1), None);
+    }
+
+    #[test]
+    fn format_locale_priority() {
+        let mut env = MockEnv::new();
+        assert_eq!(_get_format_locale(&env), None);
+
+        env.insert(LANG.into(), "en_US.UTF-8".to_owned());
+        assert_eq!(_get_format_locale(&env).as_deref(), Some("en-US"));
+
+        env.insert(
+            LocaleCategory::Numeric.env_var().into(),
+            "de_DE.UTF-8".to_owned(),
+        );
+        assert_eq!(_get_format_locale(&env).as_deref(), Some("de-DE"));
+        assert_eq!(_get(&env).as_deref(), Some("en-US"));
+
+        env.insert(LC_ALL.into(), "fr_FR.UTF-8".to_owned());
+        assert_eq!(_get_format_locale(&env).as_deref(), Some("fr-FR"));
+    }
+
+    #[test]
+    fn collation_locale_priority() {
+        let mut env = MockEnv::new();
+        assert_eq!(_get_collation_locale(&env), None);
+
+        env.insert(LANG.into(), "en_US.UTF-8".to_owned());
+        assert_eq!(_get_collation_locale(&env).as_deref(), Some("en-US"));
+
+        env.insert(
+            LocaleCategory::Collate.env_var().into(),
+            "sv_SE.UTF-8".to_owned(),
+        );
+        assert_eq!(_get_collation_locale(&env).as_deref(), Some("sv-SE"));
+        assert_eq!(_get(&env).as_deref(), Some("en-US"));
+
+        env.insert(LC_ALL.into(), "de_DE.UTF-8".to_owned());
+        assert_eq!(_get_collation_locale(&env).as_deref(), Some("de-DE"));
+        assert_eq!(_get(&env).as_deref(), Some("de-DE"));
+    }
+
+    #[test]
+    fn category_priority() {
+        let categories = [
+            (LocaleCategory::Messages, "ja_JP.UTF-8", "ja-JP"),
+            (LocaleCategory::Time, "en_GB.UTF-8", "en-GB"),
+            (LocaleCategory::Numeric, "de_DE.UTF-8", "de-DE"),
+            (LocaleCategory::Monetary, "fr_CH.UTF-8", "fr-CH"),
+            (LocaleCategory::Collate, "sv_SE.UTF-8", "sv-SE"),
+            (LocaleCategory::Measurement, "en_US.UTF-8", "en-US"),
+        ];
+
+        let mut env = MockEnv::new();
+        env.insert(LANG.into(), "nl_NL.UTF-8".to_owned());
+        for (category, _, _) in categories.iter() {
+            assert_eq!(_get_locale_for(&env, *category).as_deref(), Some("nl-NL"));
+        }
+
+        for (category, code, _) in categories.iter() {
+            env.insert(category.env_var().into(), code.to_string());
+        }
+        for (category, _, tag) in categories.iter() {
+            assert_eq!(
+                _get_locale_for(&env, *category).as_deref(),
+                Some(*tag),
+                "{:?}",
+                category
+            );
+        }
+
+        env.insert(LC_ALL.into(), "pt_BR.UTF-8".to_owned());
+        for (category, _, _) in categories.iter() {
+            assert_eq!(_get_locale_for(&env, *category).as_deref(), Some("pt-BR"));
+        }
+
+        // An empty variable falls through to the next one, at every level.
+        env.insert(LC_ALL.into(), String::new());
+        for (category, _, _) in categories.iter() {
+            env.insert(category.env_var().into(), String::new());
+            assert_eq!(
+                _get_locale_for(&env, *category).as_deref(),
+                Some("nl-NL"),
+                "{:?}",
+                category
+            );
+        }
+        env.insert(LANG.into(), String::new());
+        for (category, _, _) in categories.iter() {
+            assert_eq!(_get_locale_for(&env, *category), None);
+        }
+    }
+
+    #[test]
+    fn empty_variables_dont_configure_locale() {
+        use super::configures_locale;
+
+        let var = |key: &str, value: &str| (OsString::from(key), OsString::from(value));
+        assert!(!configures_locale(Vec::new().into_iter()));
+        assert!(!configures_locale(
+            vec![var(LC_ALL, ""), var(LANG, ""), var("HOME", "/root")].into_iter()
+        ));
+        assert!(configures_locale(
+            vec![var(LC_ALL, ""), var(LANG, "en_US.UTF-8")].into_iter()
+        ));
+        assert!(configures_locale(vec![var("LC_TIME", "de_DE")].into_iter()));
+    }
+
+    #[test]
+    fn message_variable() {
+        use super::message_variable;
+
+        let mut env = MockEnv::new();
+        assert_eq!(message_variable(&env), None);
+
+        env.insert(LANG.into(), "de_DE.UTF-8".to_owned());
+        env.insert(LC_ALL.into(), "".to_owned());
+        assert_eq!(message_variable(&env), Some(LANG));
+
+        env.insert(LANGUAGE.into(), "fr:en".to_owned());
+        assert_eq!(message_variable(&env), Some(LANGUAGE));
+
+        // `LANGUAGE` is ignored when messages aren't translated.
+        env.insert(LC_ALL.into(), "C".to_owned());
+        assert_eq!(message_variable(&env), Some(LC_ALL));
+    }
+
+    #[test]
+    fn all_categories() {
+        use super::_get_all_categories;
+
+        let mut env = MockEnv::new();
+        assert!(_get_all_categories(&env)
+            .iter()
+            .all(|(_, locale)| locale.is_none()));
+
+        env.insert("LC_TIME".into(), "en_GB.UTF-8".to_owned());
+        env.insert("LC_MONETARY".into(), "fr_CH.UTF-8".to_owned());
+        env.insert("LC_COLLATE".into(), "C".to_owned());
+        assert_eq!(
+            _get_all_categories(&env),
+            [
+                (LocaleCategory::Messages, None),
+                (LocaleCategory::Time, Some("en-GB".to_owned())),
+                (LocaleCategory::Numeric, None),
+                (LocaleCategory::Monetary, Some("fr-CH".to_owned())),
+                (LocaleCategory::Collate, None),
+                (LocaleCategory::Measurement, None),
+            ]
+        );
+
+        env.insert(LANG.into(), "de_DE.UTF-8".to_owned());
+        let categories = _get_all_categories(&env);
+        assert_eq!(
+            categories[0],
+            (LocaleCategory::Messages, Some("de-DE".to_owned()))
+        );
+        assert_eq!(
+            categories[1],
+            (LocaleCategory::Time, Some("en-GB".to_owned()))
+        );
+        assert_eq!(categories[4], (LocaleCategory::Collate, None));
+    }
+
+    #[test]
+    fn weekend_days() {
+        let mut env = MockEnv::new();
+        assert_eq!(_get_weekend_days(&env), None);
+
+        env.insert(LANG.into(), "de_DE.UTF-8".to_owned());
+        assert_eq!(
+            _get_weekend_days(&env),
+            Some((Weekday::Saturday, Weekday::Sunday))
+        );
+
+        env.insert(
+            LocaleCategory::Time.env_var().into(),
+            "ar_SA.UTF-8".to_owned(),
+        );
+        assert_eq!(
+            _get_weekend_days(&env),
+            Some((Weekday::Friday, Weekday::Saturday))
+        );
+    }
+
+    #[cfg(feature = "no-env")]
+    #[test]
+    fn no_env_ignores_variables() {
+        use super::StdEnv;
+
+        std::env::set_var(LC_ALL, "tlh_XX.UTF-8");
+        std::env::set_var(LANGUAGE, "tlh");
+        let env = StdEnv::new();
+        let locales = _get_locales(&env);
+        std::env::remove_var(LC_ALL);
+        std::env::remove_var(LANGUAGE);
+
+        assert!(matches!(env, StdEnv::Config(..)));
+        assert!(!locales.iter().any(|locale| locale.starts_with("tlh")));
+        assert_ne!(env.get(LC_ALL).as_deref(), Some("tlh_XX.UTF-8"));
+    }
+
+    #[test]
+    fn calendar() {
+        let mut env = MockEnv::new();
+        assert_eq!(_get_calendar(&env), None);
+
+        env.insert(LANG.into(), "th_TH.UTF-8".to_owned());
+        assert_eq!(_get_calendar(&env), Some(Calendar::Buddhist));
+
+        env.insert(
+            LocaleCategory::Time.env_var().into(),
+            "th_TH.UTF-8@calendar=gregorian".to_owned(),
+        );
+        assert_eq!(_get_calendar(&env), Some(Calendar::Gregorian));
+
+        env.insert(
+            LocaleCategory::Time.env_var().into(),
+            "th-TH-u-ca-buddhist".to_owned(),
+        );
+        assert_eq!(_get_calendar(&env), Some(Calendar::Buddhist));
+
+        env.insert(
+            LocaleCategory::Time.env_var().into(),
+            "en_US.UTF-8@calendar=coptic".to_owned(),
+        );
+        assert_eq!(_get_calendar(&env), None);
+    }
+
+    #[test]
+    fn numbering_system() {
+        use super::_get_numbering_system;
+
+        let mut env = MockEnv::new();
+        assert_eq!(_get_numbering_system(&env), None);
+
+        env.insert(LANG.into(), "en_US.UTF-8".to_owned());
+        assert_eq!(_get_numbering_system(&env).as_deref(), Some("latn"));
+
+        env.insert(
+            LocaleCategory::Numeric.env_var().into(),
+            "ar_EG.UTF-8".to_owned(),
+        );
+        assert_eq!(_get_numbering_system(&env).as_deref(), Some("arab"));
+
+        env.insert(
+            LocaleCategory::Numeric.env_var().into(),
+            "ar_EG.UTF-8@numbers=latn".to_owned(),
+        );
+        assert_eq!(_get_numbering_system(&env).as_deref(), Some("latn"));
+
+        env.insert(
+            LocaleCategory::Numeric.env_var().into(),
+            "en-US-u-nu-arab".to_owned(),
+        );
+        assert_eq!(_get_numbering_system(&env).as_deref(), Some("arab"));
+    }
+
+    #[test]
+    fn locale_settings() {
+        use super::_get_locale_settings;
+
+        let mut env = MockEnv::new();
+        assert_eq!(_get_locale_settings(&env), LocaleSettings::default());
+
+        env.insert(LANG.into(), "en_GB.UTF-8".to_owned());
+        env.insert(
+            LocaleCategory::Measurement.env_var().into(),
+            "en_US.UTF-8".to_owned(),
+        );
+        let settings = _get_locale_settings(&env);
+        assert_eq!(settings.locale, None);
+        assert_eq!(settings.region.as_deref(), Some("GB"));
+        assert_eq!(
+            settings.measurement_system,
+            Some(MeasurementSystem::UsCustomary)
+        );
+    }
+
+    #[test]
+    fn measurement_system() {
+        let mut env = MockEnv::new();
+        assert_eq!(_get_measurement_system(&env), None);
+
+        env.insert(LANG.into(), "en_US.UTF-8".to_owned());
+        assert_eq!(
+            _get_measurement_system(&env),
+            Some(MeasurementSystem::UsCustomary)
+        );
+
+        env.insert(
+            LocaleCategory::Measurement.env_var().into(),
+            "de_DE.UTF-8".to_owned(),
+        );
+        assert_eq!(
+            _get_measurement_system(&env),
+            Some(MeasurementSystem::Metric)
+        );
+
+        env.insert(LC_ALL.into(), "en_GB.UTF-8".to_owned());
+        assert_eq!(_get_measurement_system(&env), Some(MeasurementSystem::Uk));
+
+        // The measurement system can't be inferred without a region.
+        env.insert(LC_ALL.into(), "en".to_owned());
+        assert_eq!(_get_measurement_system(&env), None);
+    }
+
+    #[test]
+    fn paper_size_from_region() {
+        use super::{_get_paper_size, LC_PAPER};
+        use crate::PaperSize;
+
+        let mut env = MockEnv::new();
+        assert_eq!(_get_paper_size(&env), None);
+
+        env.insert(LANG.into(), "en_US.UTF-8".to_owned());
+        assert_eq!(_get_paper_size(&env), Some(PaperSize::Letter));
+
+        env.insert(LC_PAPER.into(), "de_DE.UTF-8".to_owned());
+        assert_eq!(_get_paper_size(&env), Some(PaperSize::A4));
+        assert_eq!(_get(&env).as_deref(), Some("en-US"));
+
+        env.insert(LC_ALL.into(), "es_MX.UTF-8".to_owned());
+        assert_eq!(_get_paper_size(&env), Some(PaperSize::Letter));
+
+        env.insert(LC_ALL.into(), "C".to_owned());
+        assert_eq!(_get_paper_size(&env), None);
+    }
+
+    #[test]
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    fn paper_size_from_c_locale() {
+        use super::{paper_size_from_langinfo, LC_PAPER};
+        use crate::PaperSize;
+
+        // The `C` locale is built into glibc, where it uses A4.
+        let mut env = MockEnv::new();
+        env.insert(LC_PAPER.into(), "C".to_owned());
+        assert_eq!(paper_size_from_langinfo(&env), Some(PaperSize::A4));
+    }
+
+    #[test]
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    fn locale_display_name() {
+        use super::{_get_locale_display_name, display_name, LC_IDENTIFICATION};
+
+        let name = |title: &str, language: &str| {
+            display_name(Some(title.to_owned()), Some(language.to_owned()))
+        };
+        assert_eq!(
+            name("English locale for the USA", "American English").as_deref(),
+            Some("English locale for the USA")
+        );
+        assert_eq!(
+            name("  German locale for Germany\n", "German").as_deref(),
+            Some("German locale for Germany")
+        );
+        assert_eq!(name("", " French ").as_deref(), Some("French"));
+        assert_eq!(name(" ", ""), None);
+        assert_eq!(display_name(None, None), None);
+
+        // The `C` locale is built into glibc, so it always has a title.
+        let mut env = MockEnv::new();
+        assert_eq!(_get_locale_display_name(&env), None);
+        env.insert(LC_IDENTIFICATION.into(), "C".to_owned());
+        assert!(_get_locale_display_name(&env).is_some());
+    }
+
+    #[test]
+    fn region_priority() {
+        let mut env = MockEnv::new();
+        assert_eq!(_get_region(&env), None);
+
+        env.insert(LANG.into(), "en_US.UTF-8".to_owned());
+        assert_eq!(_get_region(&env).as_deref(), Some("US"));
+
+        // The region can differ from the one implied by the language.
+        env.insert(LANG.into(), "fr_FR.UTF-8".to_owned());
+        env.insert(
+            LocaleCategory::Monetary.env_var().into(),
+            "fr_CH.UTF-8".to_owned(),
+        );
+        assert_eq!(_get_region(&env).as_deref(), Some("CH"));
+        assert_eq!(_get(&env).as_deref(), Some("fr-FR"));
+
+        env.insert(LC_ALL.into(), "de_AT.UTF-8".to_owned());
+        assert_eq!(_get_region(&env).as_deref(), Some("AT"));
+
+        env.insert(LC_ALL.into(), "de".to_owned());
+        assert_eq!(_get_region(&env), None);
     }
 }
@@ -0,0 +1,164 @@
+//! The locale settings of desktop environments, which may differ from the environment variables of
+//! graphical apps, such as those started by a display manager rather than the desktop's session.
+use super::{unquote, LocaleConf};
+use std::{env, path::PathBuf, process::Command};
+
+/// The source of the settings, as reported by [`describe_provider`](crate::describe_provider).
+pub(super) const SOURCE: &str = "desktop settings (plasma-localerc or org.gnome.system.locale)";
+
+/// The categories which GNOME's `region` setting applies to, as exported by `gnome-session`.
+const GNOME_REGION_CATEGORIES: [&str; 5] = [
+    "LC_NUMERIC",
+    "LC_TIME",
+    "LC_MONETARY",
+    "LC_MEASUREMENT",
+    "LC_PAPER",
+];
+
+/// Reads the locale settings of the running desktop environment, which is detected with
+/// `XDG_CURRENT_DESKTOP`.
+///
+/// This is best-effort, so `None` is returned if the desktop isn't one which is supported, or if
+/// its settings can't be read or don't configure the locale.
+pub(super) fn settings() -> Option<LocaleConf> {
+    let desktops = env::var("XDG_CURRENT_DESKTOP").ok()?;
+    // This is a list such as `ubuntu:GNOME`, in order of precedence.
+    desktops
+        .split(':')
+        .filter_map(|desktop| match desktop {
+            "KDE" => kde_settings(),
+            "GNOME" => gnome_settings(),
+            _ => None,
+        })
+        .find(|conf| !conf.0.is_empty())
+}
+
+/// Reads the languages and formats picked in Plasma's settings, from `plasma-localerc`.
+fn kde_settings() -> Option<LocaleConf> {
+    let config = match env::var_os("XDG_CONFIG_HOME") {
+        Some(path) if !path.is_empty() => PathBuf::from(path),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+    let contents = std::fs::read_to_string(config.join("plasma-localerc")).ok()?;
+    Some(parse_plasma_localerc(&contents))
+}
+
+/// Parses the `[Formats]` and `[Translations]` groups of `plasma-localerc`, which contain
+/// `LANG` and `LC_*` variables, and `LANGUAGE` respectively.
+fn parse_plasma_localerc(contents: &str) -> LocaleConf {
+    let mut variables = String::new();
+    let mut in_locale_group = false;
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_locale_group = line == "[Formats]" || line == "[Translations]";
+        } else if in_locale_group {
+            variables.push_str(line);
+            variables.push('\n');
+        }
+    }
+
+    let mut conf = LocaleConf::parse(&variables);
+    conf.0
+        .retain(|(key, _)| key == "LANG" || key == "LANGUAGE" || key.starts_with("LC_"));
+    conf
+}
+
+/// Reads GNOME's `region` setting, which picks the formats, through `gsettings` since dconf's
+/// database isn't practical to read directly. GNOME doesn't store the language in dconf, so it
+/// still comes from the environment.
+fn gnome_settings() -> Option<LocaleConf> {
+    let output = Command::new("gsettings")
+        .arg("get")
+        .arg("org.gnome.system.locale")
+        .arg("region")
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let region = parse_gsettings_string(std::str::from_utf8(&output.stdout).ok()?)?;
+    Some(gnome_region_settings(region))
+}
+
+fn gnome_region_settings(region: &str) -> LocaleConf {
+    LocaleConf(
+        GNOME_REGION_CATEGORIES
+            .iter()
+            .map(|category| (String::from(*category), String::from(region)))
+            .collect(),
+    )
+}
+
+/// Parses a string printed by `gsettings`, such as `'en_GB.UTF-8'`, returning `None` if it's
+/// empty, which is how GNOME stores that the region hasn't been picked.
+fn parse_gsettings_string(output: &str) -> Option<&str> {
+    let value = unquote(output.trim());
+    if value.is_empty() {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_gsettings_string, parse_plasma_localerc};
+    use crate::unix::{_get, _get_format_locale, _get_locales, EnvAccess};
+
+    #[test]
+    fn plasma_localerc() {
+        let conf = parse_plasma_localerc(
+            "[Formats]\n\
+             LANG=de_DE.UTF-8\n\
+             LC_TIME=en_GB.UTF-8\n\
+             \n\
+             [Translations]\n\
+             LANGUAGE=fr:en_US\n\
+             \n\
+             [Other]\n\
+             LC_ALL=it_IT.UTF-8\n\
+             useDetailed=true\n",
+        );
+        assert_eq!(_get_locales(&conf), ["fr", "en-US", "de-DE"]);
+        assert_eq!(_get_format_locale(&conf).as_deref(), Some("de-DE"));
+        assert_eq!(
+            EnvAccess::get(&conf, "LC_TIME").as_deref(),
+            Some("en_GB.UTF-8")
+        );
+        // Groups other than those of the locale are ignored.
+        assert_eq!(EnvAccess::get(&conf, "LC_ALL"), None);
+
+        assert!(parse_plasma_localerc("").0.is_empty());
+        assert!(parse_plasma_localerc("[Formats]\nuseDetailed=true\n")
+            .0
+            .is_empty());
+    }
+
+    #[test]
+    fn gsettings_string() {
+        assert_eq!(
+            parse_gsettings_string("'en_GB.UTF-8'\n"),
+            Some("en_GB.UTF-8")
+        );
+        assert_eq!(
+            parse_gsettings_string("\"pt_BR.UTF-8\""),
+            Some("pt_BR.UTF-8")
+        );
+        assert_eq!(parse_gsettings_string("''\n"), None);
+        assert_eq!(parse_gsettings_string(""), None);
+    }
+
+    #[test]
+    fn gnome_region() {
+        let conf = super::gnome_region_settings("en_GB.UTF-8");
+        assert_eq!(_get_format_locale(&conf).as_deref(), Some("en-GB"));
+        // The region doesn't pick the language.
+        assert_eq!(_get(&conf), None);
+        assert_eq!(
+            EnvAccess::get(&conf, "LC_PAPER").as_deref(),
+            Some("en_GB.UTF-8")
+        );
+    }
+}
@@ -0,0 +1,139 @@
+//! Reads locale data with `nl_langinfo_l`.
+//!
+//! Unlike `setlocale` and `localeconv`, this doesn't read or change the global locale of the
+//! process, so it is safe to use from multiple threads. The locale name is also passed in
+//! explicitly, so the environment is never read outside of [std::env].
+use std::ffi::{CStr, CString};
+
+/// The date which the list of weekday names of `LC_TIME` starts on, as a number in the form
+/// `YYYYMMDD`. This is a glibc extension, which isn't provided by `libc`.
+#[cfg(target_env = "gnu")]
+pub(super) const _NL_TIME_WEEK_1STDAY: libc::nl_item = 0x20066;
+/// The first day of the week in `LC_TIME`, counted from `_NL_TIME_WEEK_1STDAY` starting at 1.
+/// This is a glibc extension, which isn't provided by `libc`.
+#[cfg(target_env = "gnu")]
+pub(super) const _NL_TIME_FIRST_WEEKDAY: libc::nl_item = 0x20068;
+/// The international currency symbol of `LC_MONETARY`, such as `"USD "`, which is also the
+/// `int_curr_symbol` of `localeconv`. This is a glibc extension, which isn't provided by `libc`.
+#[cfg(target_env = "gnu")]
+pub(super) const __INT_CURR_SYMBOL: libc::nl_item = 0x40000;
+/// The signs of positive and negative amounts in `LC_MONETARY`, which are also the
+/// `positive_sign` and `negative_sign` of `localeconv`. These are provided by glibc, but not by
+/// `libc`.
+#[cfg(target_env = "gnu")]
+pub(super) const __POSITIVE_SIGN: libc::nl_item = 0x40005;
+#[cfg(target_env = "gnu")]
+pub(super) const __NEGATIVE_SIGN: libc::nl_item = 0x40006;
+
+/// The sizes of the groups of digits in `LC_NUMERIC`, as a string of bytes which is also the
+/// `grouping` of `localeconv`. This is provided by glibc, but not by `libc`.
+#[cfg(target_env = "gnu")]
+pub(super) const GROUPING: libc::nl_item = 0x10002;
+
+/// The height and width of paper in `LC_PAPER`, in millimeters. These are glibc extensions,
+/// which aren't provided by `libc`.
+#[cfg(target_env = "gnu")]
+pub(super) const _NL_PAPER_HEIGHT: libc::nl_item = 0x70000;
+#[cfg(target_env = "gnu")]
+pub(super) const _NL_PAPER_WIDTH: libc::nl_item = 0x70001;
+
+/// The title of `LC_IDENTIFICATION`, such as "English locale for the USA", and the name of its
+/// language, such as "American English". These are glibc extensions, which aren't provided by
+/// `libc`.
+#[cfg(target_env = "gnu")]
+pub(super) const _NL_IDENTIFICATION_TITLE: libc::nl_item = 0xC0000;
+#[cfg(target_env = "gnu")]
+pub(super) const _NL_IDENTIFICATION_LANGUAGE: libc::nl_item = 0xC0007;
+
+/// Locale data that has been loaded for one or more categories.
+pub(super) struct LocaleData(libc::locale_t);
+
+impl LocaleData {
+    /// Loads the locale data named `name`, such as `de_DE.UTF-8`, for the categories
+    /// in `mask`, such as `LC_NUMERIC_MASK`.
+    ///
+    /// Returns `None` if the locale isn't installed on the system.
+    pub(super) fn new(mask: libc::c_int, name: &str) -> Option<Self> {
+        let name = CString::new(name).ok()?;
+
+        // SAFETY: `name` is a valid C string. Passing a null base creates a new
+        // locale object, which will be owned by us.
+        let locale = unsafe { libc::newlocale(mask, name.as_ptr(), core::ptr::null_mut()) };
+        if locale.is_null() {
+            None
+        } else {
+            Some(Self(locale))
+        }
+    }
+
+    /// Reads an item of the locale data, such as `RADIXCHAR`.
+    ///
+    /// Returns `None` if the item isn't valid UTF-8.
+    pub(super) fn item(&self, item: libc::nl_item) -> Option<String> {
+        // SAFETY: This wrapper contains a valid locale object.
+        let value = unsafe { libc::nl_langinfo_l(item, self.0) };
+        if value.is_null() {
+            return None;
+        }
+
+        // SAFETY: The returned string is null-terminated, and remains valid until the
+        // locale object is freed, which can't happen while `self` is borrowed.
+        let value = unsafe { CStr::from_ptr(value) };
+        value.to_str().ok().map(String::from)
+    }
+
+    /// Reads an item which is a string of bytes rather than text, such as `GROUPING`.
+    #[cfg(target_env = "gnu")]
+    pub(super) fn bytes(&self, item: libc::nl_item) -> Option<Vec<u8>> {
+        // SAFETY: This wrapper contains a valid locale object.
+        let value = unsafe { libc::nl_langinfo_l(item, self.0) };
+        if value.is_null() {
+            return None;
+        }
+
+        // SAFETY: The returned string is null-terminated, and remains valid until the
+        // locale object is freed, which can't happen while `self` is borrowed.
+        Some(unsafe { CStr::from_ptr(value) }.to_bytes().to_vec())
+    }
+
+    /// Reads an item which glibc stores as a number in place of the string, such as
+    /// `_NL_TIME_WEEK_1STDAY`.
+    #[cfg(target_env = "gnu")]
+    pub(super) fn word(&self, item: libc::nl_item) -> Option<u32> {
+        // SAFETY: This wrapper contains a valid locale object.
+        let value = unsafe { libc::nl_langinfo_l(item, self.0) };
+        if value.is_null() {
+            return None;
+        }
+
+        // glibc returns the value through a union of a pointer and a `u32`, so the number
+        // is in the first bytes of the pointer, regardless of the byte order.
+        #[allow(clippy::as_conversions)]
+        let bytes = (value as usize).to_ne_bytes();
+        let mut word = [0; 4];
+        word.copy_from_slice(&bytes[..4]);
+        Some(u32::from_ne_bytes(word))
+    }
+
+    /// Reads an item which is stored as a single byte, such as `_NL_TIME_FIRST_WEEKDAY`.
+    #[cfg(target_env = "gnu")]
+    #[allow(clippy::as_conversions)]
+    pub(super) fn byte(&self, item: libc::nl_item) -> Option<u8> {
+        // SAFETY: This wrapper contains a valid locale object.
+        let value = unsafe { libc::nl_langinfo_l(item, self.0) };
+        if value.is_null() {
+            return None;
+        }
+
+        // SAFETY: The returned string contains at least the byte, and remains valid until the
+        // locale object is freed, which can't happen while `self` is borrowed.
+        Some(unsafe { *value } as u8)
+    }
+}
+
+impl Drop for LocaleData {
+    fn drop(&mut self) {
+        // SAFETY: This wrapper contains a valid locale object which is owned by us.
+        unsafe { libc::freelocale(self.0) }
+    }
+}
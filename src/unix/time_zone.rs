@@ -0,0 +1,114 @@
+//! The system's time zone, which is configured separately from the locale.
+use std::{env, fs};
+
+/// The file which contains the name of the system's time zone on Debian and its derivatives.
+const TIMEZONE_PATH: &str = "/etc/timezone";
+/// The system's time zone data, which is usually a symlink into the time zone database.
+const LOCALTIME_PATH: &str = "/etc/localtime";
+
+pub(crate) fn get_time_zone() -> Option<String> {
+    // Like the locale, `TZ` comes from whoever starts the process, so it isn't used with
+    // `no-env`.
+    let variable = if cfg!(feature = "no-env") {
+        None
+    } else {
+        env::var("TZ").ok()
+    };
+
+    variable
+        .and_then(|tz| time_zone_from_variable(&tz))
+        .or_else(|| {
+            let contents = fs::read_to_string(TIMEZONE_PATH).ok()?;
+            time_zone_name(contents.trim())
+        })
+        .or_else(|| {
+            let target = fs::read_link(LOCALTIME_PATH).ok()?;
+            time_zone_from_path(target.to_str()?)
+        })
+}
+
+/// Reads the time zone from the `TZ` variable, which is a name such as `Europe/Paris`, optionally
+/// after a `:`, or the path of a file in the time zone database.
+fn time_zone_from_variable(tz: &str) -> Option<String> {
+    let tz = tz.strip_prefix(':').unwrap_or(tz);
+    if tz.starts_with('/') {
+        time_zone_from_path(tz)
+    } else {
+        time_zone_name(tz)
+    }
+}
+
+/// Reads the time zone from a path in the time zone database, such as the
+/// `/usr/share/zoneinfo/Europe/Paris` which `/etc/localtime` links to.
+fn time_zone_from_path(path: &str) -> Option<String> {
+    const DATABASE: &str = "zoneinfo/";
+
+    let name = &path[path.rfind(DATABASE)? + DATABASE.len()..];
+    // The database may also contain copies of itself, with and without leap seconds.
+    let name = name
+        .strip_prefix("posix/")
+        .or_else(|| name.strip_prefix("right/"))
+        .unwrap_or(name);
+    time_zone_name(name)
+}
+
+/// Checks that a time zone is shaped like an IANA name, such as `America/New_York` or `UTC`.
+///
+/// Names with digits but without an area, such as `CET-1CEST`, are rules for POSIX's `TZ` rather
+/// than IANA names, so they're rejected.
+fn time_zone_name(name: &str) -> Option<String> {
+    let valid = !name.is_empty()
+        && !name.starts_with('/')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | '_' | '+' | '-'))
+        && (name.contains('/') || !name.chars().any(|c| c.is_ascii_digit()));
+    if valid {
+        Some(String::from(name))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{time_zone_from_path, time_zone_from_variable};
+
+    #[test]
+    fn time_zone_variable() {
+        assert_eq!(
+            time_zone_from_variable("Europe/Paris").as_deref(),
+            Some("Europe/Paris")
+        );
+        assert_eq!(
+            time_zone_from_variable(":America/New_York").as_deref(),
+            Some("America/New_York")
+        );
+        assert_eq!(
+            time_zone_from_variable("/usr/share/zoneinfo/Asia/Tokyo").as_deref(),
+            Some("Asia/Tokyo")
+        );
+        assert_eq!(time_zone_from_variable("UTC").as_deref(), Some("UTC"));
+        assert_eq!(
+            time_zone_from_variable("Etc/GMT+5").as_deref(),
+            Some("Etc/GMT+5")
+        );
+        assert_eq!(time_zone_from_variable("CET-1CEST,M3.5.0,M10.5.0/3"), None);
+        assert_eq!(time_zone_from_variable("EST5"), None);
+        assert_eq!(time_zone_from_variable(""), None);
+    }
+
+    #[test]
+    fn time_zone_path() {
+        assert_eq!(
+            time_zone_from_path("/usr/share/zoneinfo/Europe/London").as_deref(),
+            Some("Europe/London")
+        );
+        assert_eq!(
+            time_zone_from_path("../usr/share/zoneinfo/posix/America/Sao_Paulo").as_deref(),
+            Some("America/Sao_Paulo")
+        );
+        assert_eq!(time_zone_from_path("/usr/share/zoneinfo/"), None);
+        assert_eq!(time_zone_from_path("/etc/localtime"), None);
+    }
+}
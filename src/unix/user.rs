@@ -0,0 +1,178 @@
+//! The locale settings of another user, read from the configuration files in their home
+//! directory, for services which act on behalf of several users.
+use super::{_get_locales, unquote, LocaleConf};
+use std::{
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// The file which lists the users, including their IDs and home directories.
+const PASSWD_PATH: &str = "/etc/passwd";
+
+pub(crate) fn get_locales_for_user(uid: u32) -> Vec<String> {
+    let home = match fs::read_to_string(PASSWD_PATH)
+        .ok()
+        .and_then(|passwd| home_directory(&passwd, uid))
+    {
+        Some(home) => home,
+        None => return Vec::new(),
+    };
+    _get_locales(&user_conf(&home))
+}
+
+/// Finds the home directory of a user in the contents of `/etc/passwd`, which has lines such as
+/// `alice:x:1000:1000:Alice:/home/alice:/bin/bash`.
+///
+/// Users who only come from elsewhere, such as LDAP, aren't listed, so they aren't found.
+fn home_directory(passwd: &str, uid: u32) -> Option<PathBuf> {
+    passwd
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .find_map(|line| {
+            let mut fields = line.split(':');
+            let user_uid = fields.nth(2)?.parse::<u32>().ok()?;
+            let home = fields.nth(2)?;
+            if user_uid == uid && !home.is_empty() {
+                Some(PathBuf::from(home))
+            } else {
+                None
+            }
+        })
+}
+
+/// Reads the locale configuration files in a user's home directory, in the order in which
+/// they're applied when the user logs in, so that the later ones take precedence: PAM's
+/// `~/.pam_environment`, then systemd's `~/.config/environment.d/*.conf`, then the
+/// `~/.config/locale.conf` read by the login shell.
+///
+/// Files which can't be read, such as without permission, are skipped.
+fn user_conf(home: &Path) -> LocaleConf {
+    let mut variables = Vec::new();
+    if let Ok(contents) = fs::read_to_string(home.join(".pam_environment")) {
+        variables.extend(parse_pam_environment(&contents).0);
+    }
+
+    // systemd reads the files in the order of their names.
+    let mut environment_files = fs::read_dir(home.join(".config/environment.d"))
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension() == Some(OsStr::new("conf")))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    environment_files.sort();
+
+    let config_files = environment_files
+        .into_iter()
+        .chain(Some(home.join(".config/locale.conf")));
+    for path in config_files {
+        if let Ok(contents) = fs::read_to_string(path) {
+            variables.extend(LocaleConf::parse(&contents).0);
+        }
+    }
+
+    variables.retain(|(_, value)| !is_expansion(value));
+    LocaleConf(variables)
+}
+
+/// Parses `~/.pam_environment`, which contains lines such as `LANG DEFAULT=en_US.UTF-8`, where
+/// an `OVERRIDE=` value takes precedence over the `DEFAULT=`, or `KEY=value` lines like those of
+/// `/etc/environment`.
+fn parse_pam_environment(contents: &str) -> LocaleConf {
+    let mut variables = Vec::new();
+    for line in contents.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut parts = line.split_whitespace();
+        let key = match parts.next() {
+            Some(key) if key.contains('=') => {
+                variables.extend(LocaleConf::parse(line).0);
+                continue;
+            }
+            Some(key) => key,
+            None => continue,
+        };
+
+        let (mut default, mut overridden) = (None, None);
+        for option in parts {
+            if let Some(value) = option.strip_prefix("DEFAULT=") {
+                default = Some(unquote(value));
+            } else if let Some(value) = option.strip_prefix("OVERRIDE=") {
+                overridden = Some(unquote(value));
+            }
+        }
+
+        let value = overridden.filter(|value| !value.is_empty()).or(default);
+        if let Some(value) = value.filter(|value| !value.is_empty()) {
+            variables.push((String::from(key), String::from(value)));
+        }
+    }
+    LocaleConf(variables)
+}
+
+/// Returns `true` for values which refer to other variables, such as `${LANG}` or PAM's
+/// `@{HOME}`, which can't be expanded without the user's session.
+fn is_expansion(value: &str) -> bool {
+    value.contains("${") || value.contains("@{")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{home_directory, parse_pam_environment};
+    use crate::unix::{_get_locales, EnvAccess};
+    use std::path::PathBuf;
+
+    const PASSWD: &str = "root:x:0:0:root:/root:/bin/bash\n\
+                          # A comment\n\
+                          alice:x:1000:1000:Alice,,,:/home/alice:/bin/bash\n\
+                          nobody:x:65534:65534:nobody::/usr/sbin/nologin\n";
+
+    #[test]
+    fn passwd_home_directory() {
+        assert_eq!(home_directory(PASSWD, 0), Some(PathBuf::from("/root")));
+        assert_eq!(
+            home_directory(PASSWD, 1000),
+            Some(PathBuf::from("/home/alice"))
+        );
+        // Users without a home directory, or who aren't listed, aren't found.
+        assert_eq!(home_directory(PASSWD, 65534), None);
+        assert_eq!(home_directory(PASSWD, 1001), None);
+        assert_eq!(home_directory("not a passwd file", 0), None);
+    }
+
+    #[test]
+    fn pam_environment() {
+        let conf = parse_pam_environment(
+            "# Written by language-selector\n\
+             LANGUAGE\tDEFAULT=fr:en_US\n\
+             LANG DEFAULT=de_DE.UTF-8 OVERRIDE=\"fr_FR.UTF-8\"\n\
+             LC_TIME DEFAULT=en_GB.UTF-8 OVERRIDE=\n\
+             LC_PAPER=\"en_GB.UTF-8\"\n\
+             LC_NUMERIC\n",
+        );
+        assert_eq!(_get_locales(&conf), ["fr", "en-US", "fr-FR"]);
+        assert_eq!(
+            EnvAccess::get(&conf, "LC_TIME").as_deref(),
+            Some("en_GB.UTF-8")
+        );
+        assert_eq!(
+            EnvAccess::get(&conf, "LC_PAPER").as_deref(),
+            Some("en_GB.UTF-8")
+        );
+        assert_eq!(EnvAccess::get(&conf, "LC_NUMERIC"), None);
+
+        assert!(_get_locales(&parse_pam_environment("")).is_empty());
+    }
+
+    #[test]
+    fn expansions() {
+        assert!(super::is_expansion("${LANG}"));
+        assert!(super::is_expansion("@{HOME}/.locale"));
+        assert!(!super::is_expansion("sr_RS.UTF-8@latin"));
+    }
+}
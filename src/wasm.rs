@@ -1,56 +1,728 @@
-use alloc::string::String;
+//! Reads the locales in WebAssembly, with either the `js` or `web-sys` feature.
+//!
+//! The `js` feature supports every JavaScript runtime through `js-sys`, while the `web-sys`
+//! feature only reads the `navigator` of a browser window. When both are enabled, the `js`
+//! sources are used. CI runs the tests of each in Node.js and a headless browser, respectively.
+#[cfg(feature = "js")]
+use crate::posix::parse_locale_code;
+use crate::{
+    Calendar, CurrencyFormat, DateOrder, DateTimeSymbols, LocaleCategory, LocaleError,
+    LocaleSettings, LocaleSource, MeasurementSystem, NumberSeparators, PaperSize, PercentSymbol,
+    ProviderInfo, SignSymbols, Weekday,
+};
+use alloc::{borrow::Cow, string::String, vec, vec::Vec};
 
-use js_sys::{JsString, Object};
-use wasm_bindgen::{prelude::*, JsCast, JsValue};
+#[cfg(feature = "maximize")]
+use js_sys::Object;
+#[cfg(feature = "js")]
+use js_sys::{Array, Function, Reflect};
+#[cfg(feature = "js")]
+use wasm_bindgen::JsValue;
 
-#[derive(Clone)]
-enum GlobalType {
-    Window(web_sys::Window),
-    Worker(web_sys::WorkerGlobalScope),
+#[cfg(feature = "js")]
+const LC_ALL: &str = "LC_ALL";
+#[cfg(feature = "js")]
+const LANG: &str = "LANG";
+
+pub(crate) const SUPPORTED: bool = true;
+
+pub(crate) fn get() -> impl Iterator<Item = String> {
+    get_with_source().0
 }
 
-/// Returns a handle to the global scope object.
-///
-/// Simplified version of https://github.com/rustwasm/wasm-bindgen/blob/main/crates/js-sys/src/lib.rs,
-/// which we can't use directly because it discards information about how it
-/// retrieved the global.
-fn global() -> GlobalType {
-    #[wasm_bindgen]
-    extern "C" {
-        type Global;
-
-        #[wasm_bindgen(getter, catch, static_method_of = Global, js_class = window, js_name = window)]
-        fn get_window() -> Result<Object, JsValue>;
-
-        #[wasm_bindgen(getter, catch, static_method_of = Global, js_class = self, js_name = self)]
-        fn get_self() -> Result<Object, JsValue>;
-    }
-
-    if let Ok(window) = Global::get_window() {
-        GlobalType::Window(
-            window
-                .dyn_into::<web_sys::Window>()
-                .expect("expected window to be an instance of Window"),
-        )
-    } else if let Ok(worker) = Global::get_self() {
-        GlobalType::Worker(
-            worker
-                .dyn_into::<web_sys::WorkerGlobalScope>()
-                .expect("expected self to be an instance of WorkerGlobalScope"),
-        )
+/// The locales are copied out of JavaScript strings, so none can be borrowed.
+pub(crate) fn get_cow() -> impl Iterator<Item = Cow<'static, str>> {
+    get().map(Cow::Owned)
+}
+
+pub(crate) fn get_with_source() -> (impl Iterator<Item = String>, LocaleSource) {
+    let (locales, source) = read_with_source();
+
+    #[cfg(feature = "maximize")]
+    let locales = locales.map(maximize).collect::<Vec<_>>().into_iter();
+
+    (locales, source)
+}
+
+fn read_with_source() -> (vec::IntoIter<String>, LocaleSource) {
+    // The environment is detected at runtime, so that the same build works in every host.
+    // The locales come from the first of these sources which has any:
+    // 1. `navigator.languages`, which is available in browsers, web workers, Deno and Node.js 21+,
+    //    or `navigator.language` in older runtimes that only provide the primary language.
+    // 2. The default locale of `Intl`, which is available in every modern JavaScript runtime.
+    // 3. The `LC_ALL` or `LANG` environment variables, when running under Node.js.
+    //
+    // Only the first is available with the `web-sys` feature alone. With the
+    // `prefer-document-lang` feature, the `lang` of the document comes before all of them.
+    #[allow(unused_mut)]
+    let mut locales = navigator_languages();
+    #[cfg(feature = "prefer-document-lang")]
+    if prepend_document_lang(&mut locales) {
+        return (locales.into_iter(), LocaleSource::Application);
+    }
+    if !locales.is_empty() {
+        return (locales.into_iter(), LocaleSource::System);
+    }
+
+    read_fallback_with_source()
+}
+
+/// The `lang` of the document, with the `prefer-document-lang` feature.
+const DOCUMENT_LANG_SOURCE: &str = "document.documentElement.lang";
+const NAVIGATOR_SOURCE: &str = "navigator.languages";
+/// The default locale of `Intl`, with the `js` feature.
+const INTL_SOURCE: &str = "Intl.DateTimeFormat().resolvedOptions().locale";
+/// The environment variables of Node.js, with the `js` feature.
+const NODE_ENV_SOURCE: &str = "process.env (LC_ALL or LANG)";
+
+pub(crate) fn describe() -> ProviderInfo {
+    let mut sources = Vec::new();
+    if cfg!(feature = "prefer-document-lang") {
+        sources.push(DOCUMENT_LANG_SOURCE);
+    }
+    sources.push(NAVIGATOR_SOURCE);
+    if cfg!(feature = "js") {
+        sources.extend([INTL_SOURCE, NODE_ENV_SOURCE].iter());
+    }
+
+    // Each of the sources has its own `LocaleSource`.
+    let (mut locales, source) = read_with_source();
+    let active_source = locales.next().map(|_| match source {
+        LocaleSource::Application => DOCUMENT_LANG_SOURCE,
+        LocaleSource::System => NAVIGATOR_SOURCE,
+        LocaleSource::Fallback => INTL_SOURCE,
+        LocaleSource::Environment => NODE_ENV_SOURCE,
+    });
+
+    ProviderInfo {
+        name: "wasm",
+        sources,
+        active_source,
+    }
+}
+
+#[cfg(not(feature = "js"))]
+fn read_fallback_with_source() -> (vec::IntoIter<String>, LocaleSource) {
+    (Vec::new().into_iter(), LocaleSource::System)
+}
+
+#[cfg(feature = "js")]
+fn read_fallback_with_source() -> (vec::IntoIter<String>, LocaleSource) {
+    if let Some(locale) = intl_locale() {
+        return (vec![locale].into_iter(), LocaleSource::Fallback);
+    }
+
+    let locales = node_env_code()
+        .and_then(|code| parse_locale_code(&code))
+        .into_iter()
+        .collect::<Vec<_>>();
+    (locales.into_iter(), LocaleSource::Environment)
+}
+
+/// Returns the same locales as [`get`], but with the POSIX locale name from `process.env`
+/// instead of its language tag.
+pub(crate) fn get_raw() -> impl Iterator<Item = String> {
+    #[allow(unused_mut)]
+    let mut locales = navigator_languages();
+    #[cfg(feature = "prefer-document-lang")]
+    prepend_document_lang(&mut locales);
+
+    #[cfg(feature = "js")]
+    let locales = if locales.is_empty() {
+        intl_locale().or_else(node_env_code).into_iter().collect()
     } else {
-        panic!("Unable to find global in this environment")
+        locales
+    };
+
+    locales.into_iter()
+}
+
+/// Every source is optional, so there's nothing to report beyond the locales being missing.
+pub(crate) fn try_get() -> Result<impl Iterator<Item = String>, LocaleError> {
+    Ok(get())
+}
+
+#[cfg(all(feature = "js", feature = "std"))]
+std::thread_local! {
+    #[allow(unknown_lints, clippy::missing_const_for_thread_local)]
+    static GLOBAL: core::cell::RefCell<Option<JsValue>> = core::cell::RefCell::new(None);
+}
+
+/// Makes the locales be read from `global` instead of `globalThis`, until [`clear_js_global`]
+/// is called.
+///
+/// This is useful for testing, or in embeddings where the standard objects aren't on the global
+/// scope, since `navigator`, `Intl`, and `process` are all looked up as properties of `global`.
+/// Any of them which it doesn't have are treated as unavailable.
+///
+/// This is only available with the `js` and `std` features, and only applies to the current
+/// thread, which is the only one in most WebAssembly programs.
+#[cfg(all(feature = "js", feature = "std"))]
+pub fn set_js_global(global: JsValue) {
+    GLOBAL.with(|injected| *injected.borrow_mut() = Some(global));
+}
+
+/// Reverts [`set_js_global`], so that the locales are read from `globalThis` again.
+#[cfg(all(feature = "js", feature = "std"))]
+pub fn clear_js_global() {
+    GLOBAL.with(|injected| *injected.borrow_mut() = None);
+}
+
+/// Returns the object whose properties are the standard objects, such as `navigator`.
+#[cfg(feature = "js")]
+fn global() -> JsValue {
+    #[cfg(feature = "std")]
+    {
+        if let Some(global) = GLOBAL.with(|injected| injected.borrow().clone()) {
+            return global;
+        }
     }
+    js_sys::global().into()
 }
 
-pub(crate) fn get() -> impl Iterator<Item = String> {
-    let languages = match global() {
-        GlobalType::Window(window) => window.navigator().languages(),
-        GlobalType::Worker(worker) => worker.navigator().languages(),
+/// Reads a property of a JavaScript object, returning `None` if it's `undefined` or `null`,
+/// or if `target` isn't an object.
+#[cfg(feature = "js")]
+fn property(target: &JsValue, key: &str) -> Option<JsValue> {
+    if !target.is_object() {
+        return None;
+    }
+    Reflect::get(target, &JsValue::from_str(key))
+        .ok()
+        .filter(|value| !value.is_undefined() && !value.is_null())
+}
+
+/// Returns `navigator.languages` of the global scope, such as the `window` or `self` of a worker,
+/// in the user's order of preference.
+///
+/// If the list is unavailable or empty, this falls back to the single `navigator.language`.
+#[cfg(feature = "js")]
+fn navigator_languages() -> Vec<String> {
+    let navigator = match property(&global(), "navigator") {
+        Some(navigator) => navigator,
+        None => return Vec::new(),
     };
-    languages
-        .values()
+
+    let languages = property(&navigator, "languages")
+        .filter(Array::is_array)
+        .map(|languages| Array::from(&languages))
+        .iter()
+        .flat_map(Array::iter)
+        .filter_map(|language| language.as_string())
+        .filter(|language| !language.is_empty())
+        .collect::<Vec<_>>();
+    if !languages.is_empty() {
+        return languages;
+    }
+
+    property(&navigator, "language")
+        .and_then(|language| language.as_string())
+        .filter(|language| !language.is_empty())
         .into_iter()
-        .flat_map(|v| v.and_then(|v| v.dyn_into::<JsString>()))
-        .map(String::from)
+        .collect()
+}
+
+/// Returns `navigator.languages` of the browser window through `web-sys`, in the user's order
+/// of preference.
+///
+/// If the list is empty, this falls back to the single `navigator.language`. There's no window
+/// in web workers or outside of browsers, so nothing is returned there.
+#[cfg(not(feature = "js"))]
+fn navigator_languages() -> Vec<String> {
+    let navigator = match web_sys::window() {
+        Some(window) => window.navigator(),
+        None => return Vec::new(),
+    };
+
+    let languages = navigator
+        .languages()
+        .iter()
+        .filter_map(|language| language.as_string())
+        .filter(|language| !language.is_empty())
+        .collect::<Vec<_>>();
+    if !languages.is_empty() {
+        return languages;
+    }
+
+    navigator
+        .language()
+        .filter(|language| !language.is_empty())
+        .into_iter()
+        .collect()
+}
+
+/// Adds the `lang` of the document to the start of `locales`, removing it from anywhere else,
+/// and returns whether it was added.
+///
+/// A site sets this for the language of its content, such as `<html lang="de">`, so it's more
+/// authoritative than the browser's preferences.
+#[cfg(feature = "prefer-document-lang")]
+fn prepend_document_lang(locales: &mut Vec<String>) -> bool {
+    let lang = match document_lang() {
+        Some(lang) => lang,
+        None => return false,
+    };
+    locales.retain(|locale| !locale.eq_ignore_ascii_case(&lang));
+    locales.insert(0, lang);
+    true
+}
+
+/// Returns `document.documentElement.lang`, if it's set to anything other than whitespace.
+///
+/// There's no document in web workers or outside of browsers, so nothing is returned there.
+#[cfg(all(feature = "prefer-document-lang", feature = "js"))]
+fn document_lang() -> Option<String> {
+    let element = property(&property(&global(), "document")?, "documentElement")?;
+    let lang = property(&element, "lang")?.as_string()?;
+    non_blank(&lang)
+}
+
+/// Returns the `lang` attribute of the browser window's document element through `web-sys`, if
+/// it's set to anything other than whitespace.
+#[cfg(all(feature = "prefer-document-lang", not(feature = "js")))]
+fn document_lang() -> Option<String> {
+    let element = web_sys::window()?.document()?.document_element()?;
+    non_blank(&element.get_attribute("lang")?)
+}
+
+#[cfg(feature = "prefer-document-lang")]
+fn non_blank(lang: &str) -> Option<String> {
+    let lang = lang.trim();
+    if lang.is_empty() {
+        None
+    } else {
+        Some(String::from(lang))
+    }
+}
+
+/// Returns the default locale of the JavaScript runtime, from `Intl.DateTimeFormat().resolvedOptions()`.
+#[cfg(feature = "js")]
+fn intl_locale() -> Option<String> {
+    property(&intl_resolved_options()?, "locale")?.as_string()
+}
+
+/// Returns the default calendar of the JavaScript runtime, such as `buddhist`, from
+/// `Intl.DateTimeFormat().resolvedOptions()`.
+#[cfg(feature = "js")]
+fn intl_calendar() -> Option<String> {
+    property(&intl_resolved_options()?, "calendar")?.as_string()
+}
+
+/// Returns the default numbering system of the JavaScript runtime, such as `arab`, from
+/// `Intl.DateTimeFormat().resolvedOptions()`.
+#[cfg(feature = "js")]
+fn intl_numbering_system() -> Option<String> {
+    property(&intl_resolved_options()?, "numberingSystem")?.as_string()
+}
+
+/// Returns the options which `Intl.DateTimeFormat` resolves by default.
+#[cfg(feature = "js")]
+fn intl_resolved_options() -> Option<JsValue> {
+    // Some runtimes can be built without `Intl`, such as Node.js with `--without-intl`.
+    let constructor = property(&global(), "Intl")
+        .and_then(|intl| property(&intl, "DateTimeFormat"))
+        .filter(JsValue::is_function)
+        .map(Function::from)?;
+
+    let format = Reflect::construct(&constructor, &Array::new()).ok()?;
+    let resolved_options = property(&format, "resolvedOptions")
+        .filter(JsValue::is_function)
+        .map(Function::from)?;
+    resolved_options.call0(&format).ok()
+}
+
+/// Adds the likely script and region to `tag` with `new Intl.Locale(tag).maximize()`, so that
+/// `zh-TW` becomes `zh-Hant-TW`.
+///
+/// The tag is returned unchanged if the runtime doesn't support `Intl.Locale`, or if it
+/// rejects the tag.
+#[cfg(feature = "maximize")]
+fn maximize(tag: String) -> String {
+    let constructor = property(&global(), "Intl")
+        .and_then(|intl| property(&intl, "Locale"))
+        .filter(JsValue::is_function)
+        .map(Function::from);
+    let constructor = match constructor {
+        Some(constructor) => constructor,
+        None => return tag,
+    };
+
+    let args = Array::of1(&JsValue::from_str(&tag));
+    let maximized = Reflect::construct(&constructor, &args)
+        .ok()
+        .and_then(|locale| {
+            let maximize = Function::from(property(&locale, "maximize")?);
+            maximize.call0(&locale).ok()
+        })
+        .map(|locale| String::from(Object::from(locale).to_string()));
+    maximized.unwrap_or(tag)
+}
+
+/// Returns the POSIX locale name from `process.env` when running under Node.js.
+#[cfg(feature = "js")]
+fn node_env_code() -> Option<String> {
+    let env = property(&global(), "process").and_then(|process| property(&process, "env"))?;
+
+    [LC_ALL, LANG]
+        .iter()
+        .filter_map(|key| property(&env, key)?.as_string())
+        .find(|code| !code.is_empty())
+}
+
+/// With the `maximize` feature, the likely region is inferred when the preferred locale doesn't
+/// have one, so that `de` has the region `DE`.
+pub(crate) fn get_region() -> Option<String> {
+    let locale = read_with_source().0.next()?;
+    let region = crate::region_from_tag(&locale);
+
+    #[cfg(feature = "maximize")]
+    let region = region.or_else(|| crate::region_from_tag(&maximize(locale)));
+
+    region
+}
+
+/// The web doesn't configure formatting separately from the language.
+pub(crate) fn get_format_locale() -> Option<String> {
+    get().next()
+}
+
+/// The default locale of `Intl`, which is the only default that JavaScript runtimes provide.
+#[cfg(feature = "js")]
+pub(crate) fn get_default_locale() -> Option<String> {
+    intl_locale()
+}
+
+#[cfg(not(feature = "js"))]
+pub(crate) fn get_default_locale() -> Option<String> {
+    None
+}
+
+/// Only the language is configured separately from the formatting locale.
+pub(crate) fn get_locale_for(category: LocaleCategory) -> Option<String> {
+    match category {
+        LocaleCategory::Messages => get().next(),
+        _ => get_format_locale(),
+    }
+}
+
+pub(crate) fn get_all_categories() -> Vec<(LocaleCategory, Option<String>)> {
+    LocaleCategory::ALL
+        .iter()
+        .map(|&category| (category, get_locale_for(category)))
+        .collect()
+}
+
+/// Sorting isn't configured separately, so this follows the formatting locale.
+pub(crate) fn get_collation_locale() -> Option<String> {
+    get_format_locale()
+}
+
+pub(crate) fn get_measurement_system() -> Option<MeasurementSystem> {
+    get_region().map(|region| MeasurementSystem::for_region(&region))
+}
+
+pub(crate) fn get_paper_size() -> Option<PaperSize> {
+    get_region().map(|region| PaperSize::for_region(&region))
+}
+
+pub(crate) fn get_number_separators() -> Option<NumberSeparators> {
+    None
+}
+
+pub(crate) fn get_grouping_sizes() -> Option<Vec<u8>> {
+    None
+}
+
+pub(crate) fn get_list_separator() -> Option<char> {
+    None
+}
+
+pub(crate) fn get_first_day_of_week() -> Option<Weekday> {
+    None
+}
+
+pub(crate) fn get_weekend_days() -> Option<(Weekday, Weekday)> {
+    get_region().map(|region| Weekday::weekend_for_region(&region))
+}
+
+pub(crate) fn uses_24_hour_time() -> Option<bool> {
+    None
+}
+
+pub(crate) fn get_date_field_order() -> Option<DateOrder> {
+    None
+}
+
+pub(crate) fn get_calendar() -> Option<Calendar> {
+    #[cfg(feature = "js")]
+    {
+        if let Some(calendar) = intl_calendar() {
+            return Calendar::from_identifier(&calendar);
+        }
+    }
+
+    // Otherwise, it's the default of the locale's region.
+    get()
+        .next()
+        .and_then(|locale| crate::calendar_from_tag(&locale))
+        .or_else(|| get_region().map(|region| Calendar::for_region(&region)))
+}
+
+pub(crate) fn get_numbering_system() -> Option<String> {
+    #[cfg(feature = "js")]
+    {
+        if let Some(system) = intl_numbering_system() {
+            return Some(system);
+        }
+    }
+
+    get()
+        .next()
+        .and_then(|locale| crate::numbering_system_from_tag(&locale))
+}
+
+pub(crate) fn get_locale_settings() -> LocaleSettings {
+    LocaleSettings {
+        region: get_region(),
+        measurement_system: get_measurement_system(),
+        currency_code: get_currency_code(),
+        first_day_of_week: get_first_day_of_week(),
+        uses_24_hour_time: uses_24_hour_time(),
+        number_separators: get_number_separators(),
+        ..LocaleSettings::default()
+    }
+}
+
+pub(crate) fn get_datetime_symbols() -> Option<DateTimeSymbols> {
+    None
+}
+
+pub(crate) fn get_currency_code() -> Option<String> {
+    None
+}
+
+pub(crate) fn get_currency_format() -> Option<CurrencyFormat> {
+    None
+}
+
+pub(crate) fn get_percent_symbol() -> Option<PercentSymbol> {
+    None
+}
+
+pub(crate) fn get_sign_symbols() -> Option<SignSymbols> {
+    None
+}
+
+/// The time zone is only available through `Intl`, so it needs the `js` feature.
+#[cfg(feature = "time-zone")]
+pub(crate) fn get_time_zone() -> Option<String> {
+    #[cfg(feature = "js")]
+    {
+        property(&intl_resolved_options()?, "timeZone")?.as_string()
+    }
+    #[cfg(not(feature = "js"))]
+    {
+        None
+    }
+}
+
+pub(crate) fn display_name_of(_tag: &str, _in_locale: Option<&str>) -> Option<String> {
+    None
+}
+
+pub(crate) fn native_name_of(_tag: &str) -> Option<String> {
+    None
+}
+
+pub(crate) fn is_locale_available(_tag: &str) -> Option<bool> {
+    None
+}
+
+pub(crate) fn get_available_locales() -> Vec<String> {
+    Vec::new()
+}
+
+pub(crate) fn get_locale_display_name() -> Option<String> {
+    None
+}
+
+/// Locale changes can't be watched on this platform.
+#[cfg(feature = "notify")]
+pub(crate) enum Watcher {}
+
+#[cfg(feature = "notify")]
+pub(crate) fn watch(_callback: alloc::boxed::Box<dyn Fn() + Send + Sync>) -> Option<Watcher> {
+    None
+}
+
+#[cfg(all(test, feature = "js"))]
+mod tests {
+    use super::get;
+    use alloc::vec::Vec;
+    use js_sys::{Array, Object, Reflect};
+    use wasm_bindgen::JsValue;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    /// Replaces `globalThis.navigator` with `navigator` while `f` runs, or removes it if `None`.
+    fn with_navigator(navigator: Option<&Object>, f: impl FnOnce()) {
+        with_global("navigator", navigator, f)
+    }
+
+    /// Replaces the property `key` of `globalThis` with `value` while `f` runs, or removes it if
+    /// `None`.
+    fn with_global(key: &str, value: Option<&Object>, f: impl FnOnce()) {
+        let global = js_sys::global();
+        let key = JsValue::from_str(key);
+        let original = Object::get_own_property_descriptor(&global, &key);
+
+        match value {
+            Some(value) => {
+                let descriptor = Object::new();
+                Reflect::set(&descriptor, &"value".into(), value).unwrap();
+                Reflect::set(&descriptor, &"configurable".into(), &JsValue::TRUE).unwrap();
+                Object::define_property(&global, &key, &descriptor);
+            }
+            None => {
+                Reflect::delete_property(&global, &key).unwrap();
+            }
+        }
+
+        f();
+
+        if original.is_undefined() {
+            Reflect::delete_property(&global, &key).unwrap();
+        } else {
+            Object::define_property(&global, &key, &original.into());
+        }
+    }
+
+    fn navigator(languages: Option<&[&str]>, language: Option<&str>) -> Object {
+        let navigator = Object::new();
+        if let Some(languages) = languages {
+            let languages = languages
+                .iter()
+                .map(|l| JsValue::from_str(l))
+                .collect::<Array>();
+            Reflect::set(&navigator, &"languages".into(), &languages).unwrap();
+        }
+        if let Some(language) = language {
+            Reflect::set(&navigator, &"language".into(), &language.into()).unwrap();
+        }
+        navigator
+    }
+
+    #[wasm_bindgen_test]
+    #[cfg(not(feature = "maximize"))]
+    fn navigator_languages_in_order() {
+        let languages = ["fr-CA", "fr", "en-US"];
+        with_navigator(Some(&navigator(Some(&languages), Some("fr-CA"))), || {
+            assert_eq!(get().collect::<Vec<_>>(), languages);
+        });
+    }
+
+    #[wasm_bindgen_test]
+    #[cfg(all(feature = "prefer-document-lang", not(feature = "maximize")))]
+    fn document_lang_first() {
+        use super::get_with_source;
+        use crate::LocaleSource;
+
+        let document = |lang: &str| {
+            let element = Object::new();
+            Reflect::set(&element, &"lang".into(), &lang.into()).unwrap();
+            let document = Object::new();
+            Reflect::set(&document, &"documentElement".into(), &element).unwrap();
+            document
+        };
+
+        let languages = ["fr-CA", "de", "en-US"];
+        with_navigator(Some(&navigator(Some(&languages), None)), || {
+            with_global("document", Some(&document("de")), || {
+                let (locales, source) = get_with_source();
+                assert_eq!(locales.collect::<Vec<_>>(), ["de", "fr-CA", "en-US"]);
+                assert_eq!(source, LocaleSource::Application);
+            });
+
+            // An empty or missing `lang` falls back to the browser's preferences.
+            with_global("document", Some(&document(" ")), || {
+                assert_eq!(get().collect::<Vec<_>>(), languages);
+            });
+            with_global("document", Some(&Object::new()), || {
+                assert_eq!(get().collect::<Vec<_>>(), languages);
+            });
+            with_global("document", None, || {
+                assert_eq!(get_with_source().1, LocaleSource::System);
+            });
+        });
+    }
+
+    #[wasm_bindgen_test]
+    #[cfg(not(feature = "maximize"))]
+    fn navigator_language_fallback() {
+        with_navigator(Some(&navigator(None, Some("de-DE"))), || {
+            assert_eq!(get().collect::<Vec<_>>(), ["de-DE"]);
+        });
+
+        with_navigator(Some(&navigator(Some(&[]), Some("es"))), || {
+            assert_eq!(get().collect::<Vec<_>>(), ["es"]);
+        });
+    }
+
+    #[wasm_bindgen_test]
+    #[cfg(feature = "maximize")]
+    fn maximized_languages() {
+        let languages = ["zh-TW", "en", "not a tag"];
+        with_navigator(Some(&navigator(Some(&languages), None)), || {
+            assert_eq!(
+                get().collect::<Vec<_>>(),
+                ["zh-Hant-TW", "en-Latn-US", "not a tag"]
+            );
+        });
+    }
+
+    #[wasm_bindgen_test]
+    #[cfg(feature = "maximize")]
+    fn maximized_region() {
+        use super::get_region;
+
+        with_navigator(Some(&navigator(Some(&["de", "en-GB"]), None)), || {
+            assert_eq!(get_region().as_deref(), Some("DE"));
+        });
+
+        // A region in the tag itself is kept, even if it isn't the likely one.
+        with_navigator(Some(&navigator(Some(&["de-CH"]), None)), || {
+            assert_eq!(get_region().as_deref(), Some("CH"));
+        });
+    }
+
+    #[wasm_bindgen_test]
+    #[cfg(feature = "std")]
+    fn injected_global() {
+        use super::{clear_js_global, set_js_global};
+
+        let global = Object::new();
+        let navigator = navigator(Some(&["nl-BE", "fr-BE"]), None);
+        Reflect::set(&global, &"navigator".into(), &navigator).unwrap();
+        set_js_global(global.into());
+        let locales = get().collect::<Vec<_>>();
+
+        // Without `navigator`, `Intl`, or `process`, there are no locales at all.
+        set_js_global(Object::new().into());
+        let missing = get().count();
+        clear_js_global();
+
+        assert_eq!(locales, ["nl-BE", "fr-BE"]);
+        assert_eq!(missing, 0);
+    }
+
+    #[wasm_bindgen_test]
+    #[cfg(not(feature = "maximize"))]
+    fn intl_fallback_without_navigator() {
+        use super::intl_locale;
+
+        // Some workers and server-side rendering runtimes don't have a `navigator` at all.
+        with_navigator(None, || {
+            let locale = intl_locale().expect("`Intl` should provide a default locale");
+            assert_eq!(get().collect::<Vec<_>>(), [locale]);
+        });
+    }
 }
@@ -1,47 +1,1139 @@
-use alloc::{string::String, vec::Vec};
-use windows_sys::Win32::Foundation::TRUE;
-use windows_sys::Win32::Globalization::{GetUserPreferredUILanguages, MUI_LANGUAGE_NAME};
+use crate::{
+    Calendar, CurrencyFormat, DateOrder, DateTimeSymbols, LocaleCategory, LocaleError,
+    LocaleSettings, LocaleSource, MeasurementSystem, NumberSeparators, PaperSize, PercentSymbol,
+    ProviderInfo, SignSymbols, Weekday,
+};
+use alloc::{borrow::Cow, string::String, vec, vec::Vec};
+use windows_sys::core::PCWSTR;
+use windows_sys::Win32::Foundation::{
+    GetLastError, BOOL, ERROR_SUCCESS, HANDLE, LPARAM, TRUE, WIN32_ERROR,
+};
+use windows_sys::Win32::Globalization::{
+    EnumSystemLocalesEx, GetLocaleInfoEx, GetSystemDefaultLocaleName, GetUserDefaultGeoName,
+    GetUserDefaultLocaleName, GetUserPreferredUILanguages, IsValidLocaleName, LCIDToLocaleName,
+    CAL_GREGORIAN, CAL_GREGORIAN_ARABIC, CAL_GREGORIAN_ME_FRENCH, CAL_GREGORIAN_US,
+    CAL_GREGORIAN_XLIT_ENGLISH, CAL_GREGORIAN_XLIT_FRENCH, CAL_HEBREW, CAL_HIJRI, CAL_JAPAN,
+    CAL_PERSIAN, CAL_TAIWAN, CAL_THAI, CAL_UMALQURA, LOCALE_ALLOW_NEUTRAL_NAMES,
+    LOCALE_ICALENDARTYPE, LOCALE_IDATE, LOCALE_IDIGITSUBSTITUTION, LOCALE_IFIRSTDAYOFWEEK,
+    LOCALE_IMEASURE, LOCALE_IPAPERSIZE, LOCALE_IPOSITIVEPERCENT, LOCALE_NOUSEROVERRIDE,
+    LOCALE_S1159, LOCALE_S2359, LOCALE_SDATE, LOCALE_SDECIMAL, LOCALE_SENGLISHDISPLAYNAME,
+    LOCALE_SGROUPING, LOCALE_SINTLSYMBOL, LOCALE_SLIST, LOCALE_SLOCALIZEDDISPLAYNAME,
+    LOCALE_SNATIVEDIGITS, LOCALE_SNATIVEDISPLAYNAME, LOCALE_SNATIVELANGUAGENAME,
+    LOCALE_SNEGATIVESIGN, LOCALE_SPERCENT, LOCALE_SPERMILLE, LOCALE_SPOSITIVESIGN,
+    LOCALE_SSHORTDATE, LOCALE_STHOUSAND, LOCALE_STIME, LOCALE_STIMEFORMAT, LOCALE_SUPPLEMENTAL,
+    LOCALE_WINDOWS, MUI_LANGUAGE_NAME,
+};
+use windows_sys::Win32::Security::{ImpersonateLoggedOnUser, RevertToSelf};
+use windows_sys::Win32::System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_MULTI_SZ};
+use windows_sys::Win32::System::Threading::{GetCurrentProcess, TerminateProcess};
+
+#[cfg(feature = "time-zone")]
+mod time_zone;
+#[cfg(feature = "notify")]
+mod watcher;
+#[cfg(feature = "notify")]
+pub(crate) use watcher::{watch, Watcher};
+
+/// The maximum length of a locale name, including the null terminator.
+const LOCALE_NAME_MAX_LENGTH: usize = 85;
+
+pub(crate) const SUPPORTED: bool = true;
+
+/// How the `_with_options` functions read the format settings of the user's locale, such as
+/// `get_number_separators_with_options`.
+///
+/// The default reads them in the same way as the functions without options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FormatOptions {
+    /// Includes the user's customizations from the Region settings, such as a different decimal
+    /// separator. Without them, the settings are the defaults of the user's locale.
+    pub user_overrides: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> FormatOptions {
+        FormatOptions {
+            user_overrides: true,
+        }
+    }
+}
 
-#[allow(clippy::as_conversions)]
 pub(crate) fn get() -> impl Iterator<Item = String> {
+    try_get().unwrap_or_else(|_| MultiStringIter::new(Vec::new()))
+}
+
+/// The languages are decoded from UTF-16, so none can be borrowed.
+pub(crate) fn get_cow() -> impl Iterator<Item = Cow<'static, str>> {
+    get().map(Cow::Owned)
+}
+
+/// The UI languages are always the current user's preferences for the whole system.
+pub(crate) fn get_with_source() -> (impl Iterator<Item = String>, LocaleSource) {
+    (get(), LocaleSource::System)
+}
+
+/// The UI languages are already BCP-47 language tags, so they're returned as-is.
+pub(crate) fn get_raw() -> impl Iterator<Item = String> {
+    get()
+}
+
+pub(crate) fn try_get() -> Result<MultiStringIter, LocaleError> {
+    let languages = preferred_ui_languages();
+    debug!(
+        "GetUserPreferredUILanguages returned {:?}",
+        languages
+            .as_ref()
+            .map(|languages| languages.clone().collect::<Vec<_>>())
+    );
+
+    with_registry_fallback(languages, || {
+        let languages = registry_languages();
+        debug!(
+            "Read the languages {:?} from the registry",
+            languages.clone().collect::<Vec<_>>()
+        );
+        languages
+    })
+}
+
+/// The API which the UI languages are read from.
+const PREFERRED_UI_LANGUAGES_SOURCE: &str = "GetUserPreferredUILanguages";
+/// The registry value which the UI languages are read from if the API doesn't return any.
+const REGISTRY_LANGUAGES_SOURCE: &str =
+    "HKEY_CURRENT_USER\\Control Panel\\International\\User Profile\\Languages";
+
+pub(crate) fn describe() -> ProviderInfo {
+    let active_source = match preferred_ui_languages() {
+        Ok(languages) if !languages.is_empty() => Some(PREFERRED_UI_LANGUAGES_SOURCE),
+        _ if !registry_languages().is_empty() => Some(REGISTRY_LANGUAGES_SOURCE),
+        _ => None,
+    };
+
+    ProviderInfo {
+        name: "windows",
+        sources: vec![PREFERRED_UI_LANGUAGES_SOURCE, REGISTRY_LANGUAGES_SOURCE],
+        active_source,
+    }
+}
+
+/// Returns `languages` if it has any, or otherwise the languages from `fallback`.
+///
+/// If neither has any languages, the original result is returned so that its error isn't lost.
+fn with_registry_fallback(
+    languages: Result<MultiStringIter, LocaleError>,
+    fallback: impl FnOnce() -> MultiStringIter,
+) -> Result<MultiStringIter, LocaleError> {
+    match languages {
+        Ok(languages) if !languages.is_empty() => Ok(languages),
+        languages => {
+            let fallback = fallback();
+            if fallback.is_empty() {
+                languages
+            } else {
+                Ok(fallback)
+            }
+        }
+    }
+}
+
+/// Reads the user's display languages, which are the languages of the UI's text rather than the
+/// region used for formatting. `MUI_LANGUAGE_NAME` returns them as names such as `en-US`, rather
+/// than as the hexadecimal LCIDs returned with `MUI_LANGUAGE_ID`.
+fn preferred_ui_languages() -> Result<MultiStringIter, LocaleError> {
+    // SAFETY: The pointers are valid, and the buffer is either null or as long as `buffer_length`.
+    read_multi_string(|num_languages, buffer, buffer_length| unsafe {
+        GetUserPreferredUILanguages(MUI_LANGUAGE_NAME, num_languages, buffer, buffer_length)
+    })
+}
+
+/// Reads the user's languages from `HKCU\Control Panel\International\User Profile\Languages`,
+/// for when `GetUserPreferredUILanguages` fails or returns nothing, as it can for some
+/// service accounts.
+///
+/// The list is empty if the value doesn't exist or can't be read.
+fn registry_languages() -> MultiStringIter {
+    let key = windows_sys::w!("Control Panel\\International\\User Profile");
+    let value = windows_sys::w!("Languages");
+
+    // SAFETY: The key and value are null-terminated, and the buffer is either null or as
+    // long as `size` in bytes.
+    read_registry_multi_string(|data, size| unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            key,
+            value,
+            RRF_RT_REG_MULTI_SZ,
+            core::ptr::null_mut(),
+            data,
+            size,
+        )
+    })
+}
+
+/// Reads a list of names from a function with the same signature as `RegGetValueW` for a
+/// `REG_MULTI_SZ` value, where the size of the buffer is in bytes.
+#[allow(clippy::as_conversions)]
+fn read_registry_multi_string(
+    mut read: impl FnMut(*mut core::ffi::c_void, *mut u32) -> WIN32_ERROR,
+) -> MultiStringIter {
+    let mut size: u32 = 0;
+
+    // Calling this with a null buffer will retrieve the required size
+    if read(core::ptr::null_mut(), &mut size) != ERROR_SUCCESS {
+        return MultiStringIter::new(Vec::new());
+    }
+
+    // The size of a `REG_MULTI_SZ` is always a whole number of UTF-16 chars.
+    let mut buffer = vec![0u16; size as usize / 2];
+    let mut size = (buffer.len() * 2) as u32;
+    if read(buffer.as_mut_ptr().cast(), &mut size) != ERROR_SUCCESS {
+        return MultiStringIter::new(Vec::new());
+    }
+
+    buffer.truncate(size as usize / 2);
+    MultiStringIter::new(buffer)
+}
+
+/// Reads the preferred UI languages of the user which `token` belongs to, by impersonating
+/// them on the current thread while the languages are read.
+///
+/// # Safety
+///
+/// `token` must be a valid access token handle, which stays open until this returns.
+pub(crate) unsafe fn try_get_for_token(token: HANDLE) -> Result<MultiStringIter, LocaleError> {
+    if ImpersonateLoggedOnUser(token) != TRUE {
+        return Err(last_error());
+    }
+
+    let impersonation = Impersonation;
+
+    // The registry fallback isn't used, as `HKEY_CURRENT_USER` is opened for the user which
+    // the process runs as, rather than the one being impersonated.
+    let languages = preferred_ui_languages();
+
+    drop(impersonation);
+    languages
+}
+
+/// Returns the current thread to its own security context when it's dropped, including while
+/// unwinding.
+struct Impersonation;
+
+impl Drop for Impersonation {
+    fn drop(&mut self) {
+        // SAFETY: This function is safe to call and has no invariants.
+        if unsafe { RevertToSelf() } != TRUE {
+            // Carrying on as the impersonated user would run the caller's code with the wrong
+            // privileges. A panic could be caught, so the process is stopped without unwinding,
+            // the same as `std::process::abort`, which isn't available without `std`.
+            #[cfg(feature = "log")]
+            log::error!(
+                "Failed to revert the impersonation of a user: {}",
+                last_error()
+            );
+            #[cfg(feature = "tracing")]
+            tracing::error!(
+                "Failed to revert the impersonation of a user: {}",
+                last_error()
+            );
+            // SAFETY: The pseudo handle of the current process is always valid.
+            unsafe { TerminateProcess(GetCurrentProcess(), 3) };
+            unreachable!("the process was terminated");
+        }
+    }
+}
+
+/// Reads a list of names from a function with the same signature as `GetUserPreferredUILanguages`,
+/// which writes them into a buffer of null-separated UTF-16 strings.
+#[allow(clippy::as_conversions)]
+fn read_multi_string(
+    mut read: impl FnMut(*mut u32, *mut u16, *mut u32) -> BOOL,
+) -> Result<MultiStringIter, LocaleError> {
     let mut num_languages: u32 = 0;
     let mut buffer_length: u32 = 0;
 
     // Calling this with null buffer will retrieve the required buffer length
-    let success = unsafe {
-        GetUserPreferredUILanguages(
-            MUI_LANGUAGE_NAME,
-            &mut num_languages,
-            core::ptr::null_mut(),
-            &mut buffer_length,
-        )
-    } == TRUE;
-    if !success {
-        return Vec::new().into_iter();
+    if read(
+        &mut num_languages,
+        core::ptr::null_mut(),
+        &mut buffer_length,
+    ) != TRUE
+    {
+        return Err(last_error());
     }
 
-    let mut buffer = Vec::<u16>::new();
-    buffer.resize(buffer_length as usize, 0);
+    let mut buffer = vec![0u16; buffer_length as usize];
 
     // Now that we have an appropriate buffer, we can query the names
-    let mut result = Vec::with_capacity(num_languages as usize);
-    let success = unsafe {
-        GetUserPreferredUILanguages(
-            MUI_LANGUAGE_NAME,
-            &mut num_languages,
-            buffer.as_mut_ptr(),
-            &mut buffer_length,
-        )
-    } == TRUE;
+    if read(&mut num_languages, buffer.as_mut_ptr(), &mut buffer_length) != TRUE {
+        return Err(last_error());
+    }
+
+    Ok(MultiStringIter::new(buffer))
+}
+
+/// Returns the error of the last failed Windows API call on this thread.
+#[allow(clippy::as_conversions)]
+fn last_error() -> LocaleError {
+    // SAFETY: This function is safe to call and has no invariants.
+    let code = unsafe { GetLastError() };
+    LocaleError::PlatformError { code: code as i32 }
+}
+
+/// Lazily decodes each name in a buffer of UTF-16 strings, which are split by null
+/// chars (0) and end with two null chars (00).
+#[derive(Clone)]
+pub(crate) struct MultiStringIter {
+    buffer: Vec<u16>,
+    position: usize,
+}
+
+impl MultiStringIter {
+    fn new(mut buffer: Vec<u16>) -> Self {
+        // The names end at the first pair of null chars, and anything after it isn't a name.
+        if let Some(end) = buffer.windows(2).position(|pair| pair == [0, 0]) {
+            buffer.truncate(end);
+        }
+        Self {
+            buffer,
+            position: 0,
+        }
+    }
+
+    /// Returns `true` if there are no more names, without decoding them.
+    fn is_empty(&self) -> bool {
+        let rest = self.buffer.get(self.position..).unwrap_or_default();
+        rest.iter().all(|&c| c == 0)
+    }
+}
 
-    if success {
-        // The buffer contains names split by null char (0), and ends with two null chars (00)
-        for part in buffer.split(|i| *i == 0).filter(|p| !p.is_empty()) {
+impl Iterator for MultiStringIter {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        while let Some(rest) = self.buffer.get(self.position..) {
+            if rest.is_empty() {
+                break;
+            }
+
+            let len = rest.iter().position(|c| *c == 0).unwrap_or(rest.len());
+            let part = &rest[..len];
+            self.position += len + 1;
+
+            if part.is_empty() {
+                continue;
+            }
+            // Names which aren't valid UTF-16, such as from corrupted registry data with an
+            // unpaired surrogate, are skipped rather than being decoded lossily.
             if let Ok(locale) = String::from_utf16(part) {
-                result.push(locale);
+                return Some(locale);
+            }
+        }
+
+        None
+    }
+}
+
+#[allow(clippy::as_conversions)]
+pub(crate) fn get_region() -> Option<String> {
+    // Calling this with null buffer will retrieve the required buffer length
+    let buffer_length = unsafe { GetUserDefaultGeoName(core::ptr::null_mut(), 0) };
+    if buffer_length <= 0 {
+        return None;
+    }
+
+    let mut buffer = vec![0u16; buffer_length as usize];
+    let written = unsafe { GetUserDefaultGeoName(buffer.as_mut_ptr(), buffer_length) };
+    if written <= 0 {
+        return None;
+    }
+
+    // The written length includes the null terminator.
+    buffer.truncate(written as usize - 1);
+
+    // The geo name may also be a UN M.49 numeric code, such as `001` for "World",
+    // which isn't a country.
+    String::from_utf16(&buffer)
+        .ok()
+        .and_then(|name| crate::region_code(&name))
+}
+
+/// Returns the locale used for regional formatting, such as numbers and dates.
+///
+/// This is selected separately from the UI languages returned by [`get`].
+pub(crate) fn get_format_locale() -> Option<String> {
+    get_user_locale()
+}
+
+pub(crate) fn get_default_locale() -> Option<String> {
+    get_user_locale()
+}
+
+/// Returns the current user's default locale (`GetUserDefaultLocaleName`).
+pub(crate) fn get_user_locale() -> Option<String> {
+    // SAFETY: The buffer is writable and its length is correctly passed.
+    read_locale_name(|buffer, len| unsafe { GetUserDefaultLocaleName(buffer, len) })
+}
+
+/// Converts a locale identifier (LCID), such as `0x0409`, into a BCP-47 language tag with
+/// `LCIDToLocaleName`. Neutral locales, such as `0x0009`, become a tag with only a language.
+pub(crate) fn lcid_to_bcp47(lcid: u32) -> Option<String> {
+    // SAFETY: The buffer is writable and its length is correctly passed.
+    read_locale_name(|buffer, len| unsafe {
+        LCIDToLocaleName(lcid, buffer, len, LOCALE_ALLOW_NEUTRAL_NAMES)
+    })
+}
+
+/// Returns the default locale of the operating system (`GetSystemDefaultLocaleName`), which is
+/// shared by every user and also used by services running under a system account.
+pub(crate) fn get_system_locale() -> Option<String> {
+    // SAFETY: The buffer is writable and its length is correctly passed.
+    read_locale_name(|buffer, len| unsafe { GetSystemDefaultLocaleName(buffer, len) })
+}
+
+/// Only the language is configured separately from the formatting locale.
+pub(crate) fn get_locale_for(category: LocaleCategory) -> Option<String> {
+    match category {
+        LocaleCategory::Messages => get().next(),
+        _ => get_format_locale(),
+    }
+}
+
+pub(crate) fn get_all_categories() -> Vec<(LocaleCategory, Option<String>)> {
+    LocaleCategory::ALL
+        .iter()
+        .map(|&category| (category, get_locale_for(category)))
+        .collect()
+}
+
+/// Sorting isn't configured separately, so this follows the formatting locale.
+pub(crate) fn get_collation_locale() -> Option<String> {
+    get_format_locale()
+}
+
+pub(crate) fn get_measurement_system() -> Option<MeasurementSystem> {
+    get_measurement_system_with_options(&FormatOptions::default())
+}
+
+pub(crate) fn get_measurement_system_with_options(
+    options: &FormatOptions,
+) -> Option<MeasurementSystem> {
+    // Windows only distinguishes between metric (0) and US (1) units.
+    match get_locale_info(LOCALE_IMEASURE, options)?.as_str() {
+        "0" => Some(MeasurementSystem::Metric),
+        "1" => Some(MeasurementSystem::UsCustomary),
+        _ => None,
+    }
+}
+
+pub(crate) fn get_paper_size() -> Option<PaperSize> {
+    get_paper_size_with_options(&FormatOptions::default())
+}
+
+pub(crate) fn get_paper_size_with_options(options: &FormatOptions) -> Option<PaperSize> {
+    // Other sizes, such as US Legal (5) and A3 (8), aren't represented.
+    match get_locale_info(LOCALE_IPAPERSIZE, options)?.as_str() {
+        "1" => Some(PaperSize::Letter),
+        "9" => Some(PaperSize::A4),
+        _ => None,
+    }
+}
+
+pub(crate) fn get_number_separators() -> Option<NumberSeparators> {
+    get_number_separators_with_options(&FormatOptions::default())
+}
+
+pub(crate) fn get_number_separators_with_options(
+    options: &FormatOptions,
+) -> Option<NumberSeparators> {
+    NumberSeparators::from_strings(
+        &get_locale_info(LOCALE_SDECIMAL, options)?,
+        &get_locale_info(LOCALE_STHOUSAND, options)?,
+    )
+}
+
+pub(crate) fn get_list_separator() -> Option<char> {
+    get_list_separator_with_options(&FormatOptions::default())
+}
+
+pub(crate) fn get_list_separator_with_options(options: &FormatOptions) -> Option<char> {
+    get_locale_info(LOCALE_SLIST, options)?.chars().next()
+}
+
+pub(crate) fn get_grouping_sizes() -> Option<Vec<u8>> {
+    get_grouping_sizes_with_options(&FormatOptions::default())
+}
+
+pub(crate) fn get_grouping_sizes_with_options(options: &FormatOptions) -> Option<Vec<u8>> {
+    Some(parse_grouping(&get_locale_info(LOCALE_SGROUPING, options)?))
+}
+
+/// Converts a `LOCALE_SGROUPING` value, such as `3;2;0`, into grouping sizes.
+///
+/// Windows ends the sizes with `0` when the last one repeats, which it does in every locale, so
+/// sizes without it are treated the same.
+fn parse_grouping(value: &str) -> Vec<u8> {
+    crate::settings::grouping_sizes(value.split(';').map(|size| size.parse().unwrap_or(0)))
+}
+
+pub(crate) fn get_first_day_of_week() -> Option<Weekday> {
+    get_first_day_of_week_with_options(&FormatOptions::default())
+}
+
+pub(crate) fn get_first_day_of_week_with_options(options: &FormatOptions) -> Option<Weekday> {
+    parse_first_day_of_week(&get_locale_info(LOCALE_IFIRSTDAYOFWEEK, options)?)
+}
+
+pub(crate) fn get_weekend_days() -> Option<(Weekday, Weekday)> {
+    get_region().map(|region| Weekday::weekend_for_region(&region))
+}
+
+pub(crate) fn get_datetime_symbols() -> Option<DateTimeSymbols> {
+    get_datetime_symbols_with_options(&FormatOptions::default())
+}
+
+pub(crate) fn get_datetime_symbols_with_options(
+    options: &FormatOptions,
+) -> Option<DateTimeSymbols> {
+    datetime_symbols(|lctype| get_locale_info(lctype, options))
+}
+
+/// Reads the date and time symbols with `read`, which has the same signature as [`get_locale_info`].
+fn datetime_symbols(read: impl Fn(u32) -> Option<String>) -> Option<DateTimeSymbols> {
+    // `LOCALE_SDATE` and `LOCALE_STIME` are deprecated in favor of the full date and time
+    // formats, but they're still the separators picked in the user's regional settings.
+    DateTimeSymbols::from_strings(
+        read(LOCALE_S1159),
+        read(LOCALE_S2359),
+        read(LOCALE_SDATE),
+        read(LOCALE_STIME),
+    )
+}
+
+pub(crate) fn get_currency_code() -> Option<String> {
+    get_currency_code_with_options(&FormatOptions::default())
+}
+
+pub(crate) fn get_currency_code_with_options(options: &FormatOptions) -> Option<String> {
+    crate::currency_code(&get_locale_info(LOCALE_SINTLSYMBOL, options)?)
+}
+
+pub(crate) fn get_currency_format() -> Option<CurrencyFormat> {
+    None
+}
+
+pub(crate) fn get_percent_symbol() -> Option<PercentSymbol> {
+    get_percent_symbol_with_options(&FormatOptions::default())
+}
+
+pub(crate) fn get_percent_symbol_with_options(options: &FormatOptions) -> Option<PercentSymbol> {
+    // The positive percentage patterns are `# %`, `#%`, `%#`, and `% #`, in that order.
+    let space_separated = match get_locale_info(LOCALE_IPOSITIVEPERCENT, options).as_deref() {
+        Some("0") | Some("3") => Some(true),
+        Some("1") | Some("2") => Some(false),
+        _ => None,
+    };
+
+    PercentSymbol::from_strings(
+        &get_locale_info(LOCALE_SPERCENT, options)?,
+        &get_locale_info(LOCALE_SPERMILLE, options).unwrap_or_default(),
+        space_separated,
+    )
+}
+
+pub(crate) fn get_sign_symbols() -> Option<SignSymbols> {
+    get_sign_symbols_with_options(&FormatOptions::default())
+}
+
+pub(crate) fn get_sign_symbols_with_options(options: &FormatOptions) -> Option<SignSymbols> {
+    SignSymbols::from_strings(
+        &get_locale_info(LOCALE_SPOSITIVESIGN, options).unwrap_or_default(),
+        &get_locale_info(LOCALE_SNEGATIVESIGN, options)?,
+    )
+}
+
+#[cfg(feature = "time-zone")]
+pub(crate) fn get_time_zone() -> Option<String> {
+    use windows_sys::Win32::System::Time::{
+        GetDynamicTimeZoneInformation, DYNAMIC_TIME_ZONE_INFORMATION, TIME_ZONE_ID_INVALID,
+    };
+
+    // SAFETY: The structure only contains integers and arrays of them, so all zeroes is valid.
+    let mut info: DYNAMIC_TIME_ZONE_INFORMATION = unsafe { core::mem::zeroed() };
+    // SAFETY: `info` is valid to write to.
+    if unsafe { GetDynamicTimeZoneInformation(&mut info) } == TIME_ZONE_ID_INVALID {
+        return None;
+    }
+
+    let key = &info.TimeZoneKeyName;
+    let len = key.iter().position(|&c| c == 0).unwrap_or(key.len());
+    let name = String::from_utf16(&key[..len]).ok()?;
+    time_zone::iana_time_zone(&name).map(String::from)
+}
+
+pub(crate) fn get_locale_display_name() -> Option<String> {
+    None
+}
+
+#[allow(clippy::as_conversions)]
+pub(crate) fn get_available_locales() -> Vec<String> {
+    let mut locales = Vec::<String>::new();
+    // SAFETY: The callback is only called during this call, and `lparam` is a pointer to
+    // `locales`, which outlives it and isn't otherwise accessed until it returns.
+    unsafe {
+        EnumSystemLocalesEx(
+            Some(push_locale_name),
+            LOCALE_WINDOWS | LOCALE_SUPPLEMENTAL,
+            &mut locales as *mut Vec<String> as LPARAM,
+            core::ptr::null(),
+        )
+    };
+    locales
+}
+
+/// Adds a locale name enumerated by `EnumSystemLocalesEx` to the `Vec<String>` which `lparam`
+/// points to, skipping the invariant locale, whose name is empty.
+///
+/// # Safety
+///
+/// `name` must be a null-terminated string, and `lparam` must be a pointer to a `Vec<String>`
+/// which isn't otherwise accessed.
+#[allow(clippy::as_conversions)]
+unsafe extern "system" fn push_locale_name(name: PCWSTR, _flags: u32, lparam: LPARAM) -> BOOL {
+    let locales = &mut *(lparam as *mut Vec<String>);
+    let length = (0..LOCALE_NAME_MAX_LENGTH)
+        .take_while(|&idx| *name.add(idx) != 0)
+        .count();
+    match String::from_utf16(core::slice::from_raw_parts(name, length)) {
+        Ok(name) if !name.is_empty() => locales.push(name),
+        _ => {}
+    }
+    TRUE
+}
+
+pub(crate) fn is_locale_available(tag: &str) -> Option<bool> {
+    if tag.contains('\0') {
+        return Some(false);
+    }
+    let name = tag.encode_utf16().chain(Some(0)).collect::<Vec<u16>>();
+
+    // SAFETY: `name` is a null-terminated string.
+    Some(unsafe { IsValidLocaleName(name.as_ptr()) } != 0)
+}
+
+/// Windows only provides the name of a locale in the user's UI language, in English, or in the
+/// language of the locale itself, so in other languages it isn't available.
+pub(crate) fn display_name_of(tag: &str, in_locale: Option<&str>) -> Option<String> {
+    let lctype = match in_locale {
+        None => LOCALE_SLOCALIZEDDISPLAYNAME,
+        Some(in_locale) if same_language(in_locale, tag) => LOCALE_SNATIVEDISPLAYNAME,
+        Some(in_locale) if same_language(in_locale, "en") => LOCALE_SENGLISHDISPLAYNAME,
+        Some(in_locale) => match get().next() {
+            Some(ui_language) if same_language(in_locale, &ui_language) => {
+                LOCALE_SLOCALIZEDDISPLAYNAME
+            }
+            _ => return None,
+        },
+    };
+    get_locale_info_for(Some(tag), lctype)
+}
+
+pub(crate) fn native_name_of(tag: &str) -> Option<String> {
+    get_locale_info_for(Some(tag), LOCALE_SNATIVELANGUAGENAME)
+}
+
+/// Checks whether two language tags have the same language subtag, such as `fr-CA` and `fr`.
+fn same_language(a: &str, b: &str) -> bool {
+    let language = |tag: &str| {
+        tag.split('-')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase()
+    };
+    language(a) == language(b)
+}
+
+pub(crate) fn get_date_field_order() -> Option<DateOrder> {
+    get_date_field_order_with_options(&FormatOptions::default())
+}
+
+pub(crate) fn get_date_field_order_with_options(options: &FormatOptions) -> Option<DateOrder> {
+    crate::settings::pattern_date_order(&get_locale_info(LOCALE_SSHORTDATE, options)?).or_else(
+        || {
+            // The deprecated `LOCALE_IDATE` isn't updated when the user customizes their short date
+            // format, so it's only used if the format is ambiguous.
+            match get_locale_info(LOCALE_IDATE, options)?.as_str() {
+                "0" => Some(DateOrder::Mdy),
+                "1" => Some(DateOrder::Dmy),
+                "2" => Some(DateOrder::Ymd),
+                _ => None,
+            }
+        },
+    )
+}
+
+pub(crate) fn get_calendar() -> Option<Calendar> {
+    get_calendar_with_options(&FormatOptions::default())
+}
+
+pub(crate) fn get_calendar_with_options(options: &FormatOptions) -> Option<Calendar> {
+    parse_calendar_type(&get_locale_info(LOCALE_ICALENDARTYPE, options)?)
+}
+
+pub(crate) fn get_numbering_system() -> Option<String> {
+    get_numbering_system_with_options(&FormatOptions::default())
+}
+
+pub(crate) fn get_numbering_system_with_options(options: &FormatOptions) -> Option<String> {
+    let substitution = get_locale_info(LOCALE_IDIGITSUBSTITUTION, options)?;
+    let native_digits = get_locale_info(LOCALE_SNATIVEDIGITS, options)?;
+    match numbering_system(&substitution, &native_digits) {
+        Some(system) => Some(String::from(system)),
+        None => crate::numbering_system_from_tag(&get_user_locale()?),
+    }
+}
+
+/// Converts the `LOCALE_IDIGITSUBSTITUTION` and `LOCALE_SNATIVEDIGITS` values into a numbering
+/// system.
+///
+/// The native digits are only shown if they're always substituted for Latin digits, which is
+/// `2`. `0` substitutes them depending on the surrounding text, so it's left to the locale's
+/// default, and `None` is returned.
+fn numbering_system(substitution: &str, native_digits: &str) -> Option<&'static str> {
+    match substitution {
+        "1" => Some("latn"),
+        "2" => crate::settings::numbering_system_for_zero(native_digits.chars().next()?),
+        _ => None,
+    }
+}
+
+pub(crate) fn get_locale_settings() -> LocaleSettings {
+    get_locale_settings_with_options(&FormatOptions::default())
+}
+
+pub(crate) fn get_locale_settings_with_options(options: &FormatOptions) -> LocaleSettings {
+    LocaleSettings {
+        region: get_region(),
+        measurement_system: get_measurement_system_with_options(options),
+        currency_code: get_currency_code_with_options(options),
+        first_day_of_week: get_first_day_of_week_with_options(options),
+        uses_24_hour_time: uses_24_hour_time_with_options(options),
+        number_separators: get_number_separators_with_options(options),
+        ..LocaleSettings::default()
+    }
+}
+
+/// Converts a `LOCALE_ICALENDARTYPE` value, which is one of the `CAL_*` identifiers, into a
+/// calendar.
+fn parse_calendar_type(value: &str) -> Option<Calendar> {
+    match value.parse::<u32>().ok()? {
+        // These are all the Gregorian calendar, with the names of the months written in
+        // different languages.
+        CAL_GREGORIAN
+        | CAL_GREGORIAN_US
+        | CAL_GREGORIAN_ME_FRENCH
+        | CAL_GREGORIAN_ARABIC
+        | CAL_GREGORIAN_XLIT_ENGLISH
+        | CAL_GREGORIAN_XLIT_FRENCH => Some(Calendar::Gregorian),
+        CAL_JAPAN => Some(Calendar::Japanese),
+        CAL_TAIWAN => Some(Calendar::RepublicOfChina),
+        CAL_HIJRI | CAL_UMALQURA => Some(Calendar::Islamic),
+        CAL_THAI => Some(Calendar::Buddhist),
+        CAL_HEBREW => Some(Calendar::Hebrew),
+        CAL_PERSIAN => Some(Calendar::Persian),
+        // Such as the Korean Tangun era calendar.
+        _ => None,
+    }
+}
+
+pub(crate) fn uses_24_hour_time() -> Option<bool> {
+    uses_24_hour_time_with_options(&FormatOptions::default())
+}
+
+pub(crate) fn uses_24_hour_time_with_options(options: &FormatOptions) -> Option<bool> {
+    // This is used instead of the deprecated `LOCALE_ITIME`, which isn't updated when
+    // the user customizes their time format.
+    crate::settings::pattern_uses_24_hour_time(&get_locale_info(LOCALE_STIMEFORMAT, options)?)
+}
+
+/// Converts a `LOCALE_IFIRSTDAYOFWEEK` value into a weekday.
+///
+/// Windows counts the days from Monday as `0` to Sunday as `6`.
+fn parse_first_day_of_week(value: &str) -> Option<Weekday> {
+    value
+        .parse::<u32>()
+        .ok()
+        .filter(|days| *days < 7)
+        .map(Weekday::from_monday_offset)
+}
+
+/// Reads a piece of information about the user's default locale, such as `LOCALE_IMEASURE`,
+/// including the user's customizations unless `options` turns them off.
+fn get_locale_info(lctype: u32, options: &FormatOptions) -> Option<String> {
+    get_locale_info_for(None, with_user_overrides(lctype, options.user_overrides))
+}
+
+/// Adds `LOCALE_NOUSEROVERRIDE` to `lctype` if the user's customizations shouldn't be read, so
+/// that the locale's own value is returned instead.
+fn with_user_overrides(lctype: u32, user_overrides: bool) -> u32 {
+    if user_overrides {
+        lctype
+    } else {
+        lctype | LOCALE_NOUSEROVERRIDE
+    }
+}
+
+/// Reads a piece of information about the locale named `name`, such as `fr-CA`, or about the
+/// user's default locale if it's `None`.
+#[allow(clippy::as_conversions)]
+fn get_locale_info_for(name: Option<&str>, lctype: u32) -> Option<String> {
+    let name = match name {
+        Some(name) if name.contains('\0') => return None,
+        Some(name) => Some(name.encode_utf16().chain(Some(0)).collect::<Vec<u16>>()),
+        None => None,
+    };
+    // Passing a null locale name reads the user's default locale.
+    let name = name
+        .as_ref()
+        .map_or(core::ptr::null(), |name| name.as_ptr());
+
+    // SAFETY: `name` is either null or a null-terminated string, and calling this
+    // with null buffer will retrieve the required buffer length.
+    let buffer_length = unsafe { GetLocaleInfoEx(name, lctype, core::ptr::null_mut(), 0) };
+    if buffer_length <= 0 {
+        return None;
+    }
+
+    let mut buffer = vec![0u16; buffer_length as usize];
+    // SAFETY: The buffer is writable and its length is correctly passed.
+    let written = unsafe { GetLocaleInfoEx(name, lctype, buffer.as_mut_ptr(), buffer_length) };
+    if written <= 0 {
+        return None;
+    }
+
+    // The written length includes the null terminator.
+    buffer.truncate(written as usize - 1);
+    String::from_utf16(&buffer).ok()
+}
+
+/// Reads a locale name from a function which writes it into a buffer and returns
+/// the number of chars written, including the null terminator.
+#[allow(clippy::as_conversions)]
+fn read_locale_name(read: impl FnOnce(*mut u16, i32) -> i32) -> Option<String> {
+    let mut buffer = [0u16; LOCALE_NAME_MAX_LENGTH];
+    let written = read(buffer.as_mut_ptr(), buffer.len() as i32);
+    if written <= 1 {
+        return None;
+    }
+
+    String::from_utf16(&buffer[..written as usize - 1]).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        datetime_symbols, get_format_locale, get_system_locale, get_user_locale, numbering_system,
+        parse_calendar_type, parse_first_day_of_week, parse_grouping, read_locale_name,
+        read_multi_string, try_get_for_token, with_user_overrides, FormatOptions,
+    };
+    use crate::{Calendar, DateTimeSymbols, LocaleError, Weekday};
+    use alloc::{string::String, vec::Vec};
+    use windows_sys::Win32::Foundation::{SetLastError, ERROR_ACCESS_DENIED, FALSE, TRUE};
+    use windows_sys::Win32::Globalization::{GetSystemDefaultLocaleName, GetUserDefaultLocaleName};
+
+    #[test]
+    fn available_locales() {
+        use super::is_locale_available;
+
+        assert_eq!(is_locale_available("en-US"), Some(true));
+        assert_eq!(is_locale_available("not a locale"), Some(false));
+        assert_eq!(is_locale_available("en\0US"), Some(false));
+    }
+
+    #[test]
+    fn available_locale_list() {
+        let locales = super::get_available_locales();
+        assert!(locales.iter().any(|locale| locale == "en-US"));
+        assert!(locales.iter().all(|locale| !locale.is_empty()));
+    }
+
+    #[test]
+    fn lcid_names() {
+        use super::lcid_to_bcp47;
+
+        assert_eq!(lcid_to_bcp47(0x0409).as_deref(), Some("en-US"));
+        assert_eq!(lcid_to_bcp47(0x0411).as_deref(), Some("ja-JP"));
+        assert_eq!(lcid_to_bcp47(0x0c0a).as_deref(), Some("es-ES"));
+        assert_eq!(lcid_to_bcp47(0x0009).as_deref(), Some("en"));
+        assert_eq!(lcid_to_bcp47(0xffff_ffff), None);
+    }
+
+    #[test]
+    fn display_names() {
+        use super::display_name_of;
+
+        assert_eq!(
+            display_name_of("fr-CA", Some("fr")).as_deref(),
+            Some("français (Canada)")
+        );
+        assert_eq!(
+            display_name_of("fr-CA", Some("en-US")).as_deref(),
+            Some("French (Canada)")
+        );
+        assert!(!display_name_of("de-DE", None).unwrap().is_empty());
+        assert_eq!(display_name_of("not a locale", None), None);
+    }
+
+    #[test]
+    fn native_names() {
+        use super::native_name_of;
+
+        assert_eq!(native_name_of("de-CH").as_deref(), Some("Deutsch"));
+        assert!(!native_name_of("de").unwrap().is_empty());
+        assert!(!native_name_of("ja").unwrap().is_empty());
+        assert_eq!(native_name_of("not a locale"), None);
+    }
+
+    #[test]
+    fn numbering_systems() {
+        assert_eq!(numbering_system("1", "0123456789"), Some("latn"));
+        assert_eq!(
+            numbering_system(
+                "2",
+                "\u{0660}\u{0661}\u{0662}\u{0663}\u{0664}\u{0665}\u{0666}\u{0667}\u{0668}\u{0669}"
+            ),
+            Some("arab")
+        );
+        assert_eq!(numbering_system("1", "\u{0660}"), Some("latn"));
+        assert_eq!(numbering_system("0", "\u{0660}"), None);
+        assert_eq!(numbering_system("2", ""), None);
+    }
+
+    #[test]
+    fn calendar_types() {
+        assert_eq!(parse_calendar_type("1"), Some(Calendar::Gregorian));
+        assert_eq!(parse_calendar_type("7"), Some(Calendar::Buddhist));
+        assert_eq!(parse_calendar_type("23"), Some(Calendar::Islamic));
+        assert_eq!(parse_calendar_type("5"), None);
+        assert_eq!(parse_calendar_type(""), None);
+    }
+
+    #[test]
+    fn first_day_of_week_counts_from_monday() {
+        assert_eq!(parse_first_day_of_week("0"), Some(Weekday::Monday));
+        assert_eq!(parse_first_day_of_week("5"), Some(Weekday::Saturday));
+        assert_eq!(parse_first_day_of_week("6"), Some(Weekday::Sunday));
+        assert_eq!(parse_first_day_of_week("7"), None);
+        assert_eq!(parse_first_day_of_week(""), None);
+    }
+
+    #[test]
+    fn user_overrides_flag() {
+        use super::get_locale_info_for;
+        use windows_sys::Win32::Globalization::{LOCALE_NOUSEROVERRIDE, LOCALE_SDECIMAL};
+
+        assert!(FormatOptions::default().user_overrides);
+        assert_eq!(with_user_overrides(LOCALE_SDECIMAL, true), LOCALE_SDECIMAL);
+        assert_eq!(
+            with_user_overrides(LOCALE_SDECIMAL, false),
+            LOCALE_SDECIMAL | LOCALE_NOUSEROVERRIDE
+        );
+
+        // Both the customized and the locale's own value can be read.
+        for &user_overrides in &[true, false] {
+            let lctype = with_user_overrides(LOCALE_SDECIMAL, user_overrides);
+            assert!(get_locale_info_for(None, lctype).is_some());
+        }
+    }
+
+    #[test]
+    fn grouping_sizes() {
+        // en-IN
+        assert_eq!(parse_grouping("3;2;0"), [3, 2]);
+        // en-US
+        assert_eq!(parse_grouping("3;0"), [3]);
+        assert_eq!(parse_grouping("3"), [3]);
+        assert!(parse_grouping("0").is_empty());
+        assert!(parse_grouping("").is_empty());
+    }
+
+    #[test]
+    fn datetime_symbols_trimmed() {
+        use windows_sys::Win32::Globalization::{
+            LOCALE_S1159, LOCALE_S2359, LOCALE_SDATE, LOCALE_STIME,
+        };
+
+        let symbols = datetime_symbols(|lctype| {
+            let value = match lctype {
+                LOCALE_S1159 => "AM ",
+                LOCALE_S2359 => " PM",
+                LOCALE_SDATE => "/",
+                LOCALE_STIME => "",
+                _ => return None,
+            };
+            Some(String::from(value))
+        });
+        assert_eq!(
+            symbols,
+            Some(DateTimeSymbols {
+                am: Some(String::from("AM")),
+                pm: Some(String::from("PM")),
+                date_separator: Some(String::from("/")),
+                time_separator: None,
+            })
+        );
+
+        assert_eq!(datetime_symbols(|_| None), None);
+    }
+
+    #[test]
+    fn token_failure_is_platform_error() {
+        // SAFETY: A null handle is never a valid token, so it's rejected before impersonating.
+        let result = unsafe { try_get_for_token(0) };
+        assert_eq!(result.err(), Some(LocaleError::PlatformError { code: 6 }));
+    }
+
+    #[test]
+    fn multi_string_failure_is_platform_error() {
+        let result = read_multi_string(|_, _, _| {
+            // SAFETY: This function is safe to call and has no invariants.
+            unsafe { SetLastError(ERROR_ACCESS_DENIED) };
+            FALSE
+        });
+        assert_eq!(result.err(), Some(LocaleError::PlatformError { code: 5 }));
+    }
+
+    #[test]
+    fn multi_string_skips_invalid_utf16() {
+        use super::MultiStringIter;
+
+        let mut names: Vec<u16> = "en-US\0".encode_utf16().collect();
+        // An unpaired high surrogate, followed by an unpaired low surrogate.
+        names.extend_from_slice(&[0xD800, u16::from(b'x'), 0]);
+        names.extend_from_slice(&[u16::from(b'x'), 0xDC00, 0]);
+        names.extend("fr-FR\0\0".encode_utf16());
+
+        let locales = MultiStringIter::new(names).collect::<Vec<_>>();
+        assert_eq!(locales, ["en-US", "fr-FR"]);
+    }
+
+    #[test]
+    #[allow(clippy::as_conversions)]
+    fn multi_string_entries() {
+        let names: Vec<u16> = "en-US\0fr-FR\0\0".encode_utf16().collect();
+        let result = read_multi_string(|_, buffer, buffer_length| {
+            if !buffer.is_null() {
+                // SAFETY: The buffer was allocated with the length written below.
+                unsafe { buffer.copy_from(names.as_ptr(), names.len()) };
             }
+            // SAFETY: The length is always a valid pointer.
+            unsafe { *buffer_length = names.len() as u32 };
+            TRUE
+        });
+        assert_eq!(result.unwrap().collect::<Vec<_>>(), ["en-US", "fr-FR"]);
+    }
+
+    #[test]
+    #[allow(clippy::as_conversions)]
+    fn registry_multi_string_entries() {
+        use super::read_registry_multi_string;
+        use windows_sys::Win32::Foundation::ERROR_SUCCESS;
+
+        let names: Vec<u16> = "de-DE\0en-GB\0fr\0\0".encode_utf16().collect();
+        let locales = read_registry_multi_string(|data, size| {
+            if !data.is_null() {
+                // SAFETY: The buffer was allocated with the size written below.
+                unsafe { data.cast::<u16>().copy_from(names.as_ptr(), names.len()) };
+            }
+            // SAFETY: The size is always a valid pointer.
+            unsafe { *size = (names.len() * 2) as u32 };
+            ERROR_SUCCESS
+        });
+        assert_eq!(locales.collect::<Vec<_>>(), ["de-DE", "en-GB", "fr"]);
+    }
+
+    #[test]
+    fn registry_missing_value_is_empty() {
+        use super::read_registry_multi_string;
+        use windows_sys::Win32::Foundation::ERROR_FILE_NOT_FOUND;
+
+        let locales = read_registry_multi_string(|_, _| ERROR_FILE_NOT_FOUND);
+        assert_eq!(locales.count(), 0);
+    }
+
+    #[test]
+    fn multi_string_empty() {
+        use super::MultiStringIter;
+
+        let names = |names: &str| MultiStringIter::new(names.encode_utf16().collect());
+        assert!(names("").is_empty());
+        assert!(names("\0").is_empty());
+        assert!(names("\0\0").is_empty());
+        // Anything after the terminating pair of null chars isn't a name.
+        assert!(names("\0\0en-US\0\0").is_empty());
+        assert!(!names("en-US\0\0").is_empty());
+        assert!(!names("\0de-DE\0\0").is_empty());
+
+        let mut iter = names("en-US\0\0");
+        iter.next();
+        assert!(iter.is_empty());
+        assert_eq!(names("\0\0en-US\0\0").count(), 0);
+    }
+
+    #[test]
+    fn registry_fallback_only_when_needed() {
+        use super::{with_registry_fallback, MultiStringIter};
+
+        let names = |names: &str| MultiStringIter::new(names.encode_utf16().collect());
+        let error = LocaleError::PlatformError { code: 5 };
+
+        let result = with_registry_fallback(Ok(names("en-US\0\0")), || unreachable!());
+        assert_eq!(result.unwrap().collect::<Vec<_>>(), ["en-US"]);
+
+        let result = with_registry_fallback(Ok(names("\0")), || names("de-DE\0\0"));
+        assert_eq!(result.unwrap().collect::<Vec<_>>(), ["de-DE"]);
+
+        let result = with_registry_fallback(Err(error), || names("de-DE\0fr\0\0"));
+        assert_eq!(result.unwrap().collect::<Vec<_>>(), ["de-DE", "fr"]);
+
+        let result = with_registry_fallback(Err(error), || names(""));
+        assert_eq!(result.err(), Some(error));
+    }
+
+    #[test]
+    fn preferred_languages_are_names() {
+        // LCIDs such as `0409` aren't well-formed language tags, since languages are alphabetic.
+        for language in super::preferred_ui_languages().unwrap() {
+            assert!(
+                crate::is_well_formed_bcp47(&language),
+                "{} isn't a language name",
+                language
+            );
         }
+        assert!(!crate::is_well_formed_bcp47("0409"));
     }
 
-    result.into_iter()
+    #[test]
+    fn format_locale_is_user_default_locale_name() {
+        // SAFETY: The buffer is writable and its length is correctly passed.
+        let expected =
+            read_locale_name(|buffer, len| unsafe { GetUserDefaultLocaleName(buffer, len) });
+        assert_eq!(get_format_locale(), expected);
+    }
+
+    #[test]
+    fn user_locale_is_user_default_locale_name() {
+        // SAFETY: The buffer is writable and its length is correctly passed.
+        let expected =
+            read_locale_name(|buffer, len| unsafe { GetUserDefaultLocaleName(buffer, len) });
+        assert_eq!(get_user_locale(), expected);
+    }
+
+    #[test]
+    fn system_locale_is_system_default_locale_name() {
+        // SAFETY: The buffer is writable and its length is correctly passed.
+        let expected =
+            read_locale_name(|buffer, len| unsafe { GetSystemDefaultLocaleName(buffer, len) });
+        assert_eq!(get_system_locale(), expected);
+    }
 }
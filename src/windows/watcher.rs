@@ -0,0 +1,220 @@
+//! Watches for changes to the user's regional settings, which Windows broadcasts to every
+//! top-level window as `WM_SETTINGCHANGE` with the `"intl"` section name.
+use alloc::{boxed::Box, vec::Vec};
+use std::cell::RefCell;
+use std::sync::mpsc;
+use std::thread::{self, JoinHandle};
+use windows_sys::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+use windows_sys::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows_sys::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW, PostMessageW, PostQuitMessage,
+    RegisterClassW, TranslateMessage, MSG, WM_CLOSE, WM_DESTROY, WM_SETTINGCHANGE, WNDCLASSW,
+};
+
+type Callback = Box<dyn Fn() + Send + Sync>;
+
+/// The section name sent with `WM_SETTINGCHANGE` when the regional settings change,
+/// including the null terminator.
+#[allow(clippy::as_conversions)]
+const INTL: [u16; 5] = [b'i' as u16, b'n' as u16, b't' as u16, b'l' as u16, 0];
+
+thread_local! {
+    /// The callback of the watcher which owns the window on this thread.
+    static CALLBACK: RefCell<Option<Callback>> = RefCell::new(None);
+}
+
+/// A hidden window, owned by a dedicated thread that pumps its messages.
+///
+/// Dropping this destroys the window and waits for the thread to exit.
+pub(crate) struct Watcher {
+    window: HWND,
+    thread: Option<JoinHandle<()>>,
+}
+
+pub(crate) fn watch(callback: Callback) -> Option<Watcher> {
+    let (sender, receiver) = mpsc::channel();
+
+    let thread = thread::Builder::new()
+        .name("sys-locale watcher".into())
+        .spawn(move || {
+            CALLBACK.with(|cell| *cell.borrow_mut() = Some(callback));
+
+            let window = create_window();
+            let _ = sender.send(window);
+            if window != 0 {
+                pump_messages();
+            }
+        })
+        .ok()?;
+
+    match receiver.recv() {
+        Ok(window) if window != 0 => Some(Watcher {
+            window,
+            thread: Some(thread),
+        }),
+        _ => {
+            let _ = thread.join();
+            None
+        }
+    }
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        // Closing the window quits the message loop, which ends the thread.
+        // SAFETY: The window handle is valid until the thread has exited.
+        unsafe { PostMessageW(self.window, WM_CLOSE, 0, 0) };
+
+        if let Some(thread) = self.thread.take() {
+            // The watcher may be dropped by its own callback, in which case the
+            // thread will exit once the callback returns.
+            if thread.thread().id() != thread::current().id() {
+                let _ = thread.join();
+            }
+        }
+    }
+}
+
+/// Creates a hidden top-level window to receive `WM_SETTINGCHANGE` on the current thread,
+/// returning 0 if it couldn't be created.
+///
+/// This can't be a message-only window (`HWND_MESSAGE`), as those don't receive broadcasts.
+fn create_window() -> HWND {
+    let class_name: Vec<u16> = "sys-locale-watcher"
+        .encode_utf16()
+        .chain(core::iter::once(0))
+        .collect();
+
+    // SAFETY: A null module name returns the handle of the current executable.
+    let instance = unsafe { GetModuleHandleW(core::ptr::null()) };
+
+    let class = WNDCLASSW {
+        style: 0,
+        lpfnWndProc: Some(window_proc),
+        cbClsExtra: 0,
+        cbWndExtra: 0,
+        hInstance: instance,
+        hIcon: 0,
+        hCursor: 0,
+        hbrBackground: 0,
+        lpszMenuName: core::ptr::null(),
+        lpszClassName: class_name.as_ptr(),
+    };
+
+    // SAFETY: The class is fully initialized and its name is null-terminated. Registering
+    // fails if an earlier watcher already registered the class, which can be ignored as
+    // it's the same class.
+    unsafe { RegisterClassW(&class) };
+
+    // SAFETY: The class name is null-terminated, and the window is never shown.
+    unsafe {
+        CreateWindowExW(
+            0,
+            class_name.as_ptr(),
+            core::ptr::null(),
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            instance,
+            core::ptr::null(),
+        )
+    }
+}
+
+/// Dispatches messages for the current thread's windows until `WM_QUIT` is received.
+fn pump_messages() {
+    // SAFETY: `MSG` is a plain C struct, for which all zeroes is a valid value.
+    let mut message: MSG = unsafe { core::mem::zeroed() };
+
+    // SAFETY: `message` is a valid, writable `MSG`. This returns 0 for `WM_QUIT`
+    // and -1 on errors, both of which end the loop.
+    while unsafe { GetMessageW(&mut message, 0, 0, 0) } > 0 {
+        // SAFETY: `message` was filled in by `GetMessageW`.
+        unsafe {
+            TranslateMessage(&message);
+            DispatchMessageW(&message);
+        }
+    }
+}
+
+unsafe extern "system" fn window_proc(
+    window: HWND,
+    message: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    match message {
+        // SAFETY: The `lParam` of `WM_SETTINGCHANGE` is either null or a section name.
+        WM_SETTINGCHANGE if unsafe { is_intl_change(lparam) } => {
+            CALLBACK.with(|cell| {
+                if let Some(callback) = &*cell.borrow() {
+                    callback();
+                }
+            });
+            0
+        }
+        WM_DESTROY => {
+            // SAFETY: This function is safe to call and has no invariants.
+            unsafe { PostQuitMessage(0) };
+            0
+        }
+        // SAFETY: The arguments are passed through unchanged.
+        _ => unsafe { DefWindowProcW(window, message, wparam, lparam) },
+    }
+}
+
+/// Checks if the `lParam` of a `WM_SETTINGCHANGE` message is the `"intl"` section name,
+/// rather than that of an unrelated setting.
+///
+/// # Safety
+///
+/// `lparam` must be null or point to a null-terminated UTF-16 string.
+#[allow(clippy::as_conversions)]
+unsafe fn is_intl_change(lparam: LPARAM) -> bool {
+    let name = lparam as *const u16;
+    if name.is_null() {
+        return false;
+    }
+
+    // Comparing stops at the first mismatch, so this never reads past the end of `name`.
+    INTL.iter()
+        .enumerate()
+        // SAFETY: Every char up to and including the terminator is readable.
+        .all(|(i, expected)| unsafe { *name.add(i) } == *expected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_intl_change, watch};
+    use alloc::{boxed::Box, vec::Vec};
+
+    fn section(name: &str) -> Vec<u16> {
+        name.encode_utf16().chain(core::iter::once(0)).collect()
+    }
+
+    #[test]
+    #[allow(clippy::as_conversions)]
+    fn intl_change_only() {
+        let check = |name: &str| unsafe { is_intl_change(section(name).as_ptr() as isize) };
+
+        assert!(check("intl"));
+        assert!(!check("int"));
+        assert!(!check("intlx"));
+        assert!(!check("Policy"));
+        assert!(!check("ImmersiveColorSet"));
+        assert!(!unsafe { is_intl_change(0) });
+    }
+
+    #[test]
+    fn watcher_shuts_down() {
+        let watcher = watch(Box::new(|| {})).expect("the watcher should be created");
+        drop(watcher);
+
+        // The window class can be reused by later watchers.
+        assert!(watch(Box::new(|| {})).is_some());
+    }
+}
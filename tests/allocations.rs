@@ -0,0 +1,187 @@
+//! Counts the allocations made while obtaining the locales from a provider, to check that the
+//! ways of reading them which are meant to allocate less really do.
+//!
+//! Nothing is logged, so these counts also hold with the `log` and `tracing` features.
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::sync::Mutex;
+use sys_locale::{clear_provider, set_provider, LocaleProvider};
+
+struct CountingAllocator;
+
+std::thread_local! {
+    // Only the current thread's allocations are counted, so tests running in parallel don't
+    // affect each other.
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn count_allocations<T>(f: impl FnOnce() -> T) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    drop(f());
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+/// Provides locales which are already in their canonical casing, so that none of them are
+/// changed or skipped, unless stated otherwise.
+struct Locales(&'static [&'static str]);
+
+impl LocaleProvider for Locales {
+    fn get(&self) -> Vec<String> {
+        self.0.iter().map(|tag| String::from(*tag)).collect()
+    }
+
+    fn get_cow(&self) -> Vec<Cow<'static, str>> {
+        self.0.iter().map(|tag| Cow::Borrowed(*tag)).collect()
+    }
+}
+
+static THREE_LOCALES: &dyn LocaleProvider = &Locales(&["en-US", "fr-FR", "de-DE"]);
+static TWO_LOCALES: &dyn LocaleProvider = &Locales(&["en-US", "fr-FR"]);
+/// The second locale isn't in its canonical casing.
+static UNCANONICAL_LOCALES: &dyn LocaleProvider = &Locales(&["en-US", "fr-fr", "de-DE"]);
+
+/// The provider is shared by every thread, so only one test can install one at a time.
+static PROVIDER: Mutex<()> = Mutex::new(());
+
+fn with_provider<T>(provider: &'static &'static dyn LocaleProvider, f: impl FnOnce() -> T) -> T {
+    let _lock = PROVIDER.lock().unwrap_or_else(|err| err.into_inner());
+    set_provider(provider);
+    let value = f();
+    clear_provider();
+    value
+}
+
+/// The allocations made while canonicalizing a locale which isn't in its canonical casing,
+/// which parses it into its subtags and joins them back together. Those which already are
+/// canonical are returned as they are.
+const PER_UNCANONICAL_LOCALE: usize = 4;
+
+#[test]
+fn provider_allocations() {
+    // The `Vec` and each of its `String`s.
+    assert_eq!(count_allocations(|| THREE_LOCALES.get()), 4);
+    assert_eq!(count_allocations(|| TWO_LOCALES.get()), 3);
+    // Only the `Vec`, since the locales are borrowed.
+    assert_eq!(count_allocations(|| THREE_LOCALES.get_cow()), 1);
+    // Installing a provider doesn't allocate, so it can be swapped as often as needed.
+    assert_eq!(
+        count_allocations(|| with_provider(&THREE_LOCALES, || ())),
+        0
+    );
+}
+
+#[test]
+fn first_locale_allocates_less_than_collecting() {
+    let (first, all) = with_provider(&THREE_LOCALES, || {
+        let first = count_allocations(sys_locale::get_locale);
+        assert_eq!(
+            count_allocations(|| sys_locale::get_locales_iter().next()),
+            first
+        );
+        let all = count_allocations(|| sys_locale::get_locales().collect::<Vec<_>>());
+        (first, all)
+    });
+
+    // Only the provider's locales are allocated, since the hash of the first locale isn't
+    // added to the list of those which were already returned until another one is read.
+    assert_eq!(first, 4);
+    // The list of the hashes of the locales which were returned and the collected `Vec` are
+    // both allocated once.
+    assert_eq!(all, 4 + 1 + 1);
+    assert!(first < all);
+}
+
+#[test]
+fn uncanonical_locales_are_canonicalized() {
+    let all = with_provider(&UNCANONICAL_LOCALES, || {
+        count_allocations(|| sys_locale::get_locales().collect::<Vec<_>>())
+    });
+
+    // Only the locale which isn't canonical is parsed and joined back together.
+    assert_eq!(all, 4 + PER_UNCANONICAL_LOCALE + 1 + 1);
+}
+
+#[test]
+fn borrowed_locales_allocate_less_than_collecting() {
+    let (borrowed, all) = with_provider(&THREE_LOCALES, || {
+        let borrowed = count_allocations(sys_locale::get_locales_cow);
+        let all = count_allocations(|| sys_locale::get_locales().collect::<Vec<_>>());
+        (borrowed, all)
+    });
+
+    // The locales are already canonical, so they're all borrowed, and they're checked for
+    // duplicates within the returned `Vec`, which is the only allocation.
+    assert_eq!(borrowed, 1);
+    assert_eq!(all, 4 + 1 + 1);
+}
+
+#[test]
+fn borrowed_locales_copy_uncanonical() {
+    let borrowed = with_provider(&UNCANONICAL_LOCALES, || {
+        count_allocations(sys_locale::get_locales_cow)
+    });
+
+    // Only the locale which isn't canonical is copied so that it can be canonicalized.
+    assert_eq!(borrowed, 1 + 1 + PER_UNCANONICAL_LOCALE);
+}
+
+#[cfg(feature = "smallvec")]
+#[test]
+fn small_locales_allocate_less_than_collecting() {
+    let (small, all) = with_provider(&TWO_LOCALES, || {
+        let small = count_allocations(sys_locale::get_locales_small);
+        let all = count_allocations(|| sys_locale::get_locales().collect::<Vec<_>>());
+        (small, all)
+    });
+
+    // Two locales fit into the `SmallVec`, which is checked for duplicates itself, so only
+    // the provider's locales are allocated.
+    assert_eq!(small, 3);
+    // Collecting also allocates the list of the hashes of the locales which were returned, and
+    // the `Vec`.
+    assert_eq!(all, 3 + 1 + 1);
+}
+
+#[cfg(feature = "smallvec")]
+#[test]
+fn small_locales_spill_when_needed() {
+    let small = with_provider(&THREE_LOCALES, || {
+        count_allocations(sys_locale::get_locales_small)
+    });
+
+    // The third locale doesn't fit, so the `SmallVec` allocates once to hold all of them.
+    assert_eq!(small, 4 + 1);
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn fixed_locales_stop_when_full() {
+    // None of the locales are in their canonical casing.
+    static LOWERCASE_LOCALES: &dyn LocaleProvider = &Locales(&["en-us", "fr-fr", "de-de"]);
+
+    let (one, two) = with_provider(&LOWERCASE_LOCALES, || {
+        let one = count_allocations(sys_locale::get_locales_fixed::<1>);
+        let two = count_allocations(sys_locale::get_locales_fixed::<2>);
+        (one, two)
+    });
+
+    // Only the locales which fit into the `heapless::Vec` are canonicalized, and it doesn't
+    // allocate itself.
+    assert_eq!(one, 4 + PER_UNCANONICAL_LOCALE);
+    assert_eq!(two, 4 + 2 * PER_UNCANONICAL_LOCALE);
+}
@@ -0,0 +1,39 @@
+//! Checks that the `test-stub` feature replaces the platform's locales on every target.
+#![cfg(feature = "test-stub")]
+use std::borrow::Cow;
+use sys_locale::{
+    get_locale, get_locales, get_locales_cow, get_region, try_get_locales, PaperSize,
+};
+
+#[cfg_attr(target_family = "wasm", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_family = "wasm"), test)]
+fn stub_returns_fixed_locales() {
+    let expected = match option_env!("SYS_LOCALE_TEST_STUB") {
+        Some(locales) => locales.split(',').map(|tag| tag.trim()).collect(),
+        None => vec!["en-US"],
+    };
+
+    assert!(sys_locale::is_supported());
+    assert_eq!(get_locale().as_deref(), Some(expected[0]));
+    assert_eq!(get_locales().collect::<Vec<_>>(), expected);
+    assert_eq!(try_get_locales().unwrap(), expected);
+
+    // The locales are compiled into the crate, so they're borrowed from it.
+    let locales = get_locales_cow();
+    assert_eq!(locales, expected);
+    assert!(locales
+        .iter()
+        .all(|locale| matches!(locale, Cow::Borrowed(_))));
+}
+
+#[cfg_attr(target_family = "wasm", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_family = "wasm"), test)]
+fn stub_settings_follow_first_locale() {
+    // The default list is only used when `SYS_LOCALE_TEST_STUB` wasn't set during the build.
+    if option_env!("SYS_LOCALE_TEST_STUB").is_some() {
+        return;
+    }
+
+    assert_eq!(get_region().as_deref(), Some("US"));
+    assert_eq!(sys_locale::get_paper_size(), Some(PaperSize::Letter));
+}
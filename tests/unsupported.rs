@@ -0,0 +1,19 @@
+//! Checks that unsupported platforms can be told apart from a missing locale.
+//!
+//! On WebAssembly, these run with `wasm-pack test --node`, without the `js` or `web-sys` features.
+#![cfg(not(any(
+    feature = "test-stub",
+    unix,
+    all(target_family = "wasm", any(feature = "js", feature = "web-sys")),
+    windows
+)))]
+use sys_locale::{is_supported, try_get_locales, LocaleError, SUPPORTED};
+
+#[cfg_attr(target_family = "wasm", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_family = "wasm"), test)]
+fn platform_is_unsupported() {
+    assert!(!is_supported());
+    assert_eq!(is_supported(), SUPPORTED);
+    assert_eq!(try_get_locales(), Err(LocaleError::Unsupported));
+    assert_eq!(sys_locale::get_locales().count(), 0);
+}
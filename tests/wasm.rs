@@ -0,0 +1,24 @@
+//! Checks that the locale can be obtained outside of a web browser.
+//!
+//! These run in Node.js with `wasm-pack test --node --features js`.
+#![cfg(all(target_family = "wasm", feature = "js", not(unix)))]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[wasm_bindgen_test]
+fn can_obtain_locale() {
+    let locale = sys_locale::get_locale().expect("a locale should be available in Node.js");
+    assert!(!locale.is_empty());
+}
+
+#[wasm_bindgen_test]
+fn locales_are_well_formed() {
+    let locales = sys_locale::get_locales().collect::<Vec<_>>();
+    assert!(!locales.is_empty());
+    for locale in locales {
+        assert!(
+            sys_locale::Locale::from_tag(&locale).is_some(),
+            "{} isn't a BCP-47 language tag",
+            locale
+        );
+    }
+}
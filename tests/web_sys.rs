@@ -0,0 +1,27 @@
+//! Checks that the locale can be obtained in a web browser through `web-sys` alone.
+//!
+//! These run in a browser with `wasm-pack test --headless --firefox --features web-sys`.
+#![cfg(all(
+    target_family = "wasm",
+    feature = "web-sys",
+    not(feature = "js"),
+    not(unix)
+))]
+use sys_locale::{get_locales_with_source, LocaleSource};
+use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+fn locales_come_from_navigator() {
+    let locales = get_locales_with_source();
+    assert!(!locales.is_empty(), "the browser should provide a locale");
+    for (locale, source) in locales {
+        assert!(
+            sys_locale::Locale::from_tag(&locale).is_some(),
+            "{} isn't a BCP-47 language tag",
+            locale
+        );
+        assert_eq!(source, LocaleSource::System);
+    }
+}